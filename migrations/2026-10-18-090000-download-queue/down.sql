@@ -0,0 +1 @@
+drop table download_queue;
\ No newline at end of file
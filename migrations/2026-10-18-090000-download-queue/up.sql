@@ -0,0 +1,12 @@
+create table download_queue
+(
+    id TEXT not null,
+    payload TEXT not null,
+    target TEXT,
+    actions TEXT not null default '[]',
+    paused BOOLEAN not null default 0,
+    downloaded_size BIGINT not null default 0,
+    total_size BIGINT not null default 0,
+    constraint download_queue_pk
+        primary key (id)
+);
@@ -0,0 +1,2 @@
+alter table download_queue drop column position;
+alter table download_queue drop column priority;
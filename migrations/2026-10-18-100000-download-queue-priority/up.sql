@@ -0,0 +1,2 @@
+alter table download_queue add column priority INTEGER not null default 1;
+alter table download_queue add column position INTEGER not null default 0;
@@ -0,0 +1,17 @@
+create table download_history
+(
+    id INTEGER not null
+        constraint download_history_pk
+            primary key autoincrement,
+    item_id TEXT not null,
+    item_type TEXT not null,
+    label TEXT not null,
+    release TEXT,
+    bytes BIGINT not null default 0,
+    started_at BIGINT not null,
+    finished_at BIGINT not null,
+    average_speed BIGINT not null default 0,
+    outcome TEXT not null,
+    error TEXT,
+    targets TEXT not null default '[]'
+);
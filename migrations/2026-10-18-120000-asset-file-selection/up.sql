@@ -0,0 +1,7 @@
+create table asset_file_selection
+(
+    asset TEXT not null,
+    rules TEXT not null default '[]',
+    constraint asset_file_selection_pk
+        primary key (asset)
+);
@@ -0,0 +1,3 @@
+drop table asset_tag;
+drop table collection_asset;
+drop table collection;
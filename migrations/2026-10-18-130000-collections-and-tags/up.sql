@@ -0,0 +1,22 @@
+create table collection
+(
+    name TEXT not null,
+    constraint collection_pk
+        primary key (name)
+);
+
+create table collection_asset
+(
+    collection TEXT not null,
+    asset TEXT not null,
+    constraint collection_asset_pk
+        primary key (collection, asset)
+);
+
+create table asset_tag
+(
+    asset TEXT not null,
+    tag TEXT not null,
+    constraint asset_tag_pk
+        primary key (asset, tag)
+);
@@ -0,0 +1,11 @@
+create table saved_search
+(
+    name TEXT not null,
+    search TEXT not null default '',
+    category TEXT not null default '',
+    filter TEXT,
+    downloaded BOOLEAN not null default 0,
+    favorites BOOLEAN not null default 0,
+    constraint saved_search_pk
+        primary key (name)
+);
@@ -1,4 +1,5 @@
 use crate::config;
+use crate::ui::widgets::download_manager::notifications::Notifications;
 use crate::window::EpicAssetManagerWindow;
 use adw::subclass::prelude::*;
 use gio::ApplicationFlags;
@@ -211,6 +212,47 @@ impl EpicAssetManager {
             )
         );
 
+        // Desktop notifications of downloads
+        action!(
+            self,
+            "show-downloads",
+            clone!(
+                #[weak(rename_to=app)]
+                self,
+                move |_, _| {
+                    let window = app.main_window();
+                    window.show_download_manager();
+                    window.present();
+                }
+            )
+        );
+
+        action!(
+            self,
+            "open-download-folder",
+            Some(&String::static_variant_type()),
+            move |_, path| {
+                if let Some(path) = path.and_then(|p| p.get::<String>()) {
+                    crate::tools::open_directory(&path);
+                }
+            }
+        );
+
+        action!(
+            self,
+            "retry-download",
+            Some(&String::static_variant_type()),
+            clone!(
+                #[weak(rename_to=app)]
+                self,
+                move |_, id| {
+                    if let Some(id) = id.and_then(|i| i.get::<String>()) {
+                        app.main_window().imp().download_manager.retry_download(&id);
+                    }
+                }
+            )
+        );
+
         // Dark mode
         let is_dark_mode = self_.settings.boolean("dark-mode");
         let simple_action =
@@ -1,3 +1,4 @@
+use crate::models::collections::Labels;
 use chrono::{DateTime, Utc};
 use diesel::dsl::exists;
 use diesel::{select, ExpressionMethods, QueryDsl, RunQueryDsl};
@@ -39,6 +40,7 @@ mod imp {
         pub kind: RefCell<Option<String>>,
         pub asset: RefCell<Option<AssetInfo>>,
         thumbnail: RefCell<Option<Texture>>,
+        pub labels: RefCell<Labels>,
         pub settings: gtk4::gio::Settings,
     }
 
@@ -61,6 +63,7 @@ mod imp {
                 kind: RefCell::new(None),
                 asset: RefCell::new(None),
                 thumbnail: RefCell::new(None),
+                labels: RefCell::new(Labels::default()),
                 settings: gtk4::gio::Settings::new(crate::config::APP_ID),
             }
         }
@@ -190,6 +193,7 @@ impl AssetData {
 
         data.set_property("id", &asset.id);
         data.check_favorite();
+        data.check_labels();
         data.set_property("name", &asset.title);
         self_.asset.replace(Some(asset.clone()));
         data.check_downloaded();
@@ -306,7 +310,9 @@ impl AssetData {
     }
 
     fn has_category(&self, cat: &str) -> bool {
-        if cat.eq("favorites") {
+        if let Some(labeled) = self.imp().labels.borrow().matches(cat) {
+            labeled
+        } else if cat.eq("favorites") {
             self.favorite()
         } else if cat.eq("downloaded") {
             self.downloaded()
@@ -329,8 +335,8 @@ impl AssetData {
         }
     }
 
-    pub fn check_category(&self, cat: &str) -> bool {
-        crate::tools::category_filter::eval_category_expr(cat, &|c| self.has_category(c))
+    pub fn check_category(&self, expr: &crate::tools::category_filter::Expr) -> bool {
+        expr.eval(&|c| self.has_category(c))
     }
 
     pub fn check_downloaded(&self) {
@@ -381,8 +387,18 @@ impl AssetData {
         self.set_property("favorite", false);
     }
 
+    /// Collections and tags of the asset
+    pub fn labels(&self) -> Labels {
+        self.imp().labels.borrow().clone()
+    }
+
+    pub fn check_labels(&self) {
+        self.imp().labels.replace(Labels::load(&self.id()));
+    }
+
     pub fn refresh(&self) {
         self.check_favorite();
+        self.check_labels();
         self.check_downloaded();
         self.emit_by_name::<()>("refreshed", &[]);
     }
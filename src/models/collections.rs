@@ -0,0 +1,324 @@
+use crate::schema::{asset_tag, collection, collection_asset};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use log::error;
+
+/// Category filters matching assets in a collection, `collection:` alone matches any collection
+pub const COLLECTION_PREFIX: &str = "collection:";
+/// Category filters matching assets with a tag, `tag:` alone matches any tag
+pub const TAG_PREFIX: &str = "tag:";
+
+/// Collections and tags the user put an asset in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels {
+    pub collections: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Labels {
+    /// Whether the asset belongs to a `collection:` or `tag:` category, `None` for other categories
+    pub fn matches(&self, category: &str) -> Option<bool> {
+        if let Some(name) = category.strip_prefix(COLLECTION_PREFIX) {
+            let name = clean_name(name);
+            Some(if name.is_empty() {
+                !self.collections.is_empty()
+            } else {
+                // Names saved before operators were dropped are compared cleaned up as well
+                self.collections
+                    .iter()
+                    .any(|c| clean_name(c).eq_ignore_ascii_case(&name))
+            })
+        } else if let Some(tag) = category.strip_prefix(TAG_PREFIX) {
+            let tag = clean_tag(tag);
+            Some(if tag.is_empty() {
+                !self.tags.is_empty()
+            } else {
+                self.tags.iter().any(|t| clean_tag(t) == tag)
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn load(asset: &str) -> Self {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return Self::default();
+        };
+        let collections = collection_asset::table
+            .filter(collection_asset::asset.eq(asset))
+            .select(collection_asset::collection)
+            .order(collection_asset::collection)
+            .load::<String>(&mut conn)
+            .unwrap_or_else(|e| {
+                error!("Unable to load collections of {}: {}", asset, e);
+                Vec::new()
+            });
+        let tags = asset_tag::table
+            .filter(asset_tag::asset.eq(asset))
+            .select(asset_tag::tag)
+            .order(asset_tag::tag)
+            .load::<String>(&mut conn)
+            .unwrap_or_else(|e| {
+                error!("Unable to load tags of {}: {}", asset, e);
+                Vec::new()
+            });
+        Self { collections, tags }
+    }
+}
+
+/// Operators of category filter expressions, names cannot contain them
+const FILTER_OPERATORS: [char; 5] = ['&', '|', '!', '(', ')'];
+
+/// Words of a name, filter operators separate words
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| c.is_whitespace() || FILTER_OPERATORS.contains(&c))
+        .filter(|w| !w.is_empty())
+}
+
+/// Collection names keep their case, surrounding and repeated whitespace and filter operators
+/// are dropped, e.g. `Used in ProjectX (WIP)` becomes `Used in ProjectX WIP`
+pub fn clean_name(name: &str) -> String {
+    words(name).collect::<Vec<&str>>().join(" ")
+}
+
+/// Tags are lower case words joined by dashes, e.g. `Dungeon Kit` becomes `dungeon-kit`
+pub fn clean_tag(tag: &str) -> String {
+    words(tag)
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// Category filter of a collection
+pub fn collection_filter(name: &str) -> String {
+    format!("{COLLECTION_PREFIX}{}", clean_name(name))
+}
+
+/// Category filter of a tag
+pub fn tag_filter(tag: &str) -> String {
+    format!("{TAG_PREFIX}{}", clean_tag(tag))
+}
+
+/// Labels and category filters of the dropdown entries for collections and tags
+pub fn dropdown_entries(collections: &[String], tags: &[String]) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    for (title, prefix, names) in [
+        ("Collections", COLLECTION_PREFIX, collections),
+        ("Tags", TAG_PREFIX, tags),
+    ] {
+        if names.is_empty() {
+            continue;
+        }
+        entries.push((title.to_string(), prefix.to_string()));
+        for name in names {
+            entries.push((format!("    {name}"), format!("{prefix}{name}")));
+        }
+    }
+    entries
+}
+
+/// All collections by name
+pub fn collections() -> Vec<String> {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return Vec::new();
+    };
+    collection::table
+        .select(collection::name)
+        .order(collection::name)
+        .load::<String>(&mut conn)
+        .unwrap_or_else(|e| {
+            error!("Unable to load collections: {}", e);
+            Vec::new()
+        })
+}
+
+/// Tags used by any asset
+pub fn tags() -> Vec<String> {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return Vec::new();
+    };
+    asset_tag::table
+        .select(asset_tag::tag)
+        .distinct()
+        .order(asset_tag::tag)
+        .load::<String>(&mut conn)
+        .unwrap_or_else(|e| {
+            error!("Unable to load tags: {}", e);
+            Vec::new()
+        })
+}
+
+/// Create a collection, returns the cleaned up name or `None` if there is nothing left of it
+pub fn create_collection(name: &str) -> Option<String> {
+    let name = clean_name(name);
+    if name.is_empty() {
+        return None;
+    }
+    let db = crate::models::database::connection();
+    if let Ok(mut conn) = db.get() {
+        if let Err(e) = diesel::insert_or_ignore_into(collection::table)
+            .values(collection::name.eq(&name))
+            .execute(&mut conn)
+        {
+            error!("Unable to create collection {}: {}", name, e);
+            return None;
+        }
+    }
+    Some(name)
+}
+
+/// Delete a collection, the assets in it are kept
+pub fn delete_collection(name: &str) {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return;
+    };
+    if let Err(e) =
+        diesel::delete(collection_asset::table.filter(collection_asset::collection.eq(name)))
+            .execute(&mut conn)
+            .and_then(|_| {
+                diesel::delete(collection::table.filter(collection::name.eq(name)))
+                    .execute(&mut conn)
+            })
+    {
+        error!("Unable to delete collection {}: {}", name, e);
+    }
+}
+
+/// Add an asset to a collection or remove it
+pub fn set_member(asset: &str, name: &str, member: bool) {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return;
+    };
+    let result = if member {
+        diesel::insert_or_ignore_into(collection_asset::table)
+            .values((
+                collection_asset::collection.eq(name),
+                collection_asset::asset.eq(asset),
+            ))
+            .execute(&mut conn)
+    } else {
+        diesel::delete(
+            collection_asset::table
+                .filter(collection_asset::collection.eq(name))
+                .filter(collection_asset::asset.eq(asset)),
+        )
+        .execute(&mut conn)
+    };
+    if let Err(e) = result {
+        error!("Unable to update collection {} of {}: {}", name, asset, e);
+    }
+}
+
+/// Tag an asset, returns the cleaned up tag or `None` if there is nothing left of it
+pub fn add_tag(asset: &str, tag: &str) -> Option<String> {
+    let tag = clean_tag(tag);
+    if tag.is_empty() {
+        return None;
+    }
+    let db = crate::models::database::connection();
+    if let Ok(mut conn) = db.get() {
+        if let Err(e) = diesel::insert_or_ignore_into(asset_tag::table)
+            .values((asset_tag::asset.eq(asset), asset_tag::tag.eq(&tag)))
+            .execute(&mut conn)
+        {
+            error!("Unable to tag {} with {}: {}", asset, tag, e);
+            return None;
+        }
+    }
+    Some(tag)
+}
+
+pub fn remove_tag(asset: &str, tag: &str) {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return;
+    };
+    if let Err(e) = diesel::delete(
+        asset_tag::table
+            .filter(asset_tag::asset.eq(asset))
+            .filter(asset_tag::tag.eq(tag)),
+    )
+    .execute(&mut conn)
+    {
+        error!("Unable to remove tag {} from {}: {}", tag, asset, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> Labels {
+        Labels {
+            collections: vec!["Used in ProjectX".to_string()],
+            tags: vec!["dungeon-kit".to_string(), "props".to_string()],
+        }
+    }
+
+    #[test]
+    fn names_and_tags_are_cleaned_up() {
+        assert_eq!(clean_name("  Used   in ProjectX "), "Used in ProjectX");
+        assert_eq!(clean_tag(" Dungeon  Kit "), "dungeon-kit");
+        assert_eq!(clean_tag("   "), "");
+        assert_eq!(clean_name("Used in ProjectX (WIP)"), "Used in ProjectX WIP");
+        assert_eq!(clean_tag("R&D"), "r-d");
+    }
+
+    #[test]
+    fn filters_parse_as_one_category() {
+        use crate::tools::category_filter::Expr;
+        for (filter, category) in [
+            (
+                collection_filter("Used in ProjectX (WIP)"),
+                "collection:Used in ProjectX WIP",
+            ),
+            (collection_filter("R&D | !old"), "collection:R D old"),
+            (tag_filter("R&D"), "tag:r-d"),
+        ] {
+            assert_eq!(
+                Expr::parse(&filter),
+                Ok(Expr::Category(category.to_string()))
+            );
+        }
+        let labels = Labels {
+            collections: vec!["R&D".to_string()],
+            tags: vec![],
+        };
+        assert_eq!(labels.matches(&collection_filter("R&D")), Some(true));
+    }
+
+    #[test]
+    fn label_categories() {
+        let labels = labels();
+        assert_eq!(labels.matches("collection:used in projectx"), Some(true));
+        assert_eq!(
+            labels.matches("collection:Dungeon kit candidates"),
+            Some(false)
+        );
+        assert_eq!(labels.matches("collection:"), Some(true));
+        assert_eq!(labels.matches("tag:Dungeon Kit"), Some(true));
+        assert_eq!(labels.matches("tag:meshes"), Some(false));
+        assert_eq!(Labels::default().matches("tag:"), Some(false));
+        assert_eq!(labels.matches("assets"), None);
+    }
+
+    #[test]
+    fn dropdown_groups() {
+        let entries = dropdown_entries(&["Used in ProjectX".to_string()], &[]);
+        assert_eq!(
+            entries,
+            vec![
+                ("Collections".to_string(), "collection:".to_string()),
+                (
+                    "    Used in ProjectX".to_string(),
+                    "collection:Used in ProjectX".to_string()
+                ),
+            ]
+        );
+        assert!(dropdown_entries(&[], &[]).is_empty());
+    }
+}
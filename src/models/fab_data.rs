@@ -1,3 +1,4 @@
+use crate::models::collections::Labels;
 use diesel::dsl::exists;
 use diesel::{select, ExpressionMethods, QueryDsl, RunQueryDsl};
 use egs_api::api::types::fab_library::FabAsset;
@@ -29,6 +30,7 @@ mod imp {
         download_speed: RefCell<String>,
         pub asset: RefCell<Option<FabAsset>>,
         thumbnail: RefCell<Option<Texture>>,
+        pub labels: RefCell<Labels>,
         pub settings: gtk4::gio::Settings,
     }
 
@@ -51,6 +53,7 @@ mod imp {
                 download_speed: RefCell::new(String::new()),
                 asset: RefCell::new(None),
                 thumbnail: RefCell::new(None),
+                labels: RefCell::new(Labels::default()),
                 settings: gtk4::gio::Settings::new(crate::config::APP_ID),
             }
         }
@@ -178,6 +181,7 @@ impl FabData {
 
         data.set_property("id", &asset.asset_id);
         data.check_favorite();
+        data.check_labels();
         data.set_property("name", &asset.title);
         self_.asset.replace(Some(asset.clone()));
         data.check_downloaded();
@@ -219,7 +223,9 @@ impl FabData {
     }
 
     fn has_category(&self, cat: &str) -> bool {
-        if cat.eq("favorites") {
+        if let Some(labeled) = self.imp().labels.borrow().matches(cat) {
+            labeled
+        } else if cat.eq("favorites") {
             self.favorite()
         } else if cat.eq("downloaded") {
             self.downloaded()
@@ -249,8 +255,8 @@ impl FabData {
         }
     }
 
-    pub fn check_category(&self, cat: &str) -> bool {
-        crate::tools::category_filter::eval_category_expr(cat, &|c| self.has_category(c))
+    pub fn check_category(&self, expr: &crate::tools::category_filter::Expr) -> bool {
+        expr.eval(&|c| self.has_category(c))
     }
 
     pub fn check_downloaded(&self) {
@@ -295,8 +301,18 @@ impl FabData {
         self.set_property("favorite", false);
     }
 
+    /// Collections and tags of the asset
+    pub fn labels(&self) -> Labels {
+        self.imp().labels.borrow().clone()
+    }
+
+    pub fn check_labels(&self) {
+        self.imp().labels.replace(Labels::load(&self.id()));
+    }
+
     pub fn refresh(&self) {
         self.check_favorite();
+        self.check_labels();
         self.check_downloaded();
         self.emit_by_name::<()>("refreshed", &[]);
     }
@@ -0,0 +1,323 @@
+use crate::schema::asset_file_selection;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use egs_api::api::types::download_manifest::DownloadManifest;
+use log::error;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Include or exclude files matching a path or glob pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    pub pattern: String,
+    pub include: bool,
+}
+
+/// Rule together with its compiled pattern
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: Rule,
+    regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn new(rule: Rule) -> Self {
+        Self {
+            regex: pattern_regex(&rule.pattern),
+            rule,
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        self.regex.as_ref().is_some_and(|r| r.is_match(path))
+    }
+}
+
+/// Files of an asset to install, everything is included unless a rule excludes it
+#[derive(Debug, Clone, Default)]
+pub struct FileSelection {
+    rules: Vec<CompiledRule>,
+}
+
+/// Compile a pattern matching manifest paths, `None` for an empty pattern. `*` and `?` stay within
+/// a folder, `**` crosses folders. A pattern also matches everything below the folder it names.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let pattern = pattern.trim().trim_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut re = String::from("(?i)^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches no folder at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push_str("(?:/.*)?$");
+    Regex::new(&re).ok()
+}
+
+impl FileSelection {
+    pub fn from_rules(rules: Vec<Rule>) -> Self {
+        Self {
+            rules: rules.into_iter().map(CompiledRule::new).collect(),
+        }
+    }
+
+    pub fn rules(&self) -> Vec<Rule> {
+        self.rules.iter().map(|r| r.rule.clone()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The last matching rule decides
+    pub fn includes(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.matches(path))
+            .is_none_or(|r| r.rule.include)
+    }
+
+    /// Add a rule, replacing older rules for the same pattern and for the paths below it
+    pub fn set(&mut self, pattern: &str, include: bool) {
+        let pattern = pattern.trim().trim_matches('/').to_string();
+        if pattern.is_empty() {
+            return;
+        }
+        let added = CompiledRule::new(Rule { pattern, include });
+        self.rules
+            .retain(|r| r.rule.pattern != added.rule.pattern && !added.matches(&r.rule.pattern));
+        // Including something that is included anyway needs no rule
+        if include && self.includes(&added.rule.pattern) {
+            return;
+        }
+        self.rules.push(added);
+    }
+
+    pub fn remove(&mut self, pattern: &str) {
+        self.rules.retain(|r| r.rule.pattern != pattern);
+    }
+
+    /// Drop the excluded files and the chunks only they need from a download manifest
+    pub fn apply(&self, manifest: &mut DownloadManifest) {
+        if self.is_empty() {
+            return;
+        }
+        manifest
+            .file_manifest_list
+            .retain(|f| self.includes(&f.filename));
+        let used: HashSet<&String> = manifest
+            .file_manifest_list
+            .iter()
+            .flat_map(|f| f.file_chunk_parts.iter().map(|p| &p.guid))
+            .collect();
+        manifest.chunk_hash_list.retain(|g, _| used.contains(g));
+        manifest.chunk_filesize_list.retain(|g, _| used.contains(g));
+        manifest.data_group_list.retain(|g, _| used.contains(g));
+        if let Some(sha) = manifest.chunk_sha_list.as_mut() {
+            sha.retain(|g, _| used.contains(g));
+        }
+    }
+
+    /// Load the selection saved for an asset
+    pub fn load(asset: &str) -> Self {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return Self::default();
+        };
+        match asset_file_selection::table
+            .filter(asset_file_selection::asset.eq(asset))
+            .select(asset_file_selection::rules)
+            .first::<String>(&mut conn)
+            .optional()
+        {
+            Ok(Some(rules)) => Self::from_rules(serde_json::from_str(&rules).unwrap_or_default()),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                error!("Unable to load file selection of {}: {}", asset, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Remember the selection so later downloads and updates of the asset use it
+    pub fn save(&self, asset: &str) {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return;
+        };
+        let result = if self.is_empty() {
+            diesel::delete(
+                asset_file_selection::table.filter(asset_file_selection::asset.eq(asset)),
+            )
+            .execute(&mut conn)
+        } else {
+            let rules = serde_json::to_string(&self.rules()).unwrap_or_else(|_| "[]".to_string());
+            diesel::replace_into(asset_file_selection::table)
+                .values((
+                    asset_file_selection::asset.eq(asset),
+                    asset_file_selection::rules.eq(rules),
+                ))
+                .execute(&mut conn)
+        };
+        if let Err(e) = result {
+            error!("Unable to save file selection of {}: {}", asset, e);
+        }
+    }
+}
+
+/// File or folder of the picker tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub path: String,
+    pub name: String,
+    pub depth: usize,
+    pub folder: bool,
+    /// Size of the file or of everything in the folder
+    pub size: u128,
+    pub files: usize,
+}
+
+/// Flatten manifest files into a tree with folders before their contents and per-folder sizes
+pub fn tree(files: &[(String, u128)]) -> Vec<TreeEntry> {
+    let mut entries: BTreeMap<String, TreeEntry> = BTreeMap::new();
+    for (path, size) in files {
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        for depth in 0..parts.len() {
+            let folder = depth + 1 < parts.len();
+            let entry_path = parts[..=depth].join("/");
+            let entry = entries
+                .entry(entry_path.clone())
+                .or_insert_with(|| TreeEntry {
+                    path: entry_path,
+                    name: parts[depth].to_string(),
+                    depth,
+                    folder,
+                    size: 0,
+                    files: 0,
+                });
+            entry.size += size;
+            entry.files += 1;
+        }
+    }
+    // Sorting by path segments keeps the contents of a folder right after it
+    let mut entries: Vec<TreeEntry> = entries.into_values().collect();
+    entries.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        pattern_regex(pattern).is_some_and(|r| r.is_match(path))
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(matches("Content/Maps", "Content/Maps/Demo.umap"));
+        assert!(matches("Content/Maps/", "Content/Maps/Demo.umap"));
+        assert!(!matches("Content/Maps", "Content/MapsExtra/Demo.umap"));
+        assert!(matches("*.umap", "Demo.umap"));
+        assert!(!matches("*.umap", "Content/Demo.umap"));
+        assert!(matches("**/*.umap", "Content/Maps/Demo.umap"));
+        assert!(matches("**/*.umap", "Demo.umap"));
+        assert!(matches(
+            "Content/**/HighRes",
+            "Content/Textures/Rock/HighRes/T_Rock.uasset"
+        ));
+        assert!(matches("content/t?xtures", "Content/Textures/T.uasset"));
+        assert!(!matches("", "Content/Textures/T.uasset"));
+    }
+
+    #[test]
+    fn last_rule_wins() {
+        let mut selection = FileSelection::default();
+        assert!(selection.includes("Content/Maps/Demo.umap"));
+        selection.set("Content/Maps", false);
+        selection.set("Content/Maps/Keep.umap", true);
+        assert!(!selection.includes("Content/Maps/Demo.umap"));
+        assert!(selection.includes("Content/Maps/Keep.umap"));
+        assert!(selection.includes("Content/Meshes/Rock.uasset"));
+
+        // Toggling the folder again drops the rules below it
+        selection.set("Content/Maps", true);
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn excluded_files_and_chunks_are_dropped() {
+        use egs_api::api::types::download_manifest::{FileChunkPart, FileManifestList};
+        let file = |name: &str, guids: &[&str]| FileManifestList {
+            filename: name.to_string(),
+            file_chunk_parts: guids
+                .iter()
+                .map(|g| FileChunkPart {
+                    guid: (*g).to_string(),
+                    size: 1,
+                    ..FileChunkPart::default()
+                })
+                .collect(),
+            ..FileManifestList::default()
+        };
+        let mut manifest = DownloadManifest {
+            file_manifest_list: vec![
+                file("Content/Maps/Demo.umap", &["a", "b"]),
+                file("Content/Meshes/Rock.uasset", &["b", "c"]),
+            ],
+            chunk_filesize_list: [("a", 10), ("b", 20), ("c", 30)]
+                .into_iter()
+                .map(|(g, s)| (g.to_string(), s))
+                .collect(),
+            ..DownloadManifest::default()
+        };
+        let mut selection = FileSelection::default();
+        selection.set("Content/Maps", false);
+        selection.apply(&mut manifest);
+        assert_eq!(manifest.file_manifest_list.len(), 1);
+        assert_eq!(manifest.total_download_size(), 50);
+    }
+
+    #[test]
+    fn tree_with_folder_sizes() {
+        let files = vec![
+            ("Content/Maps/Demo.umap".to_string(), 10),
+            ("Content/Textures/T.uasset".to_string(), 5),
+            ("Content/Maps/Other.umap".to_string(), 1),
+            ("Readme.txt".to_string(), 2),
+        ];
+        let tree = tree(&files);
+        let summary: Vec<(&str, usize, bool, u128, usize)> = tree
+            .iter()
+            .map(|e| (e.path.as_str(), e.depth, e.folder, e.size, e.files))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Content", 0, true, 16, 3),
+                ("Content/Maps", 1, true, 11, 2),
+                ("Content/Maps/Demo.umap", 2, false, 10, 1),
+                ("Content/Maps/Other.umap", 2, false, 1, 1),
+                ("Content/Textures", 1, true, 5, 1),
+                ("Content/Textures/T.uasset", 2, false, 5, 1),
+                ("Readme.txt", 0, false, 2, 1),
+            ]
+        );
+    }
+}
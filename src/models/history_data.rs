@@ -0,0 +1,295 @@
+use crate::schema::download_history;
+use chrono::{Local, TimeZone};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use gtk4::{glib, subclass::prelude::*};
+use log::error;
+use std::collections::HashMap;
+
+/// How a download ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Finished,
+    Canceled,
+    Failed,
+}
+
+impl Outcome {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Finished => "finished",
+            Self::Canceled => "canceled",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "finished" => Self::Finished,
+            "canceled" => Self::Canceled,
+            _ => Self::Failed,
+        }
+    }
+
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Finished => "Finished",
+            Self::Canceled => "Canceled",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// One finished, canceled or failed download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryRecord {
+    /// Asset id or engine version
+    pub item_id: String,
+    pub item_type: String,
+    pub label: String,
+    pub release: Option<String>,
+    pub bytes: u64,
+    /// Unix timestamps in seconds
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// Bytes per second
+    pub average_speed: u64,
+    pub outcome: Outcome,
+    pub error: Option<String>,
+    pub targets: Vec<String>,
+}
+
+type HistoryColumns = (
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    i64,
+    i64,
+    i64,
+    String,
+    Option<String>,
+    String,
+);
+
+impl HistoryRecord {
+    pub fn duration(&self) -> i64 {
+        (self.finished_at - self.started_at).max(0)
+    }
+
+    /// Month the download ended in, formatted as `YYYY-MM`
+    pub fn month(&self) -> String {
+        Local
+            .timestamp_opt(self.finished_at, 0)
+            .single()
+            .map(|d| d.format("%Y-%m").to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn matches(&self, text: &str, outcome: Option<Outcome>) -> bool {
+        if outcome.is_some_and(|o| o != self.outcome) {
+            return false;
+        }
+        let text = text.trim().to_lowercase();
+        text.is_empty()
+            || self.label.to_lowercase().contains(&text)
+            || self.item_id.to_lowercase().contains(&text)
+            || self
+                .release
+                .as_ref()
+                .is_some_and(|r| r.to_lowercase().contains(&text))
+    }
+
+    /// Load the whole history, newest first
+    pub fn load_all() -> Vec<Self> {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return Vec::new();
+        };
+        let rows: Vec<HistoryColumns> = match download_history::table
+            .select((
+                download_history::item_id,
+                download_history::item_type,
+                download_history::label,
+                download_history::release,
+                download_history::bytes,
+                download_history::started_at,
+                download_history::finished_at,
+                download_history::average_speed,
+                download_history::outcome,
+                download_history::error,
+                download_history::targets,
+            ))
+            .order(download_history::id.desc())
+            .load(&mut conn)
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("Unable to load download history: {}", e);
+                return Vec::new();
+            }
+        };
+        rows.into_iter()
+            .map(
+                |(
+                    item_id,
+                    item_type,
+                    label,
+                    release,
+                    bytes,
+                    started_at,
+                    finished_at,
+                    average_speed,
+                    outcome,
+                    error,
+                    targets,
+                )| Self {
+                    item_id,
+                    item_type,
+                    label,
+                    release,
+                    bytes: u64::try_from(bytes).unwrap_or_default(),
+                    started_at,
+                    finished_at,
+                    average_speed: u64::try_from(average_speed).unwrap_or_default(),
+                    outcome: Outcome::from_name(&outcome),
+                    error,
+                    targets: serde_json::from_str(&targets).unwrap_or_default(),
+                },
+            )
+            .collect()
+    }
+}
+
+/// Downloaded bytes per month, newest month first
+pub fn monthly_totals(records: &[HistoryRecord]) -> Vec<(String, u64)> {
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for record in records {
+        *totals.entry(record.month()).or_default() += record.bytes;
+    }
+    let mut totals: Vec<(String, u64)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.0.cmp(&a.0));
+    totals
+}
+
+/// Downloaded bytes per asset or engine, largest first
+pub fn asset_totals(records: &[HistoryRecord]) -> Vec<(String, u64)> {
+    let mut totals: HashMap<&str, (&str, u64)> = HashMap::new();
+    for record in records {
+        totals
+            .entry(&record.item_id)
+            .or_insert((&record.label, 0))
+            .1 += record.bytes;
+    }
+    let mut totals: Vec<(String, u64)> = totals
+        .into_values()
+        .map(|(label, bytes)| (label.to_string(), bytes))
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+// Implementation sub-module of the GObject
+mod imp {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Default)]
+    pub struct HistoryData {
+        pub record: RefCell<Option<HistoryRecord>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HistoryData {
+        const NAME: &'static str = "HistoryData";
+        type Type = super::HistoryData;
+        type ParentType = glib::Object;
+    }
+
+    impl ObjectImpl for HistoryData {}
+}
+
+glib::wrapper! {
+    pub struct HistoryData(ObjectSubclass<imp::HistoryData>);
+}
+
+impl HistoryData {
+    pub fn new(record: &HistoryRecord) -> HistoryData {
+        let data: Self = glib::Object::new::<Self>();
+        data.imp().record.replace(Some(record.clone()));
+        data
+    }
+
+    pub fn record(&self) -> Option<HistoryRecord> {
+        self.imp().record.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(item_id: &str, label: &str, bytes: u64, finished_at: i64) -> HistoryRecord {
+        HistoryRecord {
+            item_id: item_id.to_string(),
+            item_type: "asset".to_string(),
+            label: label.to_string(),
+            release: Some(format!("{item_id}-release")),
+            bytes,
+            started_at: finished_at - 60,
+            finished_at,
+            average_speed: bytes / 60,
+            outcome: Outcome::Finished,
+            error: None,
+            targets: vec![],
+        }
+    }
+
+    // Middle of the month so the local timezone does not matter
+    const SEPTEMBER: i64 = 1_757_937_600;
+    const OCTOBER: i64 = 1_760_529_600;
+
+    #[test]
+    fn totals_per_month() {
+        let records = [
+            record("a", "A", 10, SEPTEMBER),
+            record("b", "B", 5, OCTOBER),
+            record("a", "A", 7, OCTOBER),
+        ];
+        assert_eq!(
+            monthly_totals(&records),
+            vec![("2025-10".to_string(), 12), ("2025-09".to_string(), 10)]
+        );
+    }
+
+    #[test]
+    fn totals_per_asset() {
+        let records = [
+            record("a", "A", 10, SEPTEMBER),
+            record("b", "B", 15, OCTOBER),
+            record("a", "A", 7, OCTOBER),
+        ];
+        assert_eq!(
+            asset_totals(&records),
+            vec![("A".to_string(), 17), ("B".to_string(), 15)]
+        );
+    }
+
+    #[test]
+    fn filter_by_text_and_outcome() {
+        let mut failed = record("engine", "Unreal Engine 5.4", 1, OCTOBER);
+        failed.outcome = Outcome::Failed;
+        assert!(failed.matches("", None));
+        assert!(failed.matches("unreal", None));
+        assert!(failed.matches("ENGINE-rel", Some(Outcome::Failed)));
+        assert!(!failed.matches("", Some(Outcome::Finished)));
+        assert!(!failed.matches("fab", None));
+    }
+
+    #[test]
+    fn outcome_names() {
+        for o in [Outcome::Finished, Outcome::Canceled, Outcome::Failed] {
+            assert_eq!(Outcome::from_name(o.name()), o);
+        }
+    }
+}
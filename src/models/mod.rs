@@ -1,12 +1,16 @@
 pub mod asset_data;
 pub mod category_data;
+pub mod collections;
 pub mod database;
 pub mod engine_data;
 pub mod fab_data;
 pub mod fab_search_data;
+pub mod file_selection;
+pub mod history_data;
 pub mod log_data;
 mod plugin_data;
 pub mod project_data;
+pub mod saved_search;
 
 use crate::config::APP_ID;
 use egs_api::EpicGames;
@@ -30,6 +34,9 @@ pub struct Model {
     pub settings: gio::Settings,
     #[cfg(target_os = "linux")]
     pub dclient: RefCell<Option<ghregistry::Client>>,
+    /// GitHub user and token of `dclient`, blobs are fetched without it to apply the speed limit
+    #[cfg(target_os = "linux")]
+    pub dcredentials: RefCell<Option<(String, String)>>,
 }
 
 impl Default for Model {
@@ -59,6 +66,8 @@ impl Model {
             settings: gio::Settings::new(APP_ID),
             #[cfg(target_os = "linux")]
             dclient: RefCell::new(None),
+            #[cfg(target_os = "linux")]
+            dcredentials: RefCell::new(None),
         };
         obj.load_secrets();
         obj.load_defaults();
@@ -150,6 +159,7 @@ impl Model {
         debug!("Trying to validate token for {}", user);
         #[cfg(target_os = "linux")]
         {
+            let credentials = (user.clone(), token.clone());
             let client = ghregistry::Client::configure()
                 .registry("ghcr.io")
                 .insecure_registry(false)
@@ -170,9 +180,11 @@ impl Model {
                     Ok(docker_client) => match docker_client.is_auth() {
                         Ok(auth) => {
                             if auth {
-                                let _ = sender.send_blocking(
-                                    crate::ui::messages::Msg::DockerClient(docker_client),
-                                );
+                                let _ =
+                                    sender.send_blocking(crate::ui::messages::Msg::DockerClient(
+                                        docker_client,
+                                        credentials,
+                                    ));
                                 info!("Docker Authenticated");
                             }
                         }
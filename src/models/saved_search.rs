@@ -0,0 +1,169 @@
+use crate::schema::saved_search;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use log::error;
+
+/// `default-category` value opening a saved search at startup, followed by its name
+pub const DEFAULT_CATEGORY_PREFIX: &str = "saved:";
+
+/// Filters of the library kept under a name, the results follow the library as it changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub search: String,
+    /// Path of the category dropdown entry, empty for all
+    pub category: String,
+    /// Category expression of the sidebar, e.g. `games|dlc`
+    pub filter: Option<String>,
+    pub downloaded: bool,
+    pub favorites: bool,
+}
+
+type SavedSearchColumns = (String, String, String, Option<String>, bool, bool);
+
+impl SavedSearch {
+    fn from_columns(columns: SavedSearchColumns) -> Self {
+        let (name, search, category, filter, downloaded, favorites) = columns;
+        Self {
+            name,
+            search,
+            category,
+            filter,
+            downloaded,
+            favorites,
+        }
+    }
+
+    /// `default-category` value of the saved search
+    pub fn default_category(&self) -> String {
+        format!("{DEFAULT_CATEGORY_PREFIX}{}", self.name)
+    }
+
+    /// What the search filters by, e.g. `"rock" in Environments, downloaded`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.search.is_empty() {
+            parts.push(format!("\"{}\"", self.search));
+        }
+        if let Some(filter) = &self.filter {
+            parts.push(format!("in {filter}"));
+        }
+        if !self.category.is_empty() {
+            parts.push(format!("in {}", self.category));
+        }
+        if self.downloaded {
+            parts.push("downloaded".to_string());
+        }
+        if self.favorites {
+            parts.push("starred".to_string());
+        }
+        if parts.is_empty() {
+            "Everything".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    pub fn all() -> Vec<Self> {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return Vec::new();
+        };
+        match saved_search::table
+            .select((
+                saved_search::name,
+                saved_search::search,
+                saved_search::category,
+                saved_search::filter,
+                saved_search::downloaded,
+                saved_search::favorites,
+            ))
+            .order(saved_search::name)
+            .load::<SavedSearchColumns>(&mut conn)
+        {
+            Ok(rows) => rows.into_iter().map(Self::from_columns).collect(),
+            Err(e) => {
+                error!("Unable to load saved searches: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn load(name: &str) -> Option<Self> {
+        let db = crate::models::database::connection();
+        let mut conn = db.get().ok()?;
+        match saved_search::table
+            .filter(saved_search::name.eq(name))
+            .select((
+                saved_search::name,
+                saved_search::search,
+                saved_search::category,
+                saved_search::filter,
+                saved_search::downloaded,
+                saved_search::favorites,
+            ))
+            .first::<SavedSearchColumns>(&mut conn)
+            .optional()
+        {
+            Ok(row) => row.map(Self::from_columns),
+            Err(e) => {
+                error!("Unable to load saved search {}: {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Store the search, replacing a saved search with the same name
+    pub fn save(&self) {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return;
+        };
+        if let Err(e) = diesel::replace_into(saved_search::table)
+            .values((
+                saved_search::name.eq(&self.name),
+                saved_search::search.eq(&self.search),
+                saved_search::category.eq(&self.category),
+                saved_search::filter.eq(&self.filter),
+                saved_search::downloaded.eq(self.downloaded),
+                saved_search::favorites.eq(self.favorites),
+            ))
+            .execute(&mut conn)
+        {
+            error!("Unable to save search {}: {}", self.name, e);
+        }
+    }
+
+    pub fn delete(name: &str) {
+        let db = crate::models::database::connection();
+        let Ok(mut conn) = db.get() else {
+            return;
+        };
+        if let Err(e) = diesel::delete(saved_search::table.filter(saved_search::name.eq(name)))
+            .execute(&mut conn)
+        {
+            error!("Unable to delete saved search {}: {}", name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let search = SavedSearch {
+            name: "Rocks".to_string(),
+            search: "rock".to_string(),
+            category: "assets/environments".to_string(),
+            downloaded: true,
+            ..SavedSearch::default()
+        };
+        assert_eq!(
+            search.summary(),
+            "\"rock\", in assets/environments, downloaded"
+        );
+        assert_eq!(search.default_category(), "saved:Rocks");
+        assert_eq!(SavedSearch::default().summary(), "Everything");
+    }
+}
@@ -17,3 +17,72 @@ diesel::table! {
         value -> Text,
     }
 }
+
+diesel::table! {
+    download_queue (id) {
+        id -> Text,
+        payload -> Text,
+        target -> Nullable<Text>,
+        actions -> Text,
+        paused -> Bool,
+        downloaded_size -> BigInt,
+        total_size -> BigInt,
+        priority -> Integer,
+        position -> Integer,
+    }
+}
+
+diesel::table! {
+    download_history (id) {
+        id -> Integer,
+        item_id -> Text,
+        item_type -> Text,
+        label -> Text,
+        release -> Nullable<Text>,
+        bytes -> BigInt,
+        started_at -> BigInt,
+        finished_at -> BigInt,
+        average_speed -> BigInt,
+        outcome -> Text,
+        error -> Nullable<Text>,
+        targets -> Text,
+    }
+}
+
+diesel::table! {
+    asset_file_selection (asset) {
+        asset -> Text,
+        rules -> Text,
+    }
+}
+
+diesel::table! {
+    collection (name) {
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    collection_asset (collection, asset) {
+        collection -> Text,
+        asset -> Text,
+    }
+}
+
+diesel::table! {
+    asset_tag (asset, tag) {
+        asset -> Text,
+        tag -> Text,
+    }
+}
+
+diesel::table! {
+    saved_search (name) {
+        name -> Text,
+        search -> Text,
+        category -> Text,
+        filter -> Nullable<Text>,
+        downloaded -> Bool,
+        favorites -> Bool,
+    }
+}
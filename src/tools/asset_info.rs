@@ -1,4 +1,6 @@
+use crate::tools::search::Field;
 use egs_api::api::types::asset_info::AssetInfo;
+use egs_api::api::types::fab_library::FabAsset;
 
 #[allow(dead_code)]
 pub trait Search {
@@ -8,6 +10,10 @@ pub trait Search {
     fn thumbnail(&self) -> Option<egs_api::api::types::asset_info::KeyImage> {
         None
     }
+    /// Text of the cached metadata for the full-text search
+    fn search_fields(&self) -> Vec<(Field, String)> {
+        Vec::new()
+    }
 }
 
 impl Search for AssetInfo {
@@ -23,6 +29,33 @@ impl Search for AssetInfo {
         None
     }
 
+    fn search_fields(&self) -> Vec<(Field, String)> {
+        let mut fields = Vec::new();
+        for (field, text) in [
+            (Field::Title, &self.title),
+            (Field::Seller, &self.developer),
+            (Field::Description, &self.description),
+            (Field::LongDescription, &self.long_description),
+            (Field::TechnicalDetails, &self.technical_details),
+        ] {
+            if let Some(text) = text {
+                fields.push((field, text.clone()));
+            }
+        }
+        for category in self.categories.iter().flatten() {
+            fields.push((Field::Category, category.path.clone()));
+        }
+        for attribute in self.custom_attributes.iter().flat_map(|a| a.values()) {
+            fields.push((Field::Tag, attribute.value.clone()));
+        }
+        for release in self.release_info.iter().flatten() {
+            for app in release.compatible_apps.iter().flatten() {
+                fields.push((Field::Engine, app.clone()));
+            }
+        }
+        fields
+    }
+
     fn matches_filter(&self, tag: Option<String>, search: Option<String>) -> bool {
         let mut tag_found = false;
         match tag {
@@ -52,6 +85,30 @@ impl Search for AssetInfo {
     }
 }
 
+impl Search for FabAsset {
+    fn search_fields(&self) -> Vec<(Field, String)> {
+        let mut fields = vec![
+            (Field::Title, self.title.clone()),
+            (Field::Description, self.description.clone()),
+        ];
+        for category in &self.categories {
+            fields.push((
+                Field::Category,
+                category.name.clone().unwrap_or_else(|| category.id.clone()),
+            ));
+        }
+        for attribute in self.custom_attributes.iter().flat_map(|a| a.values()) {
+            fields.push((Field::Tag, attribute.clone()));
+        }
+        for version in &self.project_versions {
+            for engine in &version.engine_versions {
+                fields.push((Field::Engine, engine.clone()));
+            }
+        }
+        fields
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,109 @@
+/// Release of an asset with the engine versions it supports, e.g. `UE_5.3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub app: String,
+    pub compatible: Vec<String>,
+}
+
+impl Release {
+    /// Releases of an asset, newest first
+    pub fn of(asset: &egs_api::api::types::asset_info::AssetInfo) -> Vec<Self> {
+        asset
+            .sorted_releases()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| {
+                Some(Self {
+                    app: r.app_id?,
+                    compatible: r.compatible_apps.unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Compatible app of an engine association like `5.3`, source builds identified by a guid have none
+pub fn engine_app(association: &str) -> Option<String> {
+    let mut parts = association.trim().split('.');
+    let major = parts.next().filter(|p| p.parse::<u32>().is_ok())?;
+    let minor = parts.next().filter(|p| p.parse::<u32>().is_ok())?;
+    Some(format!("UE_{major}.{minor}"))
+}
+
+/// Newest release (of `releases`, newest first) that replaces `installed`, `None` when it is up to date
+///
+/// With an engine only releases supporting it count, otherwise the newer release has to
+/// support at least one engine of the installed one.
+pub fn newer_release<'a>(
+    releases: &'a [Release],
+    installed: &str,
+    engine: Option<&str>,
+) -> Option<&'a Release> {
+    let index = releases.iter().position(|r| r.app == installed)?;
+    let current = &releases[index];
+    releases[..index].iter().find(|newer| match engine {
+        Some(engine) => newer.compatible.iter().any(|c| c == engine),
+        None => {
+            current.compatible.is_empty()
+                || newer
+                    .compatible
+                    .iter()
+                    .any(|c| current.compatible.contains(c))
+        }
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn release(app: &str, compatible: &[&str]) -> Release {
+        Release {
+            app: app.to_string(),
+            compatible: compatible.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn releases() -> Vec<Release> {
+        vec![
+            release("Kit_5.4", &["UE_5.4"]),
+            release("Kit_5.2", &["UE_5.2", "UE_5.3"]),
+            release("Kit_5.1", &["UE_5.1", "UE_5.2"]),
+            release("Kit_5.0", &["UE_5.0"]),
+        ]
+    }
+
+    #[test]
+    fn engine_associations() {
+        assert_eq!(engine_app("5.3"), Some("UE_5.3".to_string()));
+        assert_eq!(engine_app("4.27.2"), Some("UE_4.27".to_string()));
+        assert_eq!(engine_app("{8E2D6A4C-43A4-4D2C-9E4B-1D1A5C3F6B7E}"), None);
+        assert_eq!(engine_app(""), None);
+    }
+
+    #[test]
+    fn newer_release_for_the_same_engines() {
+        let releases = releases();
+        assert_eq!(
+            newer_release(&releases, "Kit_5.1", None).map(|r| r.app.as_str()),
+            Some("Kit_5.2")
+        );
+        assert_eq!(newer_release(&releases, "Kit_5.0", None), None);
+        assert_eq!(newer_release(&releases, "Kit_5.4", None), None);
+        assert_eq!(newer_release(&releases, "Unknown", None), None);
+    }
+
+    #[test]
+    fn newer_release_for_a_project_engine() {
+        let releases = releases();
+        assert_eq!(
+            newer_release(&releases, "Kit_5.0", Some("UE_5.4")).map(|r| r.app.as_str()),
+            Some("Kit_5.4")
+        );
+        assert_eq!(
+            newer_release(&releases, "Kit_5.1", Some("UE_5.3")).map(|r| r.app.as_str()),
+            Some("Kit_5.2")
+        );
+        assert_eq!(newer_release(&releases, "Kit_5.1", Some("UE_5.1")), None);
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{NaiveTime, Timelike};
+use log::warn;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Time of day window during which a different speed limit applies.
+/// The window may wrap around midnight, e.g. 22:00 - 07:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// Limit in bytes per second while inside the window, 0 means unlimited
+    pub limit: u64,
+}
+
+impl Schedule {
+    pub fn new(start: &str, end: &str, limit: u64) -> Option<Self> {
+        let start = parse_time(start)?;
+        let end = parse_time(end)?;
+        Some(Self { start, end, limit })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let time = NaiveTime::from_hms_opt(time.hour(), time.minute(), 0).unwrap_or(time);
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    match NaiveTime::parse_from_str(time.trim(), "%H:%M") {
+        Ok(t) => Some(t),
+        Err(e) => {
+            warn!("Unable to parse schedule time {}: {}", time, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes that can still be read without waiting, negative when in debt
+    available: f64,
+    last: Instant,
+}
+
+/// Token bucket shared by all download threads so the combined speed stays under the limit
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: Mutex<(u64, Option<Schedule>)>,
+    bucket: Mutex<Bucket>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            limit: Mutex::new((0, None)),
+            bucket: Mutex::new(Bucket {
+                available: 0.0,
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// Set the global limit in bytes per second (0 means unlimited) and the optional schedule
+    pub fn configure(&self, limit: u64, schedule: Option<Schedule>) {
+        if let Ok(mut l) = self.limit.lock() {
+            *l = (limit, schedule);
+        }
+    }
+
+    pub fn limit_at(&self, time: NaiveTime) -> u64 {
+        let Ok(l) = self.limit.lock() else {
+            return 0;
+        };
+        match l.1 {
+            Some(schedule) if schedule.contains(time) => schedule.limit,
+            _ => l.0,
+        }
+    }
+
+    pub fn current_limit(&self) -> u64 {
+        self.limit_at(chrono::Local::now().time())
+    }
+
+    /// Account for `bytes` read from the network, blocking the calling thread if we are over the limit
+    pub fn throttle(&self, bytes: usize) {
+        let limit = self.current_limit();
+        let wait = {
+            let Ok(mut bucket) = self.bucket.lock() else {
+                return;
+            };
+            let now = Instant::now();
+            if limit == 0 {
+                bucket.available = 0.0;
+                bucket.last = now;
+                return;
+            }
+            let rate = limit as f64;
+            let elapsed = now.duration_since(bucket.last).as_secs_f64();
+            // Allow at most one second worth of burst
+            bucket.available = (bucket.available + elapsed * rate).min(rate);
+            bucket.last = now;
+            bucket.available -= bytes as f64;
+            if bucket.available >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.available / rate)
+        };
+        std::thread::sleep(wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_schedule() {
+        let s = Schedule::new("22:00", " 07:30", 0).unwrap();
+        assert_eq!(s.start, time(22, 0));
+        assert_eq!(s.end, time(7, 30));
+        assert!(Schedule::new("25:00", "07:00", 0).is_none());
+        assert!(Schedule::new("", "07:00", 0).is_none());
+    }
+
+    #[test]
+    fn schedule_within_day() {
+        let s = Schedule::new("09:00", "17:00", 100).unwrap();
+        assert!(s.contains(time(9, 0)));
+        assert!(s.contains(time(16, 59)));
+        assert!(!s.contains(time(17, 0)));
+        assert!(!s.contains(time(8, 59)));
+    }
+
+    #[test]
+    fn schedule_over_midnight() {
+        let s = Schedule::new("22:00", "07:00", 0).unwrap();
+        assert!(s.contains(time(23, 15)));
+        assert!(s.contains(time(0, 0)));
+        assert!(s.contains(time(6, 59)));
+        assert!(!s.contains(time(7, 0)));
+        assert!(!s.contains(time(12, 0)));
+    }
+
+    #[test]
+    fn limit_follows_schedule() {
+        let limiter = RateLimiter::new();
+        limiter.configure(5_000_000, Schedule::new("22:00", "07:00", 0));
+        assert_eq!(limiter.limit_at(time(23, 0)), 0);
+        assert_eq!(limiter.limit_at(time(12, 0)), 5_000_000);
+        limiter.configure(1000, None);
+        assert_eq!(limiter.limit_at(time(23, 0)), 1000);
+    }
+
+    #[test]
+    fn unlimited_does_not_block() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+        limiter.throttle(100_000_000);
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -1,31 +1,155 @@
-/// Evaluate a category filter expression against a predicate.
+use std::fmt;
+
+/// Parsed category filter expression.
 ///
 /// The expression language supports:
-/// - Simple terms: `"assets"` — evaluated via the predicate
-/// - Negation: `"!games"` — inverts the predicate result
-/// - AND: `"assets&!games"` — both sides must be true
-/// - OR: `"assets|games"` — either side must be true
+/// - Terms: `assets` — evaluated via the predicate, spaces inside a term are kept
+/// - Negation: `!games`
+/// - AND: `assets&!games`
+/// - OR: `assets|games`
+/// - Grouping: `(assets|plugins)&!games`
 ///
-/// Operators are evaluated left-to-right (no precedence). The predicate
-/// closure receives individual category names and returns whether the
-/// item belongs to that category.
-pub fn eval_category_expr(expr: &str, checker: &impl Fn(&str) -> bool) -> bool {
-    expr.split(&['|', '&']).next().is_some_and(|c| {
-        let result = if let Some(stripped) = c.strip_prefix('!') {
-            !checker(stripped)
-        } else {
-            checker(c)
+/// `!` binds tighter than `&`, which binds tighter than `|`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Category(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// Why an expression could not be parsed, `position` is the character offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Expr {
+    pub fn parse(expr: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            chars: expr.chars().collect(),
+            position: 0,
         };
+        let result = parser.or()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(result),
+            Some(')') => Err(parser.error("unmatched ')'")),
+            Some(c) => Err(parser.error(&format!("unexpected '{c}'"))),
+        }
+    }
+
+    /// The predicate receives individual category names and returns whether the item belongs to it
+    pub fn eval(&self, checker: &impl Fn(&str) -> bool) -> bool {
+        match self {
+            Self::Category(name) => checker(name),
+            Self::Not(expr) => !expr.eval(checker),
+            Self::And(left, right) => left.eval(checker) && right.eval(checker),
+            Self::Or(left, right) => left.eval(checker) || right.eval(checker),
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
 
-        expr.chars().nth(c.len()).map_or(result, |operator| {
-            let remainder: String = expr.chars().skip(c.len() + 1).collect();
-            match operator {
-                '&' => result && eval_category_expr(&remainder, checker),
-                '|' => result || eval_category_expr(&remainder, checker),
-                _ => false,
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.and()?;
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('|') {
+                return Ok(left);
+            }
+            self.position += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('&') {
+                return Ok(left);
+            }
+            self.position += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('!') => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some('(') => {
+                let open = self.position;
+                self.position += 1;
+                let inner = self.or()?;
+                self.skip_whitespace();
+                if self.peek() == Some(')') {
+                    self.position += 1;
+                    Ok(inner)
+                } else {
+                    Err(ParseError {
+                        position: open,
+                        message: "unclosed '('".to_string(),
+                    })
+                }
             }
-        })
-    })
+            _ => self.term(),
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| !matches!(c, '&' | '|' | '!' | '(' | ')'))
+        {
+            self.position += 1;
+        }
+        let name: String = self.chars[start..self.position].iter().collect();
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(match self.peek() {
+                None => self.error("expected a category"),
+                Some(c) => self.error(&format!("expected a category before '{c}'")),
+            });
+        }
+        Ok(Expr::Category(name.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -37,152 +161,199 @@ mod tests {
         move |c| cats.iter().any(|cat| cat.eq_ignore_ascii_case(c))
     }
 
+    /// Helper: invalid expressions match nothing, like in the library
+    fn eval(expr: &str, checker: &impl Fn(&str) -> bool) -> bool {
+        Expr::parse(expr).is_ok_and(|e| e.eval(checker))
+    }
+
     // --- simple terms ---
 
     #[test]
     fn single_term_match() {
-        assert!(eval_category_expr("assets", &has(&["assets"])));
+        assert!(eval("assets", &has(&["assets"])));
     }
 
     #[test]
     fn single_term_no_match() {
-        assert!(!eval_category_expr("games", &has(&["assets"])));
+        assert!(!eval("games", &has(&["assets"])));
     }
 
     #[test]
     fn negation_match() {
-        assert!(eval_category_expr("!games", &has(&["assets"])));
+        assert!(eval("!games", &has(&["assets"])));
     }
 
     #[test]
     fn negation_no_match() {
-        assert!(!eval_category_expr("!assets", &has(&["assets"])));
+        assert!(!eval("!assets", &has(&["assets"])));
     }
 
     // --- AND operator ---
 
     #[test]
     fn and_both_true() {
-        assert!(eval_category_expr(
-            "assets&plugins",
-            &has(&["assets", "plugins"])
-        ));
+        assert!(eval("assets&plugins", &has(&["assets", "plugins"])));
     }
 
     #[test]
     fn and_left_false() {
-        assert!(!eval_category_expr("games&plugins", &has(&["plugins"])));
+        assert!(!eval("games&plugins", &has(&["plugins"])));
     }
 
     #[test]
     fn and_right_false() {
-        assert!(!eval_category_expr("assets&games", &has(&["assets"])));
+        assert!(!eval("assets&games", &has(&["assets"])));
     }
 
     #[test]
     fn and_with_negation() {
-        assert!(eval_category_expr("assets&!games", &has(&["assets"])));
+        assert!(eval("assets&!games", &has(&["assets"])));
     }
 
     #[test]
     fn and_with_negation_fails() {
-        assert!(!eval_category_expr(
-            "assets&!games",
-            &has(&["assets", "games"])
-        ));
+        assert!(!eval("assets&!games", &has(&["assets", "games"])));
     }
 
     // --- OR operator ---
 
     #[test]
     fn or_first_true() {
-        assert!(eval_category_expr("assets|games", &has(&["assets"])));
+        assert!(eval("assets|games", &has(&["assets"])));
     }
 
     #[test]
     fn or_second_true() {
-        assert!(eval_category_expr("assets|games", &has(&["games"])));
+        assert!(eval("assets|games", &has(&["games"])));
     }
 
     #[test]
     fn or_neither_true() {
-        assert!(!eval_category_expr("assets|games", &has(&["plugins"])));
+        assert!(!eval("assets|games", &has(&["plugins"])));
     }
 
     #[test]
     fn or_both_true() {
-        assert!(eval_category_expr(
-            "assets|games",
-            &has(&["assets", "games"])
-        ));
+        assert!(eval("assets|games", &has(&["assets", "games"])));
     }
 
     // --- chained operators ---
 
     #[test]
     fn three_way_and() {
-        assert!(eval_category_expr("a&b&c", &has(&["a", "b", "c"])));
+        assert!(eval("a&b&c", &has(&["a", "b", "c"])));
     }
 
     #[test]
     fn three_way_and_one_missing() {
-        assert!(!eval_category_expr("a&b&c", &has(&["a", "b"])));
+        assert!(!eval("a&b&c", &has(&["a", "b"])));
     }
 
     #[test]
     fn three_way_or() {
-        assert!(eval_category_expr("a|b|c", &has(&["c"])));
+        assert!(eval("a|b|c", &has(&["c"])));
     }
 
     #[test]
-    fn mixed_and_or_left_to_right() {
-        // "a&b|c" with a=true, b=false, c=true
-        // left-to-right: (a&b) = false, then false|c = true
-        assert!(eval_category_expr("a&b|c", &has(&["a", "c"])));
+    fn and_binds_tighter_than_or() {
+        // (a&b)|c, evaluated left to right this was (a&b)... without c
+        assert!(eval("a&b|c", &has(&["c"])));
+        assert!(eval("c|a&b", &has(&["c"])));
+        assert!(!eval("a|b&c", &has(&["b"])));
     }
 
     #[test]
-    fn mixed_and_or_short_circuit() {
-        // "a|b&c" with a=true, b=false, c=false
-        // left-to-right: a = true, then true|... short-circuits to true
-        assert!(eval_category_expr("a|b&c", &has(&["a"])));
+    fn negation_binds_tightest() {
+        assert!(eval("!a&b", &has(&["b"])));
+        assert!(!eval("!a&b", &has(&["a", "b"])));
     }
 
-    // --- edge cases ---
+    // --- grouping ---
 
     #[test]
-    fn empty_expression() {
-        assert!(!eval_category_expr("", &has(&["anything"])));
+    fn parentheses_group() {
+        assert!(!eval("(a|b)&c", &has(&["a"])));
+        assert!(eval("(a|b)&c", &has(&["b", "c"])));
+        assert!(eval("!(a&b)", &has(&["a"])));
+        assert!(!eval("!(a|b)", &has(&["b"])));
     }
 
     #[test]
-    fn just_negation_bang() {
-        // "!" → negation of empty string → checker("") → false → !false = true
-        assert!(eval_category_expr("!", &has(&["anything"])));
+    fn sidebar_filter() {
+        let expr = "(assets|plugins)&!games&(unreal-engine-5|all-engines)";
+        assert!(eval(expr, &has(&["plugins", "unreal-engine-5"])));
+        assert!(!eval(expr, &has(&["plugins", "games", "all-engines"])));
+        assert!(!eval(expr, &has(&["assets"])));
     }
 
     #[test]
-    fn double_negation_not_supported() {
-        // "!!a" → first term is "!!a" (split sees no operator)
-        // starts with '!' → checker("!a") → false (no category named "!a") → !false = true
-        assert!(eval_category_expr("!!a", &has(&["a"])));
+    fn whitespace_is_ignored_around_terms() {
+        assert_eq!(
+            Expr::parse(" 2d assets | ( games ) "),
+            Ok(Expr::Or(
+                Box::new(Expr::Category("2d assets".to_string())),
+                Box::new(Expr::Category("games".to_string()))
+            ))
+        );
+    }
+
+    #[test]
+    fn double_negation() {
+        assert!(eval("!!a", &has(&["a"])));
+        assert!(!eval("!!a", &has(&[])));
+    }
+
+    // --- errors ---
+
+    fn error(expr: &str) -> (usize, String) {
+        let e = Expr::parse(expr).unwrap_err();
+        (e.position, e.message)
+    }
+
+    #[test]
+    fn empty_expression() {
+        assert_eq!(error(""), (0, "expected a category".to_string()));
+        assert!(!eval("", &has(&["anything"])));
+    }
+
+    #[test]
+    fn just_negation_bang() {
+        assert_eq!(error("!"), (1, "expected a category".to_string()));
+        assert!(!eval("!", &has(&["anything"])));
     }
 
     #[test]
     fn trailing_operator() {
-        // "assets&" → first term "assets" true, operator '&', remainder ""
-        // eval_category_expr("") → false
-        assert!(!eval_category_expr("assets&", &has(&["assets"])));
+        assert_eq!(error("assets&"), (7, "expected a category".to_string()));
+        assert!(!eval("assets&", &has(&["assets"])));
     }
 
     #[test]
     fn leading_operator() {
-        // "&assets" → first term "" (from split) → checker("") → false
-        assert!(!eval_category_expr("&assets", &has(&["assets"])));
+        assert_eq!(
+            error("&assets"),
+            (0, "expected a category before '&'".to_string())
+        );
+        assert!(!eval("&assets", &has(&["assets"])));
+    }
+
+    #[test]
+    fn unbalanced_parentheses() {
+        assert_eq!(error("(a|b&c"), (0, "unclosed '('".to_string()));
+        assert_eq!(error("a|b)"), (3, "unmatched ')'".to_string()));
+        assert_eq!(error("a(b)"), (1, "unexpected '('".to_string()));
+    }
+
+    #[test]
+    fn error_message() {
+        assert_eq!(
+            Expr::parse("a&").unwrap_err().to_string(),
+            "expected a category at position 3"
+        );
     }
 
     #[test]
     fn no_categories_match_nothing() {
-        assert!(!eval_category_expr("assets", &has(&[])));
+        assert!(!eval("assets", &has(&[])));
     }
 }
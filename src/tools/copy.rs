@@ -0,0 +1,239 @@
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// Name of the file used to find out whether a target supports a strategy
+const PROBE_NAME: &str = ".eam-copy-probe";
+
+/// How files from the vault are placed into projects and engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyStrategy {
+    #[default]
+    Copy,
+    /// Copy-on-write clone (btrfs, xfs), the data is shared until one side changes it
+    Reflink,
+    /// Both paths point to the same file, editing it in a project also changes the vault
+    Hardlink,
+}
+
+impl CopyStrategy {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "reflink" => Self::Reflink,
+            "hardlink" => Self::Hardlink,
+            _ => Self::Copy,
+        }
+    }
+}
+
+/// How a file ended up at its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placed {
+    Reflinked,
+    Hardlinked,
+    Copied,
+}
+
+impl Placed {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Reflinked => "reflink",
+            Self::Hardlinked => "hardlink",
+            Self::Copied => "copy",
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(from: &Path, to: &Path) -> std::io::Result<()> {
+    let source = std::fs::File::open(from)?;
+    let target = std::fs::File::create(to)?;
+    if let Err(e) = rustix::fs::ioctl_ficlone(&target, &source) {
+        drop(target);
+        let _ = std::fs::remove_file(to);
+        return Err(e.into());
+    }
+    target.set_permissions(source.metadata()?.permissions())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_from: &Path, _to: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// Place a file using the strategy, falling back to a plain copy when the target does not support it
+pub fn place(from: &Path, to: &Path, strategy: CopyStrategy) -> std::io::Result<Placed> {
+    // Never write through an existing target, it may be a link to the vault
+    if to.symlink_metadata().is_ok() {
+        std::fs::remove_file(to)?;
+    }
+    let attempt = match strategy {
+        CopyStrategy::Copy => None,
+        CopyStrategy::Reflink => Some(reflink(from, to).map(|()| Placed::Reflinked)),
+        CopyStrategy::Hardlink => Some(std::fs::hard_link(from, to).map(|()| Placed::Hardlinked)),
+    };
+    match attempt {
+        Some(Ok(placed)) => Ok(placed),
+        Some(Err(e)) => {
+            debug!(
+                "Unable to {:?} {:?} to {:?}, copying instead: {}",
+                strategy, from, to, e
+            );
+            std::fs::copy(from, to).map(|_| Placed::Copied)
+        }
+        None => std::fs::copy(from, to).map(|_| Placed::Copied),
+    }
+}
+
+/// Find out how files would be placed from one directory into another, only probe files are written
+pub fn probe(source: &Path, target: &Path, strategy: CopyStrategy) -> Placed {
+    if strategy == CopyStrategy::Copy {
+        return Placed::Copied;
+    }
+    let (Some(source), Some(target)) = (
+        super::disk_space::existing_ancestor(source),
+        super::disk_space::existing_ancestor(target),
+    ) else {
+        return Placed::Copied;
+    };
+    let from = source.join(PROBE_NAME);
+    let to = target.join(format!("{PROBE_NAME}-target"));
+    let _ = std::fs::remove_file(&to);
+    if let Err(e) = std::fs::write(&from, b"probe") {
+        debug!("Unable to write copy probe {:?}: {}", from, e);
+        return Placed::Copied;
+    }
+    let placed = match strategy {
+        CopyStrategy::Copy => Placed::Copied,
+        CopyStrategy::Reflink => reflink(&from, &to).map_or(Placed::Copied, |()| Placed::Reflinked),
+        CopyStrategy::Hardlink => {
+            std::fs::hard_link(&from, &to).map_or(Placed::Copied, |()| Placed::Hardlinked)
+        }
+    };
+    let _ = std::fs::remove_file(&to);
+    let _ = std::fs::remove_file(&from);
+    placed
+}
+
+/// Expected result of placing files from the vault into one target directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub placed: Placed,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+impl Estimate {
+    /// Bytes that would not take additional space
+    pub fn saved(&self) -> u64 {
+        if self.placed == Placed::Copied {
+            0
+        } else {
+            self.bytes
+        }
+    }
+}
+
+/// Regular files below a directory with their size
+pub fn files(directory: &Path) -> Vec<(PathBuf, u64)> {
+    let mut result = Vec::new();
+    let mut pending = vec![directory.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+                result.push((entry.path(), size));
+            }
+        }
+    }
+    result
+}
+
+/// Dry run of placing `files` files of `bytes` in total from `source` into `target`
+pub fn estimate(
+    source: &Path,
+    target: &Path,
+    files: u64,
+    bytes: u64,
+    strategy: CopyStrategy,
+) -> Estimate {
+    Estimate {
+        source: source.to_path_buf(),
+        target: target.to_path_buf(),
+        placed: probe(source, target, strategy),
+        files,
+        bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("eam-copy-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("source/nested")).unwrap();
+        std::fs::write(dir.join("source/a.uasset"), b"first").unwrap();
+        std::fs::write(dir.join("source/nested/b.uasset"), b"second").unwrap();
+        dir
+    }
+
+    #[test]
+    fn strategy_from_setting() {
+        assert_eq!(CopyStrategy::from_setting("reflink"), CopyStrategy::Reflink);
+        assert_eq!(
+            CopyStrategy::from_setting("hardlink"),
+            CopyStrategy::Hardlink
+        );
+        assert_eq!(CopyStrategy::from_setting("bogus"), CopyStrategy::Copy);
+    }
+
+    #[test]
+    fn every_strategy_places_the_file() {
+        let dir = scratch("place");
+        let from = dir.join("source/a.uasset");
+        for (strategy, name) in [
+            (CopyStrategy::Copy, "copy"),
+            (CopyStrategy::Hardlink, "hardlink"),
+            (CopyStrategy::Reflink, "reflink"),
+        ] {
+            let to = dir.join(name);
+            // An existing target is replaced
+            std::fs::write(&to, b"old").unwrap();
+            place(&from, &to, strategy).unwrap();
+            assert_eq!(std::fs::read(&to).unwrap(), b"first");
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn dry_run_leaves_no_probe() {
+        let dir = scratch("estimate");
+        let source = dir.join("source");
+        let target = dir.join("project/Content");
+        let files = files(&source);
+        assert_eq!(files.len(), 2);
+        let bytes = files.iter().map(|(_, size)| size).sum();
+        let copied = estimate(&source, &target, 2, bytes, CopyStrategy::Copy);
+        assert_eq!(copied.bytes, 11);
+        assert_eq!(copied.saved(), 0);
+
+        // Same filesystem, so a hardlink works
+        let linked = estimate(&source, &target, 2, bytes, CopyStrategy::Hardlink);
+        assert_eq!(linked.placed, Placed::Hardlinked);
+        assert_eq!(linked.saved(), 11);
+        assert!(!source.join(PROBE_NAME).exists());
+        assert!(!dir.join(format!("{PROBE_NAME}-target")).exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
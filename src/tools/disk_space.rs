@@ -0,0 +1,126 @@
+use log::warn;
+use std::path::{Path, PathBuf};
+
+/// Unpacked size of compressed downloads is estimated as this multiple of the download size
+pub const EXTRACTION_FACTOR: u64 = 2;
+
+/// Volume that does not have enough free space for a download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortfall {
+    pub path: PathBuf,
+    pub required: u64,
+    pub available: u64,
+}
+
+impl Shortfall {
+    pub fn describe(&self) -> String {
+        let format = |size: u64| {
+            byte_unit::Byte::from_u64(size).get_appropriate_unit(byte_unit::UnitType::Decimal)
+        };
+        format!(
+            "{} needs {:.1} but only {:.1} is free",
+            self.path.display(),
+            format(self.required),
+            format(self.available)
+        )
+    }
+}
+
+/// Directories that are going to be created do not exist yet, their space is taken from the closest parent
+pub fn existing_ancestor(path: &Path) -> Option<&Path> {
+    let mut path = path;
+    while !path.exists() {
+        path = path.parent()?;
+    }
+    Some(path)
+}
+
+#[cfg(unix)]
+fn volume(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+fn volume(path: &Path) -> Option<u64> {
+    use std::hash::{Hash, Hasher};
+    let root = path.components().next()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    root.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Add up the space needed per volume, the first path seen on a volume represents it
+pub fn group_by_volume(requirements: &[(PathBuf, u64)]) -> Vec<(PathBuf, u64)> {
+    let mut result: Vec<(Option<u64>, PathBuf, u64)> = Vec::new();
+    for (path, size) in requirements {
+        let Some(existing) = existing_ancestor(path) else {
+            continue;
+        };
+        let id = volume(existing);
+        match result
+            .iter_mut()
+            .find(|(v, p, _)| (id.is_some() && *v == id) || p.as_path() == existing)
+        {
+            Some(entry) => entry.2 += size,
+            None => result.push((id, existing.to_path_buf(), *size)),
+        }
+    }
+    result.into_iter().map(|(_, p, s)| (p, s)).collect()
+}
+
+pub fn available(path: &Path) -> Option<u64> {
+    let existing = existing_ancestor(path)?;
+    match fs2::available_space(existing) {
+        Ok(space) => Some(space),
+        Err(e) => {
+            warn!("Unable to get free space of {:?}: {}", existing, e);
+            None
+        }
+    }
+}
+
+/// Volumes that would end up with less than `reserve` bytes free after writing the requirements
+pub fn check(requirements: &[(PathBuf, u64)], reserve: u64) -> Vec<Shortfall> {
+    group_by_volume(requirements)
+        .into_iter()
+        .filter_map(|(path, size)| {
+            let available = available(&path)?;
+            let required = size.saturating_add(reserve);
+            (available < required).then_some(Shortfall {
+                path,
+                required,
+                available,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ancestor_of_missing_directory() {
+        let tmp = std::env::temp_dir();
+        let missing = tmp.join("eam-missing-dir").join("nested");
+        assert_eq!(existing_ancestor(&missing), Some(tmp.as_path()));
+        assert_eq!(existing_ancestor(&tmp), Some(tmp.as_path()));
+    }
+
+    #[test]
+    fn same_volume_is_added_up() {
+        let tmp = std::env::temp_dir();
+        let grouped = group_by_volume(&[(tmp.join("eam-a"), 10), (tmp.join("eam-b").join("c"), 5)]);
+        assert_eq!(grouped, vec![(tmp, 15)]);
+    }
+
+    #[test]
+    fn shortfall_reported() {
+        let tmp = std::env::temp_dir();
+        assert!(check(&[(tmp.clone(), 0)], 0).is_empty());
+        let shortfalls = check(&[(tmp, u64::MAX / 2)], 0);
+        assert_eq!(shortfalls.len(), 1);
+        assert_eq!(shortfalls[0].required, u64::MAX / 2);
+    }
+}
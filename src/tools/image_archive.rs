@@ -0,0 +1,214 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Manifests bigger than this are not read into memory while looking for the layer list
+pub const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
+/// Kind of a local engine archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// Linux engine build as published by Epic
+    Zip,
+    /// `docker save` or OCI image layout tarball
+    Image,
+}
+
+impl ArchiveKind {
+    /// Detect the archive from its first bytes
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04") {
+            Some(Self::Zip)
+        } else if header.len() >= 262 && &header[257..262] == b"ustar" {
+            Some(Self::Image)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_gzip(header: &[u8]) -> bool {
+    header.starts_with(&[0x1f, 0x8b])
+}
+
+/// Engine version from the name of an Epic zip, falls back to the file name without extension
+pub fn zip_engine_version(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let re = Regex::new(r"Linux_Unreal_Engine_(\d\.\d+.\d+(?:_preview-\d+)?)").unwrap();
+    re.captures(name).map(|cap| cap[1].to_string()).or_else(|| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+    })
+}
+
+/// Image tag and ordered layer paths inside an image tarball
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageLayout {
+    pub tag: Option<String>,
+    pub layers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerManifest {
+    #[serde(default)]
+    repo_tags: Option<Vec<String>>,
+    layers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OciDescriptor {
+    digest: String,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct OciIndex {
+    #[serde(default)]
+    manifests: Vec<OciDescriptor>,
+    #[serde(default)]
+    layers: Vec<OciDescriptor>,
+}
+
+/// Path of a blob inside an OCI image layout
+pub fn blob_path(digest: &str) -> Option<String> {
+    let (algorithm, hash) = digest.split_once(':')?;
+    Some(format!("blobs/{algorithm}/{hash}"))
+}
+
+fn tag_from_reference(reference: &str) -> String {
+    // Registry hosts can contain a port, the tag is after the last slash
+    let name = reference.rsplit('/').next().unwrap_or(reference);
+    name.rsplit_once(':')
+        .map_or_else(|| reference.to_string(), |(_, tag)| tag.to_string())
+}
+
+/// Find the layers of an image from the small files of its tarball (key: path in the tarball).
+/// `docker save` writes a `manifest.json`, plain OCI layouts only have an `index.json`.
+pub fn image_layout(files: &HashMap<String, Vec<u8>>) -> Option<ImageLayout> {
+    if let Some(manifest) = files.get("manifest.json") {
+        if let Ok(manifests) = serde_json::from_slice::<Vec<DockerManifest>>(manifest) {
+            if let Some(m) = manifests.into_iter().next() {
+                return Some(ImageLayout {
+                    tag: m
+                        .repo_tags
+                        .and_then(|tags| tags.first().map(|t| tag_from_reference(t))),
+                    layers: m.layers,
+                });
+            }
+        }
+    }
+
+    let mut index: OciIndex = serde_json::from_slice(files.get("index.json")?).ok()?;
+    let mut tag = None;
+    // Follow nested indexes until we reach the image manifest
+    for _ in 0..4 {
+        if !index.layers.is_empty() {
+            return Some(ImageLayout {
+                tag,
+                layers: index
+                    .layers
+                    .iter()
+                    .filter_map(|l| blob_path(&l.digest))
+                    .collect(),
+            });
+        }
+        let descriptor = index.manifests.first()?;
+        if tag.is_none() {
+            tag = descriptor
+                .annotations
+                .get("io.containerd.image.name")
+                .or_else(|| {
+                    descriptor
+                        .annotations
+                        .get("org.opencontainers.image.ref.name")
+                })
+                .map(|r| tag_from_reference(r));
+        }
+        index = serde_json::from_slice(files.get(&blob_path(&descriptor.digest)?)?).ok()?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_archive_kind() {
+        assert_eq!(
+            ArchiveKind::detect(b"PK\x03\x04rest"),
+            Some(ArchiveKind::Zip)
+        );
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(ArchiveKind::detect(&tar), Some(ArchiveKind::Image));
+        assert_eq!(ArchiveKind::detect(b"plain text"), None);
+        assert!(is_gzip(&[0x1f, 0x8b, 8]));
+        assert!(!is_gzip(b"ustar"));
+    }
+
+    #[test]
+    fn version_from_zip_name() {
+        assert_eq!(
+            zip_engine_version(Path::new("/mnt/Linux_Unreal_Engine_5.4.1.zip")),
+            Some("5.4.1".to_string())
+        );
+        assert_eq!(
+            zip_engine_version(Path::new("Linux_Unreal_Engine_5.5.0_preview-1.zip")),
+            Some("5.5.0_preview-1".to_string())
+        );
+        assert_eq!(
+            zip_engine_version(Path::new("custom-build.zip")),
+            Some("custom-build".to_string())
+        );
+    }
+
+    #[test]
+    fn docker_archive_layout() {
+        let mut files = HashMap::new();
+        files.insert(
+            "manifest.json".to_string(),
+            br#"[{"Config":"c.json","RepoTags":["ghcr.io:443/epicgames/unreal-engine:dev-slim-5.4.1"],"Layers":["a/layer.tar","b/layer.tar"]}]"#.to_vec(),
+        );
+        assert_eq!(
+            image_layout(&files),
+            Some(ImageLayout {
+                tag: Some("dev-slim-5.4.1".to_string()),
+                layers: vec!["a/layer.tar".to_string(), "b/layer.tar".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn oci_layout() {
+        let mut files = HashMap::new();
+        files.insert(
+            "index.json".to_string(),
+            br#"{"manifests":[{"digest":"sha256:list","annotations":{"org.opencontainers.image.ref.name":"dev-5.3.2"}}]}"#.to_vec(),
+        );
+        files.insert(
+            "blobs/sha256/list".to_string(),
+            br#"{"manifests":[{"digest":"sha256:image"}]}"#.to_vec(),
+        );
+        files.insert(
+            "blobs/sha256/image".to_string(),
+            br#"{"config":{"digest":"sha256:c"},"layers":[{"digest":"sha256:one"},{"digest":"sha256:two"}]}"#.to_vec(),
+        );
+        assert_eq!(
+            image_layout(&files),
+            Some(ImageLayout {
+                tag: Some("dev-5.3.2".to_string()),
+                layers: vec![
+                    "blobs/sha256/one".to_string(),
+                    "blobs/sha256/two".to_string()
+                ],
+            })
+        );
+        files.remove("blobs/sha256/image");
+        assert_eq!(image_layout(&files), None);
+    }
+}
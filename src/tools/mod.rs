@@ -1,9 +1,19 @@
 use log::error;
 
 pub mod asset_info;
+pub mod asset_updates;
 pub mod auth;
+pub mod bandwidth;
 pub mod category_filter;
+pub mod copy;
+pub mod disk_space;
+pub mod image_archive;
 pub mod or;
+pub mod resume;
+pub mod retry;
+pub mod scheduler;
+pub mod search;
+pub mod vault_cleanup;
 
 /// Open a directory using the XDG portal (Flatpak-safe) with `opener` fallback.
 ///
@@ -0,0 +1,132 @@
+use log::{debug, warn};
+use reqwest::blocking::Response;
+use reqwest::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+
+/// Start downloading `link`, continuing after the `existing` bytes we already have.
+/// Returns the response together with the offset it starts at, which is 0 whenever
+/// the server does not support ranges and the download has to start over.
+pub fn resume_download(link: &Url, existing: u64) -> reqwest::Result<(Response, u64)> {
+    if existing > 0 {
+        debug!("Trying to resume {} from byte {}", link, existing);
+        let response = crate::HTTP_CLIENT
+            .get(link.clone())
+            .header(RANGE, format!("bytes={existing}-"))
+            .send()?;
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let start = response
+                    .headers()
+                    .get(CONTENT_RANGE)
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(content_range_start);
+                if start == Some(existing) {
+                    return Ok((response, existing));
+                }
+                warn!(
+                    "Unexpected Content-Range {:?} when resuming {}, starting over",
+                    response.headers().get(CONTENT_RANGE),
+                    link
+                );
+            }
+            StatusCode::OK => {
+                // Range got ignored, this already is the full file
+                if accepts_ranges(&response) {
+                    debug!("Server ignored the range request for {}", link);
+                } else {
+                    debug!("Server does not support ranges for {}", link);
+                }
+                return Ok((response.error_for_status()?, 0));
+            }
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                debug!("Existing data of {} is not usable, starting over", link);
+            }
+            status => {
+                warn!("Resuming {} failed with {}, starting over", link, status);
+            }
+        }
+    }
+    let response = crate::HTTP_CLIENT.get(link.clone()).send()?;
+    Ok((response.error_for_status()?, 0))
+}
+
+fn accepts_ranges(response: &Response) -> bool {
+    response
+        .headers()
+        .get(ACCEPT_RANGES)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("bytes"))
+}
+
+/// First byte position of a `Content-Range: bytes <start>-<end>/<size>` header
+pub fn content_range_start(header: &str) -> Option<u64> {
+    let range = header.trim().strip_prefix("bytes")?.trim_start();
+    let (positions, _size) = range.split_once('/')?;
+    let (start, end) = positions.split_once('-')?;
+    let start = start.trim().parse::<u64>().ok()?;
+    let end = end.trim().parse::<u64>().ok()?;
+    (start <= end).then_some(start)
+}
+
+/// Bytes of each download already counted as progress
+///
+/// Downloads report how far into the file they are, data received again after a failure,
+/// a resume or a server ignoring the range is only counted once.
+#[derive(Debug, Default)]
+pub struct Progress {
+    reached: HashMap<String, u128>,
+}
+
+impl Progress {
+    /// `id` now has `position` bytes, returns how many of them were not counted before
+    pub fn advance(&mut self, id: &str, position: u128) -> u128 {
+        let reached = self.reached.entry(id.to_string()).or_default();
+        let new = position.saturating_sub(*reached);
+        *reached = (*reached).max(position);
+        new
+    }
+
+    /// Stop tracking `id`, returns what was counted for it
+    pub fn forget(&mut self, id: &str) -> u128 {
+        self.reached.remove(id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_after_failure_is_counted_once() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.advance("chunk", 100), 100);
+        assert_eq!(progress.advance("chunk", 400), 300);
+        // The read failed, the retry starts at the 400 bytes on disk
+        assert_eq!(progress.advance("chunk", 400), 0);
+        assert_eq!(progress.advance("chunk", 500), 100);
+        // The server ignored the range and sends everything again
+        assert_eq!(progress.advance("chunk", 0), 0);
+        assert_eq!(progress.advance("chunk", 450), 0);
+        assert_eq!(progress.advance("chunk", 600), 100);
+        assert_eq!(progress.advance("other", 50), 50);
+        assert_eq!(progress.forget("chunk"), 600);
+        assert_eq!(progress.advance("chunk", 600), 600);
+    }
+
+    #[test]
+    fn content_range() {
+        assert_eq!(content_range_start("bytes 100-199/200"), Some(100));
+        assert_eq!(content_range_start("bytes 0-0/*"), Some(0));
+        assert_eq!(content_range_start(" bytes  5-10/11 "), Some(5));
+    }
+
+    #[test]
+    fn content_range_invalid() {
+        assert_eq!(content_range_start("bytes */200"), None);
+        assert_eq!(content_range_start("items 0-10/11"), None);
+        assert_eq!(content_range_start("bytes 20-10/30"), None);
+        assert_eq!(content_range_start("bytes 10-20"), None);
+        assert_eq!(content_range_start(""), None);
+    }
+}
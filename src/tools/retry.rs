@@ -0,0 +1,144 @@
+use rand::Rng;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Delay before the first retry
+pub const BASE_DELAY: Duration = Duration::from_secs(1);
+/// Retries never wait longer than this
+pub const MAX_DELAY: Duration = Duration::from_secs(120);
+/// Failures in a row after which a host is avoided if another mirror is available
+pub const HOST_FAILURE_THRESHOLD: u32 = 2;
+
+/// Exponential backoff for the `retry`-th retry (starting at 1) with `jitter` between 0 and 1.
+/// The delay is randomized between half and the full backoff so retries of many chunks do not line up.
+pub fn backoff(retry: u32, jitter: f64) -> Duration {
+    let exponent = retry.saturating_sub(1).min(16);
+    let full = BASE_DELAY.saturating_mul(1 << exponent).min(MAX_DELAY);
+    full.div_f64(2.0)
+        .saturating_add(full.div_f64(2.0).mul_f64(jitter.clamp(0.0, 1.0)))
+}
+
+pub fn jittered_backoff(retry: u32) -> Duration {
+    backoff(retry, rand::rng().random::<f64>())
+}
+
+/// Consecutive failures of every CDN host
+#[derive(Debug, Default, Clone)]
+pub struct HostHealth {
+    failures: HashMap<String, u32>,
+}
+
+impl HostHealth {
+    pub fn failed(&mut self, url: &Url) {
+        if let Some(host) = url.host_str() {
+            *self.failures.entry(host.to_string()).or_default() += 1;
+        }
+    }
+
+    pub fn succeeded(&mut self, url: &Url) {
+        if let Some(host) = url.host_str() {
+            self.failures.remove(host);
+        }
+    }
+
+    pub fn failures(&self, url: &Url) -> u32 {
+        url.host_str()
+            .and_then(|h| self.failures.get(h))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Keep using `current` while its host works, otherwise rotate to the mirror whose host failed the least.
+    /// `pick` chooses between equally good mirrors so the load is spread over them.
+    pub fn choose<'a>(
+        &self,
+        candidates: &'a [Url],
+        current: Option<&Url>,
+        pick: usize,
+    ) -> Option<&'a Url> {
+        if let Some(current) = current {
+            if self.failures(current) < HOST_FAILURE_THRESHOLD {
+                if let Some(same) = candidates.iter().find(|c| *c == current) {
+                    return Some(same);
+                }
+            }
+        }
+        let least = candidates.iter().map(|c| self.failures(c)).min()?;
+        let mut best: Vec<&Url> = candidates
+            .iter()
+            .filter(|c| self.failures(c) == least)
+            .collect();
+        let current_host = current.and_then(Url::host_str);
+        if best.iter().any(|c| c.host_str() != current_host) {
+            best.retain(|c| c.host_str() != current_host);
+        }
+        best.get(pick % best.len()).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(host: &str) -> Url {
+        Url::parse(&format!("https://{host}/chunk")).unwrap()
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        assert_eq!(backoff(1, 1.0), BASE_DELAY);
+        assert_eq!(backoff(1, 0.0), BASE_DELAY / 2);
+        assert_eq!(backoff(3, 1.0), BASE_DELAY * 4);
+        assert_eq!(backoff(30, 1.0), MAX_DELAY);
+        assert_eq!(backoff(30, 0.0), MAX_DELAY / 2);
+        let jittered = jittered_backoff(2);
+        assert!(jittered >= BASE_DELAY && jittered <= BASE_DELAY * 2);
+    }
+
+    #[test]
+    fn healthy_host_is_kept() {
+        let mirrors = [url("a"), url("b")];
+        let mut health = HostHealth::default();
+        health.failed(&mirrors[0]);
+        assert_eq!(
+            health.choose(&mirrors, Some(&mirrors[0]), 1),
+            Some(&mirrors[0])
+        );
+    }
+
+    #[test]
+    fn failing_host_is_rotated() {
+        let mirrors = [url("a"), url("b"), url("c")];
+        let mut health = HostHealth::default();
+        health.failed(&mirrors[0]);
+        health.failed(&mirrors[0]);
+        health.failed(&mirrors[1]);
+        assert_eq!(
+            health.choose(&mirrors, Some(&mirrors[0]), 0),
+            Some(&mirrors[2])
+        );
+        health.failed(&mirrors[2]);
+        // Everything failed, move away from the current host
+        assert_eq!(
+            health.choose(&mirrors, Some(&mirrors[0]), 0),
+            Some(&mirrors[1])
+        );
+        health.succeeded(&mirrors[0]);
+        assert_eq!(health.failures(&mirrors[0]), 0);
+    }
+
+    #[test]
+    fn single_mirror_is_retried() {
+        let mirrors = [url("a")];
+        let mut health = HostHealth::default();
+        for _ in 0..5 {
+            health.failed(&mirrors[0]);
+        }
+        assert_eq!(
+            health.choose(&mirrors, Some(&mirrors[0]), 3),
+            Some(&mirrors[0])
+        );
+        assert_eq!(health.choose(&[], None, 0), None);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use threadpool::ThreadPool;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    pub const fn value(self) -> i32 {
+        match self {
+            Self::High => 0,
+            Self::Normal => 1,
+            Self::Low => 2,
+        }
+    }
+
+    pub const fn from_value(value: i32) -> Self {
+        match value {
+            0 => Self::High,
+            2 => Self::Low,
+            _ => Self::Normal,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "high" => Self::High,
+            "low" => Self::Low,
+            _ => Self::Normal,
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct Queued {
+    keys: Vec<String>,
+    seq: u64,
+    job: Job,
+}
+
+#[derive(Default)]
+struct Inner {
+    jobs: Vec<Queued>,
+    /// Priority and list position of every download (key: download id)
+    order: HashMap<String, (Priority, u32)>,
+    seq: u64,
+}
+
+/// Orders work in front of a thread pool. Every pool slot picks the most important
+/// waiting job when it becomes free, so reordering takes effect at the next job boundary.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl std::fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let waiting = self.inner.lock().map(|i| i.jobs.len()).unwrap_or_default();
+        f.debug_struct("Scheduler")
+            .field("waiting", &waiting)
+            .finish()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the priorities and positions of all downloads
+    pub fn set_order(&self, order: HashMap<String, (Priority, u32)>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.order = order;
+        }
+    }
+
+    /// Queue `job` for the downloads in `keys`, shared work runs with the best priority of its owners
+    pub fn push<F>(&self, keys: Vec<String>, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.seq += 1;
+            let seq = inner.seq;
+            inner.jobs.push(Queued {
+                keys,
+                seq,
+                job: Box::new(job),
+            });
+        }
+    }
+
+    /// Take the most important waiting job
+    pub fn pop(&self) -> Option<Job> {
+        let mut inner = self.inner.lock().ok()?;
+        let best = inner
+            .jobs
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, queued)| (rank(&inner.order, &queued.keys), queued.seq))
+            .map(|(i, _)| i)?;
+        Some(inner.jobs.swap_remove(best).job)
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.inner.lock().map(|i| i.jobs.len()).unwrap_or_default()
+    }
+
+    /// Queue `job` and reserve a pool slot that will run whatever is most important at that time
+    pub fn execute<F>(&self, pool: &ThreadPool, keys: Vec<String>, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.push(keys, job);
+        let scheduler = self.clone();
+        pool.execute(move || {
+            if let Some(job) = scheduler.pop() {
+                job();
+            }
+        });
+    }
+}
+
+fn rank(order: &HashMap<String, (Priority, u32)>, keys: &[String]) -> (Priority, u32) {
+    keys.iter()
+        .filter_map(|k| order.get(k).copied())
+        .min()
+        .unwrap_or((Priority::Normal, u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_all(scheduler: &Scheduler) {
+        while let Some(job) = scheduler.pop() {
+            job();
+        }
+    }
+
+    fn order(entries: &[(&str, Priority, u32)]) -> HashMap<String, (Priority, u32)> {
+        entries
+            .iter()
+            .map(|(k, p, pos)| ((*k).to_string(), (*p, *pos)))
+            .collect()
+    }
+
+    fn push_recording(scheduler: &Scheduler, key: &str, out: &Arc<Mutex<Vec<String>>>) {
+        let out = out.clone();
+        let name = key.to_string();
+        scheduler.push(vec![key.to_string()], move || {
+            out.lock().unwrap().push(name)
+        });
+    }
+
+    #[test]
+    fn fifo_without_order() {
+        let scheduler = Scheduler::new();
+        let out = Arc::new(Mutex::new(Vec::new()));
+        for key in ["a", "b", "c"] {
+            push_recording(&scheduler, key, &out);
+        }
+        run_all(&scheduler);
+        assert_eq!(*out.lock().unwrap(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn priority_before_position() {
+        let scheduler = Scheduler::new();
+        scheduler.set_order(order(&[
+            ("engine", Priority::Normal, 0),
+            ("asset", Priority::High, 1),
+            ("later", Priority::Low, 2),
+        ]));
+        let out = Arc::new(Mutex::new(Vec::new()));
+        for key in ["later", "engine", "asset", "engine"] {
+            push_recording(&scheduler, key, &out);
+        }
+        run_all(&scheduler);
+        assert_eq!(
+            *out.lock().unwrap(),
+            vec!["asset", "engine", "engine", "later"]
+        );
+    }
+
+    #[test]
+    fn reorder_applies_to_waiting_jobs() {
+        let scheduler = Scheduler::new();
+        scheduler.set_order(order(&[
+            ("a", Priority::Normal, 0),
+            ("b", Priority::Normal, 1),
+        ]));
+        let out = Arc::new(Mutex::new(Vec::new()));
+        for key in ["a", "b", "a"] {
+            push_recording(&scheduler, key, &out);
+        }
+        let first = scheduler.pop().unwrap();
+        first();
+        scheduler.set_order(order(&[
+            ("a", Priority::Normal, 1),
+            ("b", Priority::Normal, 0),
+        ]));
+        run_all(&scheduler);
+        assert_eq!(*out.lock().unwrap(), vec!["a", "b", "a"]);
+        assert_eq!(scheduler.waiting(), 0);
+    }
+
+    #[test]
+    fn shared_job_uses_best_owner() {
+        let scheduler = Scheduler::new();
+        scheduler.set_order(order(&[
+            ("low", Priority::Low, 0),
+            ("normal", Priority::Normal, 1),
+            ("high", Priority::High, 2),
+        ]));
+        let out = Arc::new(Mutex::new(Vec::new()));
+        push_recording(&scheduler, "normal", &out);
+        let o = out.clone();
+        scheduler.push(vec!["low".to_string(), "high".to_string()], move || {
+            o.lock().unwrap().push("shared".to_string());
+        });
+        run_all(&scheduler);
+        assert_eq!(*out.lock().unwrap(), vec!["shared", "normal"]);
+    }
+
+    #[test]
+    fn priority_names() {
+        for p in [Priority::High, Priority::Normal, Priority::Low] {
+            assert_eq!(Priority::from_name(p.name()), p);
+            assert_eq!(Priority::from_value(p.value()), p);
+        }
+        assert_eq!(Priority::from_name("bogus"), Priority::Normal);
+    }
+}
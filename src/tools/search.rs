@@ -0,0 +1,331 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Part of an asset a word was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Title,
+    Seller,
+    Description,
+    LongDescription,
+    TechnicalDetails,
+    Category,
+    Tag,
+    /// Supported engine versions, stored as `5.3` without the `UE_` prefix
+    Engine,
+}
+
+impl Field {
+    /// How much a match in the field counts towards the rank
+    fn weight(self) -> f32 {
+        match self {
+            Self::Title => 8.0,
+            Self::Seller => 4.0,
+            Self::Category | Self::Tag => 3.0,
+            Self::Engine => 2.0,
+            Self::TechnicalDetails => 1.5,
+            Self::Description => 1.0,
+            Self::LongDescription => 0.5,
+        }
+    }
+
+    /// Fields a `name:` qualifier searches in
+    fn qualified(name: &str) -> Option<&'static [Self]> {
+        Some(match name {
+            "title" | "name" => &[Self::Title],
+            "seller" | "developer" | "author" => &[Self::Seller],
+            "description" | "desc" => &[Self::Description, Self::LongDescription],
+            "technical" | "tech" => &[Self::TechnicalDetails],
+            "category" | "cat" => &[Self::Category],
+            "tag" => &[Self::Tag],
+            "engine" | "ue" => &[Self::Engine],
+            _ => return None,
+        })
+    }
+}
+
+/// Parsed search text, e.g. `nanite seller:"quixel" engine:5.3`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Query {
+    /// Words that have to appear in any field
+    pub terms: Vec<String>,
+    /// Words that have to appear in specific fields
+    pub qualified: Vec<(&'static [Field], String)>,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Self {
+        let mut query = Self::default();
+        for part in split_quoted(text) {
+            let qualifier = part
+                .split_once(':')
+                .and_then(|(name, value)| Some((Field::qualified(&name.to_lowercase())?, value)));
+            match qualifier {
+                Some((fields, value)) if fields == [Field::Engine] => {
+                    let version = engine_version(value);
+                    if !version.is_empty() {
+                        query.qualified.push((fields, version));
+                    }
+                }
+                Some((fields, value)) => {
+                    for word in words(value) {
+                        query.qualified.push((fields, word));
+                    }
+                }
+                None => query.terms.extend(words(&part)),
+            }
+        }
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.qualified.is_empty()
+    }
+}
+
+/// Inverted index of the searchable text of the library, words are matched by prefix
+#[derive(Debug, Default)]
+pub struct Index {
+    ids: Vec<String>,
+    documents: HashMap<String, usize>,
+    /// Word and the documents and fields it appears in
+    words: BTreeMap<String, Vec<(usize, Field)>>,
+}
+
+impl Index {
+    /// Add a document, a document with the same id is replaced
+    pub fn add(&mut self, id: &str, fields: &[(Field, String)]) {
+        let document = if let Some(document) = self.documents.get(id) {
+            let document = *document;
+            for postings in self.words.values_mut() {
+                postings.retain(|(d, _)| *d != document);
+            }
+            document
+        } else {
+            self.ids.push(id.to_string());
+            self.documents.insert(id.to_string(), self.ids.len() - 1);
+            self.ids.len() - 1
+        };
+        let mut seen = HashSet::new();
+        for (field, text) in fields {
+            let tokens = if *field == Field::Engine {
+                vec![engine_version(text)]
+            } else {
+                words(&strip_markup(text))
+            };
+            for token in tokens {
+                if !token.is_empty() && seen.insert((token.clone(), *field)) {
+                    self.words
+                        .entry(token)
+                        .or_default()
+                        .push((document, *field));
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Ids of the documents containing every word of the query and their rank
+    pub fn search(&self, query: &Query) -> HashMap<String, f32> {
+        let lookups = query
+            .terms
+            .iter()
+            .map(|term| self.lookup(term, None))
+            .chain(
+                query
+                    .qualified
+                    .iter()
+                    .map(|(fields, word)| self.lookup(word, Some(fields))),
+            );
+        let mut scores: Option<HashMap<usize, f32>> = None;
+        for hits in lookups {
+            scores = Some(match scores {
+                None => hits,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(document, score)| {
+                        hits.get(&document).map(|hit| (document, score + hit))
+                    })
+                    .collect(),
+            });
+        }
+        scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(document, score)| (self.ids[document].clone(), score))
+            .collect()
+    }
+
+    fn lookup(&self, word: &str, fields: Option<&[Field]>) -> HashMap<usize, f32> {
+        let mut hits = HashMap::new();
+        for (token, postings) in self
+            .words
+            .range(word.to_string()..)
+            .take_while(|(token, _)| token.starts_with(word))
+        {
+            // Whole words rank above words that only start with the search term
+            let factor = if token == word { 1.0 } else { 0.5 };
+            for (document, field) in postings {
+                if fields.is_none_or(|f| f.contains(field)) {
+                    *hits.entry(*document).or_insert(0.0) += field.weight() * factor;
+                }
+            }
+        }
+        hits
+    }
+}
+
+/// `UE_5.3` and `5.3` both become `5.3`
+fn engine_version(text: &str) -> String {
+    let text = text.trim().to_lowercase();
+    text.strip_prefix("ue_")
+        .or_else(|| text.strip_prefix("ue"))
+        .unwrap_or(&text)
+        .to_string()
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Descriptions contain HTML, only the text is searchable
+fn strip_markup(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                result.push(' ');
+            }
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+    result.replace("&nbsp;", " ").replace("&amp;", "&")
+}
+
+/// Split on whitespace, keeping double quoted parts like `seller:"Quixel Megascans"` together
+fn split_quoted(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> Index {
+        let mut index = Index::default();
+        index.add(
+            "castle",
+            &[
+                (Field::Title, "Medieval Castle".to_string()),
+                (Field::Seller, "Stone Works".to_string()),
+                (
+                    Field::LongDescription,
+                    "<p>Modular walls with <b>Nanite</b> meshes</p>".to_string(),
+                ),
+                (Field::Engine, "UE_5.3".to_string()),
+                (Field::Engine, "UE_5.4".to_string()),
+            ],
+        );
+        index.add(
+            "nanite",
+            &[
+                (Field::Title, "Nanite Rocks".to_string()),
+                (Field::Seller, "Quixel Megascans".to_string()),
+                (Field::Engine, "UE_5.1".to_string()),
+            ],
+        );
+        index.add(
+            "blueprints",
+            &[
+                (Field::Title, "Door System".to_string()),
+                (Field::TechnicalDetails, "Blueprint only".to_string()),
+                (Field::Engine, "UE_4.27".to_string()),
+            ],
+        );
+        index
+    }
+
+    fn ranked(index: &Index, text: &str) -> Vec<String> {
+        let mut results: Vec<(String, f32)> =
+            index.search(&Query::parse(text)).into_iter().collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn parse_qualifiers() {
+        let query = Query::parse(r#"Rocks seller:"Quixel Megascans" engine:UE_5.3 bogus:x"#);
+        assert_eq!(query.terms, vec!["rocks", "bogus", "x"]);
+        assert_eq!(
+            query.qualified,
+            vec![
+                (&[Field::Seller][..], "quixel".to_string()),
+                (&[Field::Seller][..], "megascans".to_string()),
+                (&[Field::Engine][..], "5.3".to_string()),
+            ]
+        );
+        assert!(Query::parse("  ").is_empty());
+    }
+
+    #[test]
+    fn title_matches_rank_first() {
+        assert_eq!(ranked(&index(), "nanite"), vec!["nanite", "castle"]);
+    }
+
+    #[test]
+    fn prefix_and_all_words() {
+        let index = index();
+        assert_eq!(ranked(&index, "blue only"), vec!["blueprints"]);
+        assert_eq!(ranked(&index, "medieval rocks"), Vec::<String>::new());
+        assert_eq!(ranked(&index, "Med"), vec!["castle"]);
+    }
+
+    #[test]
+    fn qualified_fields() {
+        let index = index();
+        assert_eq!(ranked(&index, "seller:quixel"), vec!["nanite"]);
+        assert_eq!(ranked(&index, "seller:nanite"), Vec::<String>::new());
+        assert_eq!(ranked(&index, "engine:5.3"), vec!["castle"]);
+        assert_eq!(ranked(&index, "engine:5 nanite"), vec!["nanite", "castle"]);
+        assert_eq!(ranked(&index, "engine:4"), vec!["blueprints"]);
+    }
+
+    #[test]
+    fn markup_is_not_indexed() {
+        assert_eq!(ranked(&index(), "b"), vec!["blueprints"]);
+    }
+
+    #[test]
+    fn documents_are_replaced() {
+        let mut index = index();
+        index.add("nanite", &[(Field::Title, "Granite Rocks".to_string())]);
+        assert_eq!(ranked(&index, "nanite"), vec!["castle"]);
+        assert_eq!(ranked(&index, "granite"), vec!["nanite"]);
+    }
+}
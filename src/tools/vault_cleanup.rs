@@ -0,0 +1,216 @@
+use crate::tools::asset_updates::Release;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Group of things that can be removed from the vaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// A newer release of the same asset supporting the same engines is in a vault
+    Superseded,
+    /// The asset is no longer part of the library
+    NotOwned,
+    /// Chunks of aborted downloads and chunks in the store no download needs
+    Temporary,
+    Empty,
+}
+
+impl Category {
+    pub const ALL: [Self; 4] = [
+        Self::Superseded,
+        Self::NotOwned,
+        Self::Temporary,
+        Self::Empty,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Superseded => "Superseded releases",
+            Self::NotOwned => "Assets no longer in the library",
+            Self::Temporary => "Leftover download chunks",
+            Self::Empty => "Empty folders",
+        }
+    }
+}
+
+/// Directory that can be deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub category: Category,
+    pub path: PathBuf,
+    pub label: String,
+    pub size: u64,
+}
+
+/// Releases (newest first) that have a newer release supporting at least the same engines
+pub fn superseded(releases: &[Release]) -> Vec<&Release> {
+    releases
+        .iter()
+        .enumerate()
+        .filter(|(index, release)| {
+            releases[..*index].iter().any(|newer| {
+                release
+                    .compatible
+                    .iter()
+                    .all(|c| newer.compatible.contains(c))
+            })
+        })
+        .map(|(_, release)| release)
+        .collect()
+}
+
+/// Size of a file or of everything below a directory
+pub fn dir_size(path: &Path) -> u64 {
+    if path.is_file() {
+        return std::fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or_default(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Directories below `root` without any files, children come before their parents
+pub fn empty_dirs(root: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, result: &mut Vec<PathBuf>) -> bool {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return false;
+        };
+        let mut empty = true;
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                empty &= walk(&entry.path(), result);
+            } else {
+                empty = false;
+            }
+        }
+        if empty {
+            result.push(dir.to_path_buf());
+        }
+        empty
+    }
+
+    let mut result = Vec::new();
+    walk(root, &mut result);
+    result.retain(|p| p != root);
+    result
+}
+
+/// Files of a chunk store, named `<guid>_<hash>.chunk`, whose chunk is not in `referenced`
+pub fn orphaned_chunks(store: &Path, referenced: &HashSet<String>) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(store) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".chunk"))
+                .and_then(|n| n.split('_').next())
+                .is_some_and(|guid| !referenced.contains(guid))
+        })
+        .collect()
+}
+
+/// Delete a candidate, returns the freed size
+pub fn remove(candidate: &Candidate) -> std::io::Result<u64> {
+    // Already removed together with a directory it was in
+    if !candidate.path.exists() {
+        return Ok(0);
+    }
+    if candidate.category == Category::Empty {
+        // Something may have been put there since the scan
+        std::fs::remove_dir(&candidate.path)?;
+    } else if candidate.path.is_file() {
+        std::fs::remove_file(&candidate.path)?;
+    } else {
+        std::fs::remove_dir_all(&candidate.path)?;
+    }
+    Ok(candidate.size)
+}
+
+/// Remove directories before the things inside them, empty folders last and children first
+pub fn deletion_order(candidates: &mut [Candidate]) {
+    candidates.sort_by_key(|c| {
+        let empty = c.category == Category::Empty;
+        (
+            empty,
+            if empty {
+                0
+            } else {
+                c.path.components().count()
+            },
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::asset_updates::tests::release;
+
+    #[test]
+    fn older_releases_for_the_same_engines_are_superseded() {
+        let releases = vec![
+            release("new", &["UE_5.3", "UE_5.4"]),
+            release("ue4", &["UE_4.27"]),
+            release("old", &["UE_5.3"]),
+        ];
+        let apps: Vec<&str> = superseded(&releases)
+            .iter()
+            .map(|r| r.app.as_str())
+            .collect();
+        assert_eq!(apps, vec!["old"]);
+    }
+
+    #[test]
+    fn nested_empty_folders() {
+        let root = std::env::temp_dir().join(format!("eam-cleanup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/b/c")).unwrap();
+        std::fs::create_dir_all(root.join("d")).unwrap();
+        std::fs::write(root.join("d/file"), b"12345").unwrap();
+        assert_eq!(
+            empty_dirs(&root),
+            vec![root.join("a/b/c"), root.join("a/b"), root.join("a")]
+        );
+        assert_eq!(dir_size(&root), 5);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn unreferenced_chunks_are_orphaned() {
+        let store = std::env::temp_dir().join(format!("eam-chunks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&store);
+        std::fs::create_dir_all(&store).unwrap();
+        for name in [
+            "A1_00000000000000FF.chunk",
+            "B2_0000000000000001.chunk",
+            "notes.txt",
+        ] {
+            std::fs::write(store.join(name), b"123").unwrap();
+        }
+        let referenced: HashSet<String> = ["A1".to_string()].into_iter().collect();
+        let orphaned = orphaned_chunks(&store, &referenced);
+        assert_eq!(orphaned, vec![store.join("B2_0000000000000001.chunk")]);
+
+        let candidate = Candidate {
+            category: Category::Temporary,
+            path: orphaned[0].clone(),
+            label: "B2".to_string(),
+            size: dir_size(&orphaned[0]),
+        };
+        assert_eq!(remove(&candidate).unwrap(), 3);
+        assert!(!orphaned[0].exists());
+        let _ = std::fs::remove_dir_all(store);
+    }
+}
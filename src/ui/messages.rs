@@ -26,7 +26,8 @@ pub enum Msg {
     FabTaxonomyLoaded(Vec<FabTagGroup>),
     FabAddedToLibrary(String),
     #[cfg(target_os = "linux")]
-    DockerClient(ghregistry::Client),
+    /// Authenticated client and the GitHub user and token it logged in with
+    DockerClient(ghregistry::Client, (String, String)),
     #[cfg(target_os = "linux")]
     GithubAuthFailed,
 }
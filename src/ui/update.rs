@@ -1,6 +1,9 @@
 use crate::ui::messages::Msg;
+use crate::ui::widgets::download_manager::notifications::Notifications;
+#[cfg(target_os = "linux")]
+use crate::ui::widgets::download_manager::queue::Queue;
 use crate::window::EpicAssetManagerWindow;
-use gtk4::prelude::SettingsExt;
+use gtk4::prelude::{ObjectExt, SettingsExt};
 use gtk4::subclass::prelude::ObjectSubclassIsExt;
 use log::error;
 
@@ -65,14 +68,21 @@ impl Update for EpicAssetManagerWindow {
                 self_.logged_in_stack.process_epic_asset(&epic_asset);
             }
             #[cfg(target_os = "linux")]
-            Msg::DockerClient(dclient) => {
+            Msg::DockerClient(dclient, credentials) => {
                 self_.model.borrow_mut().dclient.replace(Some(dclient));
+                self_
+                    .model
+                    .borrow_mut()
+                    .dcredentials
+                    .replace(Some(credentials));
                 self.clear_notification("GithubAuth");
                 self_.logged_in_stack.update_docker();
+                self_.download_manager.restore_docker_downloads();
             }
             #[cfg(target_os = "linux")]
             Msg::GithubAuthFailed => {
                 self_.model.borrow_mut().dclient.replace(None);
+                self_.model.borrow_mut().dcredentials.replace(None);
                 if !self_
                     .model
                     .borrow()
@@ -92,6 +102,13 @@ impl Update for EpicAssetManagerWindow {
             Msg::LoginFailed(reason) => {
                 error!("{}", reason);
                 self.add_notification("login", &reason, gtk4::MessageType::Warning);
+                if self_.download_manager.property::<bool>("has-items") {
+                    self_.download_manager.notify_attention(
+                        "login",
+                        "Sign in again to continue downloading",
+                        &reason,
+                    );
+                }
                 self.show_login();
             }
             Msg::Logout => self.do_logout(),
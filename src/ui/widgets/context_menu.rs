@@ -0,0 +1,21 @@
+use gtk4::glib::clone;
+use gtk4::{self, gio, glib, prelude::*};
+
+/// Show `menu` as a context menu of `widget` where it was clicked
+pub fn popup_at(widget: &impl IsA<gtk4::Widget>, menu: &gio::Menu, x: f64, y: f64) {
+    let popover = gtk4::PopoverMenu::from_model(Some(menu));
+    popover.set_parent(widget);
+    popover.set_has_arrow(false);
+    popover.set_pointing_to(Some(&gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+    popover.connect_closed(|popover| {
+        // Activating a menu item happens after the popover closed
+        glib::idle_add_local_once(clone!(
+            #[weak]
+            popover,
+            move || {
+                popover.unparent();
+            }
+        ));
+    });
+    popover.popup();
+}
@@ -1,4 +1,10 @@
 use crate::tools::asset_info::Search;
+use crate::tools::copy::CopyStrategy;
+use crate::ui::widgets::download_manager::chunk_store::ChunkStore;
+use crate::ui::widgets::download_manager::disk_space::DiskSpace;
+use crate::ui::widgets::download_manager::network::Network;
+use crate::ui::widgets::download_manager::queue::{Queue, QueuedDownload};
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
 use crate::ui::widgets::download_manager::Msg::CancelChunk;
 use crate::ui::widgets::download_manager::{Msg, PostDownloadAction, ThreadMessages};
 use glib::clone;
@@ -10,6 +16,7 @@ use rand::Rng;
 use reqwest::Url;
 use sha1::digest::core_api::CoreWrapper;
 use sha1::{Digest, Sha1, Sha1Core};
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fmt::Write;
 use std::fs::File;
@@ -17,8 +24,8 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-const MAX_CHUNK_RETRIES: u32 = 3;
 pub const MAX_FILE_HASH_RETRIES: u32 = 2;
+const CHUNK_HEADER_MAX_SIZE: usize = 66;
 
 #[derive(Default, Debug, Clone)]
 pub struct DownloadedFile {
@@ -66,6 +73,18 @@ pub trait Asset {
         unimplemented!()
     }
 
+    /// Validate or download every file of the manifest, reusing unchanged files of a previous release
+    fn start_file_downloads(
+        &self,
+        _id: &str,
+        _manifest: &egs_api::api::types::download_manifest::DownloadManifest,
+        _target: &Path,
+        _to_vault: bool,
+        _plan: Option<std::sync::Arc<super::delta::DeltaPlan>>,
+    ) {
+        unimplemented!()
+    }
+
     /// Download individual files
     /// This is a third step in the asset download process
     /// Splits files into chunks
@@ -79,15 +98,31 @@ pub trait Asset {
         unimplemented!()
     }
 
+    /// Pick the mirror for the next attempt of a chunk, gives up after the retry limit
     fn redownload_chunk(&self, _link: &Url, _p: PathBuf, _g: &str) {
         unimplemented!()
     }
 
+    /// Remember why the chunk failed and try again after a backoff delay
+    fn chunk_failed(&self, _link: &Url, _p: PathBuf, _g: &str, _reason: String) {
+        unimplemented!()
+    }
+
+    /// Retry a chunk unless all downloads using it were paused or canceled in the meantime
+    fn retry_chunk(&self, _link: &Url, _p: PathBuf, _g: &str) {
+        unimplemented!()
+    }
+
     /// Download Chunks
     fn download_chunk(&self, _link: Url, _p: PathBuf, _g: String) {
         unimplemented!()
     }
 
+    /// Chunk failed verification, take back its progress and fetch it again
+    fn chunk_corrupted(&self, _link: &Url, _p: PathBuf, _g: &str) {
+        unimplemented!()
+    }
+
     fn remove_chunk(&self, _p: PathBuf, _g: String) {
         unimplemented!()
     }
@@ -127,6 +162,22 @@ pub trait Asset {
         unimplemented!()
     }
 
+    /// Forget the files of the asset waiting for chunks, chunks no other download needs are
+    /// canceled or released from the store
+    fn drop_asset_chunks(&self, _asset: &str, _guids: Vec<String>) {
+        unimplemented!()
+    }
+
+    /// Dry run of placing `source` into the targets with the copy strategy, the user is told
+    /// how much space it saves. `size` is the file count and bytes, counted from disk when `None`
+    fn estimate_copy(&self, _source: PathBuf, _targets: Vec<PathBuf>, _size: Option<(u64, u64)>) {
+        unimplemented!()
+    }
+
+    fn copy_estimated(&self, _estimates: &[crate::tools::copy::Estimate]) {
+        unimplemented!()
+    }
+
     /// Add a FAB asset for download
     /// Entry point for FAB marketplace asset downloads
     fn add_fab_asset_download(
@@ -173,6 +224,11 @@ trait AssetPriv {
     ) {
         unimplemented!()
     }
+
+    /// Remove the retry message from the item once the retried chunk made it
+    fn clear_retry_status(&self, _guid: &str) {
+        unimplemented!()
+    }
 }
 
 impl Asset for super::EpicDownloadManager {
@@ -229,9 +285,35 @@ impl Asset for super::EpicDownloadManager {
         item.set_property("label", asset.title.clone());
         item.set_property("target", target.clone());
         item.set_property("status", "initializing...".to_string());
+        let previous: Vec<String> = asset
+            .sorted_releases()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| {
+                r.id.as_deref() != Some(release_id.as_str())
+                    && r.app_id.as_deref() != Some(release_id.as_str())
+            })
+            .filter_map(|r| r.app_id)
+            .collect();
+        self_
+            .state
+            .borrow_mut()
+            .previous_releases
+            .insert(release_id.clone(), previous);
+        self.queue_download(
+            &release_id,
+            &QueuedDownload::Asset {
+                release_id: release_id.clone(),
+                asset: Box::new(asset.clone()),
+            },
+            target,
+            &item.actions(),
+        );
         self.load_thumbnail(release_id.clone(), asset.thumbnail());
 
         self_.downloads.append(&item);
+        self.check_network(&item);
+        self.update_schedule();
 
         self.set_property("has-items", self_.downloads.first_child().is_some());
 
@@ -292,44 +374,46 @@ impl Asset for super::EpicDownloadManager {
             let win_ = window.imp();
             let mut eg = win_.model.borrow().epic_games.borrow().clone();
             let id = release_id.clone();
-            self_.download_pool.execute(move || {
-                if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-                    return;
-                }
-                // release_id may be either a release.id (from version dropdown)
-                // or an app_id (from tile download button). Try both lookups.
-                let release_info = asset.release_info(&release_id).or_else(|| {
-                    asset.release_info.as_ref().and_then(|releases| {
-                        releases
-                            .iter()
-                            .find(|r| r.app_id.as_deref() == Some(&release_id))
-                            .cloned()
-                    })
-                });
-                if let Some(found_release) = release_info {
-                    let app_name = found_release.app_id.clone().unwrap_or_default();
-                    if let Some(manifest) = crate::RUNTIME.block_on(eg.asset_manifest(
-                        None,
-                        None,
-                        Some(asset.namespace.clone()),
-                        Some(asset.id.clone()),
-                        Some(app_name),
-                    )) {
-                        let d = crate::RUNTIME.block_on(eg.asset_download_manifests(manifest));
-                        let _ = sender.send_blocking((id, d));
+            self_
+                .scheduler
+                .execute(&self_.download_pool, vec![id.clone()], move || {
+                    if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    // release_id may be either a release.id (from version dropdown)
+                    // or an app_id (from tile download button). Try both lookups.
+                    let release_info = asset.release_info(&release_id).or_else(|| {
+                        asset.release_info.as_ref().and_then(|releases| {
+                            releases
+                                .iter()
+                                .find(|r| r.app_id.as_deref() == Some(&release_id))
+                                .cloned()
+                        })
+                    });
+                    if let Some(found_release) = release_info {
+                        let app_name = found_release.app_id.clone().unwrap_or_default();
+                        if let Some(manifest) = crate::RUNTIME.block_on(eg.asset_manifest(
+                            None,
+                            None,
+                            Some(asset.namespace.clone()),
+                            Some(asset.id.clone()),
+                            Some(app_name),
+                        )) {
+                            let d = crate::RUNTIME.block_on(eg.asset_download_manifests(manifest));
+                            let _ = sender.send_blocking((id, d));
+                        } else {
+                            warn!(
+                                "Failed to get asset manifest for release_id={}, app_id={:?}",
+                                release_id, found_release.app_id
+                            );
+                        }
                     } else {
                         warn!(
-                            "Failed to get asset manifest for release_id={}, app_id={:?}",
-                            release_id, found_release.app_id
+                            "No release_info found for '{}' in asset {:?}",
+                            release_id, asset.title
                         );
                     }
-                } else {
-                    warn!(
-                        "No release_info found for '{}' in asset {:?}",
-                        release_id, asset.title
-                    );
-                }
-            });
+                });
         } else {
             warn!("No window set on download manager");
         }
@@ -347,7 +431,30 @@ impl Asset for super::EpicDownloadManager {
             return;
         };
         if dm.is_empty() {
-            item.set_property("status", "Failed to get download manifests".to_string());
+            self.fail(&item, "unable to get download manifests");
+            return;
+        }
+        // Only the files the user picked for this asset are downloaded and verified
+        let selection = item
+            .asset()
+            .map(|a| crate::models::file_selection::FileSelection::load(&a))
+            .unwrap_or_default();
+        let selected: Vec<egs_api::api::types::download_manifest::DownloadManifest>;
+        let dm = if selection.is_empty() {
+            dm
+        } else {
+            selected = dm
+                .iter()
+                .map(|m| {
+                    let mut m = m.clone();
+                    selection.apply(&mut m);
+                    m
+                })
+                .collect();
+            &selected
+        };
+        if dm[0].file_manifest_list.is_empty() {
+            self.discard(&item, "no files selected for download");
             return;
         }
         let mut targets: Vec<(String, bool)> = Vec::new();
@@ -367,7 +474,9 @@ impl Asset for super::EpicDownloadManager {
             }
         };
 
-        let target = if to_vault || targets.is_empty() {
+        // Without any copy target the download still lands in the vault
+        to_vault |= targets.is_empty();
+        let target = if to_vault {
             let mut v = match self.unreal_vault_dir(id) {
                 None => {
                     return;
@@ -386,6 +495,28 @@ impl Asset for super::EpicDownloadManager {
                 }
             }
         };
+        let file_size: u64 = dm[0]
+            .file_manifest_list
+            .iter()
+            .map(|f| u64::try_from(f.size()).unwrap_or_default())
+            .sum();
+        let mut requirements = vec![
+            (
+                self.chunk_store_dir(),
+                u64::try_from(dm[0].total_download_size()).unwrap_or_default(),
+            ),
+            (target.clone(), file_size),
+        ];
+        requirements.extend(targets.iter().map(|(t, _)| (PathBuf::from(t), file_size)));
+        if !self.preflight(id, &requirements) {
+            return;
+        }
+        // Copies into projects are made from the download target
+        self.estimate_copy(
+            target.clone(),
+            targets.iter().map(|(t, _)| PathBuf::from(t)).collect(),
+            Some((dm[0].file_manifest_list.len() as u64, file_size)),
+        );
         let t = target.clone();
         let manifest = dm[0].clone();
         // Create target directory in the vault and save manifests to it
@@ -402,6 +533,12 @@ impl Asset for super::EpicDownloadManager {
         {
             let mut state = self_.state.borrow_mut();
             for manifest in dm {
+                state.chunk_hashes.extend(
+                    manifest
+                        .chunk_hash_list
+                        .iter()
+                        .map(|(guid, hash)| (guid.clone(), *hash)),
+                );
                 for m in manifest.files().values() {
                     for chunk in m.file_chunk_parts.clone() {
                         if let Some(url) = chunk.link {
@@ -420,19 +557,82 @@ impl Asset for super::EpicDownloadManager {
         }
 
         item.set_property("status", "validating".to_string());
-        for (filename, manifest) in dm[0].files() {
+        let previous = self_
+            .state
+            .borrow()
+            .previous_releases
+            .get(id)
+            .cloned()
+            .unwrap_or_default();
+        if previous.is_empty() {
+            self.start_file_downloads(id, &dm[0], &target, to_vault, None);
+            return;
+        }
+        let vaults: Vec<String> = self_
+            .settings
+            .strv("unreal-vault-directories")
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect();
+        let r_id = id.to_string();
+        let manifest = dm[0].clone();
+        let sender = self_.sender.clone();
+        self_.download_pool.execute(move || {
+            let plan = super::delta::plan_update(&vaults, &previous, &manifest);
+            let _ = sender.send_blocking(Msg::DeltaPlanned(
+                r_id,
+                target,
+                to_vault,
+                Box::new(manifest),
+                plan.map(std::sync::Arc::new),
+            ));
+        });
+    }
+
+    fn start_file_downloads(
+        &self,
+        id: &str,
+        manifest: &egs_api::api::types::download_manifest::DownloadManifest,
+        target: &Path,
+        to_vault: bool,
+        plan: Option<std::sync::Arc<super::delta::DeltaPlan>>,
+    ) {
+        let self_ = self.imp();
+        // Only link into our own vault, copy targets must stay independent of it
+        let hard_link = to_vault;
+        let repair = self_.state.borrow_mut().repair_files.remove(id);
+        for (filename, m) in manifest.files() {
             debug!("Starting download of {} file {}", id, filename);
             let r_id = id.to_string();
-            let r_name = dm[0].app_name_string.clone();
-            let f_name = filename.clone();
+            let r_name = manifest.app_name_string.clone();
             let sender = self_.sender.clone();
-
-            let m = manifest.clone();
-            let full_path = target.clone().as_path().join(filename);
-
-            self_.download_pool.execute(move || {
-                initiate_file_download(&r_id, &r_name, &f_name, &sender, m, &full_path);
-            });
+            let full_path = target.join(&filename);
+            if let Some(broken) = &repair {
+                // Everything else was verified just now
+                let _ = sender.send_blocking(if broken.contains(&filename) {
+                    Msg::PerformAssetDownload(r_id, r_name, filename, m)
+                } else {
+                    Msg::FileAlreadyDownloaded(
+                        r_id,
+                        m.size(),
+                        full_path.to_string_lossy().to_string(),
+                        filename,
+                    )
+                });
+                continue;
+            }
+            let plan = plan.clone();
+
+            self_
+                .scheduler
+                .execute(&self_.download_pool, vec![r_id.clone()], move || {
+                    if let Some(from) = plan.as_ref().and_then(|p| p.reuse.get(&m.file_hash)) {
+                        if super::delta::reuse_file(from, &full_path, hard_link) {
+                            debug!("Reusing {:?} for {}", from, filename);
+                        }
+                    }
+                    initiate_file_download(&r_id, &r_name, &filename, &sender, m, &full_path);
+                });
         }
     }
     /// Download individual files
@@ -447,145 +647,185 @@ impl Asset for super::EpicDownloadManager {
     ) {
         debug!("Downloading file {} for {}", filename, id);
         let self_ = self.imp();
-        let Some(_item) = self.get_item(&id) else {
+        let Some(item) = self.get_item(&id) else {
             return;
         };
-        let vaults = self_.settings.strv("unreal-vault-directories");
-        let mut target = std::path::PathBuf::from(vaults.first().map_or_else(
-            || {
-                self_
-                    .settings
-                    .string("temporary-download-directory")
-                    .to_string()
-            },
-            std::string::ToString::to_string,
-        ));
-        target.push(release.clone());
-        target.push("temp");
+        let paused = item.paused() || item.failed();
         let full_filename = format!("{id}/{release}/{filename}");
-        {
-            let mut state = self_.state.borrow_mut();
-            state.downloaded_files.insert(
-                full_filename.clone(),
-                DownloadedFile {
-                    asset: id.clone(),
-                    release,
-                    name: filename,
-                    chunks: manifest.file_chunk_parts.clone(),
-                    finished_chunks: vec![],
-                    hash: manifest.file_hash,
-                },
-            );
-        }
+        let mut file = DownloadedFile {
+            asset: id.clone(),
+            release,
+            name: filename,
+            chunks: manifest.file_chunk_parts.clone(),
+            finished_chunks: vec![],
+            hash: manifest.file_hash,
+        };
         let sender = self_.sender.clone();
         for chunk in manifest.file_chunk_parts {
-            let should_redownload = {
+            let (should_redownload, completed) = {
                 let mut state = self_.state.borrow_mut();
                 state
                     .asset_guids
                     .entry(id.clone())
                     .or_default()
                     .push(chunk.guid.clone());
+                let completed = state.completed_chunks.contains(&chunk.guid);
                 match state.downloaded_chunks.get_mut(&chunk.guid) {
                     None => {
                         state
                             .downloaded_chunks
                             .insert(chunk.guid.clone(), vec![full_filename.clone()]);
-                        true
+                        (true, false)
                     }
                     Some(files) => {
                         files.push(full_filename.clone());
-                        false
+                        (false, completed)
                     }
                 }
             };
+            if completed {
+                // Another download already fetched this chunk into the store
+                file.finished_chunks.push(chunk.clone());
+            }
             if should_redownload {
-                let mut p = target.clone();
+                let p = self.chunk_path(&chunk.guid);
                 let g = chunk.guid.clone();
-                p.push(format!("{g}.chunk"));
-                let _ =
-                    sender.send_blocking(Msg::RedownloadChunk(Url::parse("unix:/").unwrap(), p, g));
+                if paused {
+                    self.pause_asset_chunk(Url::parse("unix:/").unwrap(), p, g);
+                } else {
+                    let _ = sender.send_blocking(Msg::RedownloadChunk(
+                        Url::parse("unix:/").unwrap(),
+                        p,
+                        g,
+                    ));
+                }
             }
         }
+        let ready = !file.chunks.is_empty() && file.finished_chunks.len() == file.chunks.len();
+        self_
+            .state
+            .borrow_mut()
+            .downloaded_files
+            .insert(full_filename.clone(), file.clone());
+        if ready {
+            self.extract_file_from_chunks(&mut Vec::new(), &full_filename, &mut file);
+        }
     }
 
     fn redownload_chunk(&self, link: &Url, p: PathBuf, g: &str) {
         let self_ = self.imp();
         let sender = self_.sender.clone();
+        let limit = self.retry_limit();
         let mut state = self_.state.borrow_mut();
 
         let retry_count = state.chunk_retries.entry(g.to_string()).or_insert(0);
         *retry_count += 1;
-
-        if *retry_count > MAX_CHUNK_RETRIES {
-            let attempt = *retry_count;
+        // The first attempt is the regular download
+        let retries = *retry_count - 1;
+        let asset_id = state
+            .downloaded_chunks
+            .get(g)
+            .and_then(|files| files.first())
+            .and_then(|file| state.downloaded_files.get(file))
+            .map(|f| f.asset.clone());
+        let item = asset_id.and_then(|id| state.download_items.get(&id).cloned());
+
+        if retries > limit {
+            let reason = state
+                .chunk_errors
+                .remove(g)
+                .unwrap_or_else(|| "unknown error".to_string());
             warn!(
-                "Chunk {} exceeded max retries ({}/{}), giving up",
-                g, attempt, MAX_CHUNK_RETRIES
+                "Chunk {} exceeded max retries ({}), giving up: {}",
+                g, limit, reason
             );
-            let asset_id = state
-                .downloaded_chunks
-                .get(g)
-                .and_then(|files| files.first())
-                .and_then(|file| state.downloaded_files.get(file))
-                .map(|f| f.asset.clone());
+            // Parked like a paused chunk so retrying the download picks it up again
+            state.chunk_retries.remove(g);
+            state
+                .paused_asset_chunks
+                .entry(g.to_string())
+                .or_default()
+                .push((link.clone(), p));
             drop(state);
-            if let Some(asset_id) = asset_id {
-                if let Some(item) = self.get_item(&asset_id) {
-                    item.set_property(
-                        "status",
-                        format!(
-                            "Failed: chunk download failed after {} retries",
-                            MAX_CHUNK_RETRIES
-                        ),
-                    );
-                }
+            if let Some(item) = item {
+                self.fail(
+                    &item,
+                    &format!("chunk download failed after {limit} retries: {reason}"),
+                );
             }
             return;
         }
 
-        let attempt = *retry_count;
-        debug!(
-            "Retrying chunk {} (attempt {}/{})",
-            g, attempt, MAX_CHUNK_RETRIES
-        );
+        if retries > 0 {
+            debug!("Retrying chunk {} (attempt {}/{})", g, retries, limit);
+            if let Some(item) = &item {
+                item.set_property("status", format!("Retrying chunk {g} ({retries}/{limit})"));
+            }
+        }
 
-        match state.chunk_urls.get_mut(g) {
-            None => {
-                let _ =
-                    sender.send_blocking(Msg::PerformChunkDownload(link.clone(), p, g.to_string()));
+        let candidates = state.chunk_urls.get(g).cloned().unwrap_or_default();
+        let current = link.has_host().then_some(link);
+        let pick = rand::rng().random_range(0..candidates.len().max(1));
+        let next = state
+            .host_health
+            .choose(&candidates, current, pick)
+            .cloned()
+            .unwrap_or_else(|| link.clone());
+        drop(state);
+        if let Some(current) = current {
+            if current.host_str() != next.host_str() {
+                info!(
+                    "Switching chunk {} from {} to {}",
+                    g,
+                    current.host_str().unwrap_or_default(),
+                    next.host_str().unwrap_or_default()
+                );
             }
-            Some(v) => {
-                v.retain(|x| !x.eq(link));
-                if v.is_empty() {
-                    let _ = sender.send_blocking(Msg::PerformChunkDownload(
-                        link.clone(),
-                        p.clone(),
-                        g.to_string(),
-                    ));
-                } else {
-                    let mut rng = rand::rng();
-                    let index = rng.random_range(0..v.len());
-                    let new: Option<&Url> = v.get(index);
-                    match new {
-                        None => {
-                            let _ = sender.send_blocking(Msg::PerformChunkDownload(
-                                link.clone(),
-                                p,
-                                g.to_string(),
-                            ));
-                        }
-                        Some(u) => {
-                            let _ = sender.send_blocking(Msg::PerformChunkDownload(
-                                u.clone(),
-                                p,
-                                g.to_string(),
-                            ));
-                        }
-                    }
+        }
+        let _ = sender.send_blocking(Msg::PerformChunkDownload(next, p, g.to_string()));
+    }
+
+    fn chunk_failed(&self, link: &Url, p: PathBuf, g: &str, reason: String) {
+        let self_ = self.imp();
+        let retry = {
+            let mut state = self_.state.borrow_mut();
+            state.host_health.failed(link);
+            state.chunk_links.remove(g);
+            state.chunk_errors.insert(g.to_string(), reason);
+            state.chunk_retries.get(g).copied().unwrap_or(1)
+        };
+        let delay = crate::tools::retry::jittered_backoff(retry);
+        debug!("Retrying chunk {} in {:?}", g, delay);
+        let link = link.clone();
+        let g = g.to_string();
+        glib::timeout_add_local_once(
+            delay,
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                move || {
+                    dm.retry_chunk(&link, p, &g);
                 }
-            }
+            ),
+        );
+    }
+
+    fn retry_chunk(&self, link: &Url, p: PathBuf, g: &str) {
+        let items: Vec<_> = self
+            .chunk_owners(g)
+            .iter()
+            .filter_map(|id| self.get_item(id))
+            .filter(|item| !item.canceled())
+            .collect();
+        if items.is_empty() {
+            debug!("Chunk {} is no longer needed", g);
+            return;
+        }
+        if items.iter().all(|item| item.paused() || item.failed()) {
+            self.pause_asset_chunk(link.clone(), p, g.to_string());
+            return;
         }
+        self.redownload_chunk(link, p, g);
     }
 
     /// Download Chunks
@@ -597,94 +837,162 @@ impl Asset for super::EpicDownloadManager {
         let (send, recv) = std::sync::mpsc::channel::<ThreadMessages>();
         self.add_thread_sender(g.clone(), send);
         let sender = self_.sender.clone();
-        self_.download_pool.execute(move || {
-            if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-                return;
-            };
-            if let Ok(m) = recv.try_recv() {
-                process_thread_message(&link, &p, &g, &sender, &m);
-                return;
-            }
-            debug!(
-                "Downloading chunk {} from {} to {:?}",
-                g,
-                link.to_string(),
-                p
-            );
-            let Some(parent) = p.parent() else {
-                error!("Chunk path has no parent: {:?}", p);
-                return;
-            };
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                error!("Failed to create chunk directory {:?}: {}", parent, e);
-                return;
-            }
-            let mut client = match crate::HTTP_CLIENT.get(link.clone()).send() {
-                Ok(c) => c,
-                Err(e) => {
-                    error!("Failed to start chunk download, trying again later: {}", e);
-                    let _ = sender.send_blocking(Msg::RedownloadChunk(
-                        link.clone(),
-                        p.clone(),
-                        g.clone(),
-                    ));
+        let hash = {
+            let mut state = self_.state.borrow_mut();
+            state.chunk_links.insert(g.clone(), link.clone());
+            state.chunk_hashes.get(&g).copied()
+        };
+        let owners = self.chunk_owners(&g);
+        self_
+            .scheduler
+            .execute(&self_.download_pool, owners, move || {
+                if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+                    return;
+                };
+                if let Ok(m) = recv.try_recv() {
+                    process_thread_message(&link, &p, &g, &sender, &m);
                     return;
                 }
-            };
-            let mut buffer: [u8; 1024] = [0; 1024];
-            let mut downloaded: u128 = 0;
-            let mut file = match File::create(&p) {
-                Ok(file) => file,
-                Err(e) => {
-                    error!("Unable to create chunk file {:?}: {}", p, e);
+                debug!(
+                    "Downloading chunk {} from {} to {:?}",
+                    g,
+                    link.to_string(),
+                    p
+                );
+                let Some(parent) = p.parent() else {
+                    error!("Chunk path has no parent: {:?}", p);
+                    return;
+                };
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    error!("Failed to create chunk directory {:?}: {}", parent, e);
                     return;
                 }
-            };
-            loop {
-                if let Ok(m) = recv.try_recv() {
-                    process_thread_message(&link, &p, &g, &sender, &m);
+                if let Ok(size) = verify_chunk(&p, &g, hash) {
+                    debug!("Reusing already downloaded chunk {}", g);
+                    let _ =
+                        sender.send_blocking(Msg::ChunkDownloadProgress(g.clone(), size, false));
+                    let _ = sender.send_blocking(Msg::ChunkDownloadProgress(g, size, true));
                     return;
                 }
-                match client.read(&mut buffer) {
-                    Ok(size) => {
-                        if let Ok(m) = recv.try_recv() {
-                            process_thread_message(&link, &p, &g, &sender, &m);
+                let existing = std::fs::metadata(&p).map(|m| m.len()).unwrap_or_default();
+                let (mut client, offset) =
+                    match crate::tools::resume::resume_download(&link, existing) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            error!("Failed to start chunk download, trying again later: {}", e);
+                            let _ = sender.send_blocking(Msg::ChunkFailed(
+                                link.clone(),
+                                p.clone(),
+                                g.clone(),
+                                e.to_string(),
+                            ));
                             return;
                         }
-                        if size > 0 {
-                            downloaded += size as u128;
-                            if let Err(e) = std::io::Write::write_all(&mut file, &buffer[0..size]) {
-                                error!("Failed to write chunk data for {}: {}", g, e);
+                    };
+                let mut buffer: [u8; 1024] = [0; 1024];
+                let mut downloaded: u128 = u128::from(offset);
+                let file = if offset > 0 {
+                    File::options().append(true).open(&p)
+                } else {
+                    File::create(&p)
+                };
+                let mut file = match file {
+                    Ok(file) => file,
+                    Err(e) => {
+                        error!("Unable to create chunk file {:?}: {}", p, e);
+                        return;
+                    }
+                };
+                if offset > 0 {
+                    let _ = sender.send_blocking(Msg::ChunkDownloadProgress(
+                        g.clone(),
+                        u128::from(offset),
+                        false,
+                    ));
+                }
+                loop {
+                    if let Ok(m) = recv.try_recv() {
+                        process_thread_message(&link, &p, &g, &sender, &m);
+                        return;
+                    }
+                    match client.read(&mut buffer) {
+                        Ok(size) => {
+                            if let Ok(m) = recv.try_recv() {
+                                process_thread_message(&link, &p, &g, &sender, &m);
                                 return;
                             }
-                            let _ = sender.send_blocking(Msg::ChunkDownloadProgress(
-                                g.clone(),
-                                size as u128,
-                                false,
+                            if size > 0 {
+                                crate::DOWNLOAD_LIMITER.throttle(size);
+                                downloaded += size as u128;
+                                if let Err(e) =
+                                    std::io::Write::write_all(&mut file, &buffer[0..size])
+                                {
+                                    error!("Failed to write chunk data for {}: {}", g, e);
+                                    return;
+                                }
+                                let _ = sender.send_blocking(Msg::ChunkDownloadProgress(
+                                    g.clone(),
+                                    downloaded,
+                                    false,
+                                ));
+                            } else {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            // Whatever we got so far is kept and the download resumes from there
+                            error!("Download error, trying again later: {:?}", e);
+                            let _ = sender.send_blocking(Msg::ChunkFailed(
+                                link.clone(),
+                                p,
+                                g,
+                                e.to_string(),
                             ));
-                        } else {
-                            break;
+                            return;
                         }
                     }
-                    Err(e) => {
-                        error!("Download error: {:?}", e);
-                        break;
+                }
+                if let Err(e) = verify_chunk(&p, &g, hash) {
+                    warn!("Chunk {} failed verification: {}", g, e);
+                    if let Err(e) = std::fs::remove_file(&p) {
+                        error!("Unable to remove corrupted chunk {:?}: {}", p, e);
                     }
+                    let _ = sender.send_blocking(Msg::ChunkCorrupted(link, p, g));
+                    return;
                 }
+                let _ =
+                    sender.send_blocking(Msg::ChunkDownloadProgress(g.clone(), downloaded, true));
+            });
+    }
+
+    fn chunk_corrupted(&self, link: &Url, p: PathBuf, g: &str) {
+        let self_ = self.imp();
+        let downloaded = self_.state.borrow_mut().chunk_progress.forget(g);
+        let files = self_
+            .state
+            .borrow()
+            .downloaded_chunks
+            .get(g)
+            .cloned()
+            .unwrap_or_default();
+        // Progress is only reported to the first item using the chunk
+        for file in files {
+            let asset_id = {
+                let state = self_.state.borrow();
+                state.downloaded_files.get(&file).map(|f| f.asset.clone())
+            };
+            if let Some(item) = asset_id.and_then(|id| self.get_item(&id)) {
+                item.remove_downloaded_size(downloaded);
+                break;
             }
-            let _ = sender.send_blocking(Msg::ChunkDownloadProgress(g.clone(), downloaded, true));
-        });
+        }
+        self.chunk_failed(link, p, g, "downloaded data is corrupted".to_string());
     }
 
     fn remove_chunk(&self, path: PathBuf, _g: String) {
         if let Err(e) = std::fs::remove_file(&path) {
             warn!("Unable to remove chunk {:?}", e);
         };
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::remove_dir(parent) {
-                info!("Unable to remove chunk directory {:?}", e);
-            };
-        };
     }
 
     fn chunk_progress_report(&self, guid: &str, progress: u128, finished: bool) {
@@ -693,10 +1001,19 @@ impl Asset for super::EpicDownloadManager {
             debug!("Finished downloading {}", guid);
             let mut finished_files: Vec<String> = Vec::new();
             let mut to_extract: Vec<(String, DownloadedFile)> = Vec::new();
+            let mut retried = false;
             {
                 let mut state = self_.state.borrow_mut();
                 state.chunk_urls.remove(guid);
-                state.chunk_retries.remove(guid);
+                state.chunk_errors.remove(guid);
+                state.chunk_progress.forget(guid);
+                if let Some(link) = state.chunk_links.remove(guid) {
+                    state.host_health.succeeded(&link);
+                }
+                if state.chunk_retries.remove(guid).is_some_and(|r| r > 1) {
+                    retried = true;
+                }
+                state.completed_chunks.insert(guid.to_string());
                 let affected_files = state
                     .downloaded_chunks
                     .get(guid)
@@ -717,6 +1034,9 @@ impl Asset for super::EpicDownloadManager {
                     }
                 }
             }
+            if retried {
+                self.clear_retry_status(guid);
+            }
             for (file, mut file_details) in to_extract {
                 self.extract_file_from_chunks(&mut finished_files, &file, &mut file_details);
             }
@@ -735,6 +1055,18 @@ impl Asset for super::EpicDownloadManager {
                         let Some(item) = self.get_item(&asset_id) else {
                             break;
                         };
+                        // Bytes arriving while paused get counted with the next report
+                        if item.paused() || item.canceled() {
+                            break;
+                        }
+                        let progress = self_
+                            .state
+                            .borrow_mut()
+                            .chunk_progress
+                            .advance(guid, progress);
+                        if progress == 0 {
+                            break;
+                        }
                         item.add_downloaded_size(progress);
                         self.emit_by_name::<()>("tick", &[]);
 
@@ -795,8 +1127,14 @@ impl Asset for super::EpicDownloadManager {
             }
         }
 
+        let strategy = CopyStrategy::from_setting(&self_.settings.string("copy-strategy"));
         self_.file_pool.execute(move || {
-            copy_files(&PathBuf::from_str(&fullname).unwrap(), targets, &filename);
+            copy_files(
+                &PathBuf::from_str(&fullname).unwrap(),
+                targets,
+                &filename,
+                strategy,
+            );
         });
 
         item.add_downloaded_size(progress);
@@ -832,19 +1170,12 @@ impl Asset for super::EpicDownloadManager {
                 }
                 if let Some(r) = item.release() {
                     state.download_items.remove(&r);
-                }
-                for guid in guids {
-                    state.chunk_retries.remove(&guid);
-                    for file in state
-                        .downloaded_chunks
-                        .remove(guid.as_str())
-                        .unwrap_or_default()
-                    {
-                        state.file_retries.remove(&file);
-                        state.downloaded_files.remove(&file);
-                    }
+                    state.previous_releases.remove(&r);
+                    state.repair_files.remove(&r);
                 }
             }
+            drop(state);
+            self.drop_asset_chunks(&asset, guids);
         }
     }
 
@@ -886,16 +1217,13 @@ impl Asset for super::EpicDownloadManager {
     fn cancel_asset_download(&self, asset: String) {
         let self_ = self.imp();
         let item = self.get_item(&asset);
-        let paused = item.as_ref().is_some_and(|item| item.paused());
         if let Some(item) = &item {
             item.set_property("status", "Canceled".to_string());
             item.set_property("speed", String::new());
         }
 
-        let (guids, paused_chunks) = {
+        let guids = {
             let mut state = self_.state.borrow_mut();
-            let guids = state.asset_guids.remove(&asset);
-            let mut paused_chunks = Vec::new();
             if let Some(item) = &item {
                 if let Some(v) = item.version() {
                     state.download_items.remove(&v);
@@ -904,33 +1232,62 @@ impl Asset for super::EpicDownloadManager {
                     state.download_items.remove(&r);
                 }
             }
-            if let Some(ref guids) = guids {
-                for guid in guids {
-                    state.chunk_retries.remove(guid.as_str());
-                    for file in state
-                        .downloaded_chunks
-                        .remove(guid.as_str())
-                        .unwrap_or_default()
-                    {
-                        state.file_retries.remove(&file);
-                        state.downloaded_files.remove(&file);
-                    }
-                    // Remove chunks if we are already in paused state
-                    if paused {
-                        if let Some(values) = state.paused_asset_chunks.remove(guid.as_str()) {
-                            paused_chunks.push((guid.clone(), values));
-                        }
-                    }
-                }
-            }
-            (guids, paused_chunks)
+            state.asset_guids.remove(&asset)
         };
-
         if let Some(guids) = guids {
+            self.drop_asset_chunks(&asset, guids);
+        }
+    }
+
+    fn drop_asset_chunks(&self, asset: &str, mut guids: Vec<String>) {
+        let self_ = self.imp();
+        // Files are keyed by `{asset}/{release}/{file}`
+        let prefix = format!("{asset}/");
+        guids.sort();
+        guids.dedup();
+        let mut in_flight = Vec::new();
+        let mut paused_chunks = Vec::new();
+        let mut released = Vec::new();
+        {
+            let mut state = self_.state.borrow_mut();
             for guid in guids {
-                self.send_to_thread_sender(&guid, &ThreadMessages::Cancel);
+                let Some(files) = state.downloaded_chunks.get_mut(&guid) else {
+                    continue;
+                };
+                let ours: Vec<String> = files
+                    .iter()
+                    .filter(|f| f.starts_with(&prefix))
+                    .cloned()
+                    .collect();
+                files.retain(|f| !f.starts_with(&prefix));
+                let unused = files.is_empty();
+                for file in ours {
+                    state.file_retries.remove(&file);
+                    state.downloaded_files.remove(&file);
+                }
+                // Other downloads still wait for this chunk
+                if !unused {
+                    continue;
+                }
+                state.downloaded_chunks.remove(&guid);
+                state.chunk_retries.remove(&guid);
+                state.chunk_errors.remove(&guid);
+                state.chunk_links.remove(&guid);
+                state.chunk_urls.remove(&guid);
+                state.chunk_progress.forget(&guid);
+                if let Some(values) = state.paused_asset_chunks.remove(&guid) {
+                    paused_chunks.push((guid, values));
+                } else if state.completed_chunks.contains(&guid) {
+                    released.push(guid);
+                } else {
+                    in_flight.push(guid);
+                }
             }
         }
+
+        for guid in in_flight {
+            self.send_to_thread_sender(&guid, &ThreadMessages::Cancel);
+        }
         for (guid, values) in paused_chunks {
             for (url, path) in values {
                 let _ = self_
@@ -938,6 +1295,76 @@ impl Asset for super::EpicDownloadManager {
                     .send_blocking(CancelChunk(url, path, guid.clone()));
             }
         }
+        for guid in released {
+            self.release_chunk(&guid);
+        }
+    }
+
+    fn estimate_copy(&self, source: PathBuf, targets: Vec<PathBuf>, size: Option<(u64, u64)>) {
+        let self_ = self.imp();
+        let strategy = CopyStrategy::from_setting(&self_.settings.string("copy-strategy"));
+        // A plain copy saves nothing
+        if strategy == CopyStrategy::Copy || targets.is_empty() {
+            return;
+        }
+        let sender = self_.sender.clone();
+        self_.file_pool.execute(move || {
+            let (files, bytes) = size.unwrap_or_else(|| {
+                let files = crate::tools::copy::files(&source);
+                (files.len() as u64, files.iter().map(|(_, size)| size).sum())
+            });
+            let estimates = targets
+                .iter()
+                .map(|target| crate::tools::copy::estimate(&source, target, files, bytes, strategy))
+                .collect();
+            let _ = sender.send_blocking(Msg::CopyEstimated(estimates));
+        });
+    }
+
+    fn copy_estimated(&self, estimates: &[crate::tools::copy::Estimate]) {
+        let self_ = self.imp();
+        let format = |size: u64| {
+            format!(
+                "{:.2}",
+                byte_unit::Byte::from_u64(size).get_appropriate_unit(byte_unit::UnitType::Decimal)
+            )
+        };
+        let message: Vec<String> = estimates
+            .iter()
+            .map(|estimate| {
+                debug!(
+                    "Placing {:?} into {:?} would {} {} files",
+                    estimate.source,
+                    estimate.target,
+                    estimate.placed.describe(),
+                    estimate.files
+                );
+                if estimate.saved() > 0 {
+                    format!(
+                        "{} shared with the vault in {} ({})",
+                        format(estimate.saved()),
+                        estimate.target.display(),
+                        estimate.placed.describe()
+                    )
+                } else {
+                    format!(
+                        "{} copied into {}, the copy strategy is not supported there",
+                        format(estimate.bytes),
+                        estimate.target.display()
+                    )
+                }
+            })
+            .collect();
+        if message.is_empty() {
+            return;
+        }
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "copy-estimate",
+                &message.join(", "),
+                gtk4::MessageType::Info,
+            );
+        }
     }
 
     fn add_fab_asset_download(
@@ -978,8 +1405,20 @@ impl Asset for super::EpicDownloadManager {
         item.set_property("label", fab_asset.title.clone());
         item.set_property("target", target.clone());
         item.set_property("status", "initializing...".to_string());
+        self.queue_download(
+            &asset_id,
+            &QueuedDownload::Fab {
+                asset: Box::new(fab_asset.clone()),
+                artifact_id: artifact_id.clone(),
+                platform: platform.clone(),
+            },
+            target,
+            &[],
+        );
 
         self_.downloads.append(&item);
+        self.check_network(&item);
+        self.update_schedule();
         self.set_property("has-items", self_.downloads.first_child().is_some());
 
         item.connect_local(
@@ -1035,53 +1474,55 @@ impl Asset for super::EpicDownloadManager {
             let win_ = window.imp();
             let eg = win_.model.borrow().epic_games.borrow().clone();
             let id = asset_id.clone();
-            self_.download_pool.execute(move || {
-                if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-                    return;
-                }
-                let start = std::time::Instant::now();
-                match crate::RUNTIME.block_on(eg.fab_asset_manifest(
-                    &artifact_id,
-                    &namespace,
-                    &asset_id,
-                    Some(&platform),
-                )) {
-                    Ok(download_infos) => {
-                        if let Some(download_info) = download_infos.into_iter().next() {
-                            if let Some(base_url) =
-                                download_info.distribution_point_base_urls.first()
-                            {
-                                match crate::RUNTIME.block_on(
-                                    eg.fab_download_manifest(download_info.clone(), base_url),
-                                ) {
-                                    Ok(manifest) => {
-                                        debug!(
-                                            "Got FAB download manifest for {} in {:?}",
-                                            id,
-                                            start.elapsed()
-                                        );
-                                        let _ = sender.send_blocking((id, vec![manifest]));
-                                    }
-                                    Err(e) => {
-                                        error!(
-                                            "Failed to get FAB download manifest for {}: {:?}",
-                                            id, e
-                                        );
+            self_
+                .scheduler
+                .execute(&self_.download_pool, vec![id.clone()], move || {
+                    if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    let start = std::time::Instant::now();
+                    match crate::RUNTIME.block_on(eg.fab_asset_manifest(
+                        &artifact_id,
+                        &namespace,
+                        &asset_id,
+                        Some(&platform),
+                    )) {
+                        Ok(download_infos) => {
+                            if let Some(download_info) = download_infos.into_iter().next() {
+                                if let Some(base_url) =
+                                    download_info.distribution_point_base_urls.first()
+                                {
+                                    match crate::RUNTIME.block_on(
+                                        eg.fab_download_manifest(download_info.clone(), base_url),
+                                    ) {
+                                        Ok(manifest) => {
+                                            debug!(
+                                                "Got FAB download manifest for {} in {:?}",
+                                                id,
+                                                start.elapsed()
+                                            );
+                                            let _ = sender.send_blocking((id, vec![manifest]));
+                                        }
+                                        Err(e) => {
+                                            error!(
+                                                "Failed to get FAB download manifest for {}: {:?}",
+                                                id, e
+                                            );
+                                        }
                                     }
+                                } else {
+                                    error!("No distribution point base URLs for FAB asset {}", id);
                                 }
                             } else {
-                                error!("No distribution point base URLs for FAB asset {}", id);
+                                error!("No download info returned for FAB asset {}", id);
                             }
-                        } else {
-                            error!("No download info returned for FAB asset {}", id);
+                        }
+                        Err(e) => {
+                            error!("Failed to get FAB asset manifest for {}: {:?}", id, e);
                         }
                     }
-                    Err(e) => {
-                        error!("Failed to get FAB asset manifest for {}: {:?}", id, e);
-                    }
-                }
-                debug!("FAB manifest requests took {:?}", start.elapsed());
-            });
+                    debug!("FAB manifest requests took {:?}", start.elapsed());
+                });
         }
     }
 }
@@ -1107,6 +1548,46 @@ fn process_thread_message(
     }
 }
 
+/// Check a downloaded chunk against the data in its header and the hash from the manifest.
+/// Returns the size of the chunk file when it is complete and intact.
+fn verify_chunk(p: &Path, guid: &str, hash: Option<u128>) -> Result<u128, String> {
+    let buffer = std::fs::read(p).map_err(|e| e.to_string())?;
+    // Shorter files cannot even hold the chunk header
+    if buffer.len() <= CHUNK_HEADER_MAX_SIZE {
+        return Err(format!("chunk is too small ({} bytes)", buffer.len()));
+    }
+    let size = buffer.len() as u128;
+    let chunk = egs_api::api::types::chunk::Chunk::from_vec(buffer)
+        .ok_or_else(|| "unable to parse chunk".to_string())?;
+    if !chunk.guid.eq_ignore_ascii_case(guid) {
+        return Err(format!("chunk contains {} instead", chunk.guid));
+    }
+    if let Some(expected) = chunk.uncompressed_size {
+        if expected as usize != chunk.data.len() {
+            return Err(format!(
+                "expected {} bytes of data, got {}",
+                expected,
+                chunk.data.len()
+            ));
+        }
+    }
+    if let Some(expected) = hash {
+        if expected != 0 && expected != u128::from(chunk.hash) {
+            return Err(format!(
+                "hash {:016X} does not match the manifest {:016X}",
+                chunk.hash, expected
+            ));
+        }
+    }
+    // Hash type 2 and 3 carry a SHA1 of the uncompressed data
+    if let (Some(sha), Some(2 | 3)) = (&chunk.sha_hash, chunk.hash_type) {
+        if Sha1::digest(&chunk.data).as_slice() != sha.as_slice() {
+            return Err("SHA1 of the data does not match the header".to_string());
+        }
+    }
+    Ok(size)
+}
+
 fn save_asset_manifest(
     t: &Path,
     manifest: &egs_api::api::types::download_manifest::DownloadManifest,
@@ -1262,6 +1743,25 @@ impl AssetPriv for super::EpicDownloadManager {
         }
     }
 
+    fn clear_retry_status(&self, guid: &str) {
+        let self_ = self.imp();
+        let asset_id = {
+            let state = self_.state.borrow();
+            state
+                .downloaded_chunks
+                .get(guid)
+                .and_then(|files| files.first())
+                .and_then(|file| state.downloaded_files.get(file))
+                .map(|f| f.asset.clone())
+        };
+        if let Some(item) = asset_id.and_then(|id| self.get_item(&id)) {
+            let status: Option<String> = item.property("status");
+            if status.is_some_and(|s| s.starts_with("Retrying chunk")) {
+                item.set_property("status", String::new());
+            }
+        }
+    }
+
     fn extract_file_from_chunks(
         &self,
         finished_files: &mut Vec<String>,
@@ -1269,16 +1769,6 @@ impl AssetPriv for super::EpicDownloadManager {
         f: &mut DownloadedFile,
     ) {
         let self_ = self.imp();
-        let vaults = self_.settings.strv("unreal-vault-directories");
-        let temp_dir = std::path::PathBuf::from(vaults.first().map_or_else(
-            || {
-                self_
-                    .settings
-                    .string("temporary-download-directory")
-                    .to_string()
-            },
-            std::string::ToString::to_string,
-        ));
         let mut targets: Vec<(String, bool)> = Vec::new();
         let mut to_vault = true;
         {
@@ -1301,9 +1791,11 @@ impl AssetPriv for super::EpicDownloadManager {
         debug!("File finished {}", f.name);
         finished_files.push(file.to_string());
         let finished = f.clone();
-        let mut temp = temp_dir;
-        temp.push(f.release.clone());
-        temp.push("temp");
+        let chunk_paths: HashMap<String, PathBuf> = f
+            .chunks
+            .iter()
+            .map(|c| (c.guid.clone(), self.chunk_path(&c.guid)))
+            .collect();
         let mut vault = if to_vault || targets.is_empty() {
             let mut v = match self.unreal_vault_dir(&f.asset) {
                 None => {
@@ -1323,6 +1815,7 @@ impl AssetPriv for super::EpicDownloadManager {
                 }
             }
         };
+        let strategy = CopyStrategy::from_setting(&self_.settings.string("copy-strategy"));
         let sender = self_.sender.clone();
         let f_c = f.clone();
         let file_c = file.to_string();
@@ -1343,7 +1836,7 @@ impl AssetPriv for super::EpicDownloadManager {
             match File::create(vault.clone()) {
                 Ok(mut target) => {
                     let hash =
-                        extract_chunks(finished.chunks, &temp.clone(), &mut target).finalize();
+                        extract_chunks(finished.chunks, &chunk_paths, &mut target).finalize();
                     if finished
                         .hash
                         .eq(&hash.iter().fold(String::new(), |mut output, b| {
@@ -1351,7 +1844,7 @@ impl AssetPriv for super::EpicDownloadManager {
                             output
                         }))
                     {
-                        copy_files(&vault.clone(), targets, &finished.name);
+                        copy_files(&vault.clone(), targets, &finished.name, strategy);
                         let _ = sender.send_blocking(Msg::FinalizeFileDownload(
                             file_c.to_string(),
                             f_c.clone(),
@@ -1372,14 +1865,16 @@ impl AssetPriv for super::EpicDownloadManager {
 
 fn extract_chunks(
     chunks: Vec<egs_api::api::types::download_manifest::FileChunkPart>,
-    temp: &Path,
+    chunk_paths: &HashMap<String, PathBuf>,
     target: &mut File,
 ) -> CoreWrapper<Sha1Core> {
     let mut hasher = Sha1::new();
     for chunk in chunks {
-        let mut t = temp.to_path_buf();
-        t.push(format!("{}.chunk", chunk.guid));
-        match File::open(&t) {
+        let Some(t) = chunk_paths.get(&chunk.guid) else {
+            error!("No stored chunk for {}", chunk.guid);
+            break;
+        };
+        match File::open(t) {
             Ok(mut f) => {
                 let metadata = match f.metadata() {
                     Ok(metadata) => metadata,
@@ -1432,7 +1927,7 @@ fn extract_chunks(
     hasher
 }
 
-fn copy_files(from: &Path, targets: Vec<(String, bool)>, filename: &str) {
+fn copy_files(from: &Path, targets: Vec<(String, bool)>, filename: &str, strategy: CopyStrategy) {
     for t in targets {
         let mut tar = PathBuf::from_str(&t.0).unwrap();
         tar.push(filename);
@@ -1462,7 +1957,7 @@ fn copy_files(from: &Path, targets: Vec<(String, bool)>, filename: &str) {
             error!("Unable to create target directory {:?}: {}", parent, e);
             continue;
         }
-        if let Err(e) = std::fs::copy(from, tar) {
+        if let Err(e) = crate::tools::copy::place(from, &tar, strategy) {
             error!("Unable to copy file: {:?}", e);
         };
     }
@@ -0,0 +1,135 @@
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use log::{debug, error, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub trait ChunkStore {
+    /// Directory shared by all asset downloads, chunks are stored by GUID and hash
+    /// so every release and asset using the same chunk finds it there
+    fn chunk_store_dir(&self) -> PathBuf {
+        unimplemented!()
+    }
+
+    fn chunk_path(&self, _guid: &str) -> PathBuf {
+        unimplemented!()
+    }
+
+    /// Called once no pending file references the chunk anymore.
+    /// The chunk is either removed or kept for later downloads if retention is enabled.
+    fn release_chunk(&self, _guid: &str) {
+        unimplemented!()
+    }
+}
+
+impl ChunkStore for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn chunk_store_dir(&self) -> PathBuf {
+        let self_ = self.imp();
+        let vaults = self_.settings.strv("unreal-vault-directories");
+        let mut dir = PathBuf::from(vaults.first().map_or_else(
+            || {
+                self_
+                    .settings
+                    .string("temporary-download-directory")
+                    .to_string()
+            },
+            std::string::ToString::to_string,
+        ));
+        dir.push("chunks");
+        dir
+    }
+
+    fn chunk_path(&self, guid: &str) -> PathBuf {
+        let self_ = self.imp();
+        let hash = self_
+            .state
+            .borrow()
+            .chunk_hashes
+            .get(guid)
+            .copied()
+            .unwrap_or_default();
+        self.chunk_store_dir().join(chunk_file_name(guid, hash))
+    }
+
+    fn release_chunk(&self, guid: &str) {
+        let self_ = self.imp();
+        let path = self.chunk_path(guid);
+        let limit =
+            u64::try_from(self_.settings.int("chunk-store-size")).unwrap_or_default() * 1024 * 1024;
+        self_.state.borrow_mut().completed_chunks.remove(guid);
+        if limit == 0 {
+            debug!("Removing chunk {}", path.as_path().to_string_lossy());
+            if let Err(e) = std::fs::remove_file(&path) {
+                error!("Unable to remove chunk file: {}", e);
+            };
+            return;
+        }
+        // Chunks still needed by running downloads must survive the pruning
+        let dir = self.chunk_store_dir();
+        let in_use: HashSet<PathBuf> = {
+            let state = self_.state.borrow();
+            state
+                .downloaded_chunks
+                .keys()
+                .map(|g| {
+                    dir.join(chunk_file_name(
+                        g,
+                        state.chunk_hashes.get(g).copied().unwrap_or_default(),
+                    ))
+                })
+                .collect()
+        };
+        self_.file_pool.execute(move || {
+            touch(&path);
+            prune(&dir, limit, &in_use);
+        });
+    }
+}
+
+fn chunk_file_name(guid: &str, hash: u128) -> String {
+    format!("{guid}_{hash:016X}.chunk")
+}
+
+/// Mark the chunk as recently used
+pub fn touch(path: &Path) {
+    if let Ok(f) = std::fs::File::options().append(true).open(path) {
+        if let Err(e) = f.set_modified(std::time::SystemTime::now()) {
+            debug!("Unable to update chunk modification time: {}", e);
+        }
+    }
+}
+
+/// Remove least recently used chunks until the store fits into `limit` bytes
+fn prune(dir: &Path, limit: u64, in_use: &HashSet<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut chunks: Vec<(std::time::SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+        })
+        .collect();
+    let mut total: u64 = chunks.iter().map(|(_, size, _)| size).sum();
+    if total <= limit {
+        return;
+    }
+    chunks.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in chunks {
+        if total <= limit {
+            break;
+        }
+        if in_use.contains(&path) {
+            continue;
+        }
+        debug!("Pruning chunk {:?} from the store", path);
+        match std::fs::remove_file(&path) {
+            Ok(()) => total -= size,
+            Err(e) => warn!("Unable to prune chunk {:?}: {}", path, e),
+        }
+    }
+}
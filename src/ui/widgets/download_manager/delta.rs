@@ -0,0 +1,125 @@
+use egs_api::api::types::download_manifest::DownloadManifest;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Files of an already downloaded release that can be reused by the release being downloaded
+#[derive(Debug, Default)]
+pub struct DeltaPlan {
+    /// Previous release directory the files are taken from
+    pub source: PathBuf,
+    /// Existing files by their hash
+    pub reuse: HashMap<String, PathBuf>,
+    /// Size of the chunks that still need to be downloaded
+    pub delta_size: u128,
+}
+
+/// Load the manifest saved next to the `data` directory of a release in the vault
+pub fn load_release_manifest(release_dir: &Path) -> Option<DownloadManifest> {
+    for name in ["manifest", "manifest.json"] {
+        let path = release_dir.join(name);
+        if let Ok(data) = std::fs::read(&path) {
+            if let Some(manifest) = DownloadManifest::parse(data) {
+                return Some(manifest);
+            }
+            warn!("Unable to parse saved manifest {:?}", path);
+        }
+    }
+    None
+}
+
+/// Find the previous release of the asset in the vaults sharing the most files with the new one
+/// and work out what can be reused from it
+pub fn plan_update(
+    vaults: &[String],
+    candidates: &[String],
+    new: &DownloadManifest,
+) -> Option<DeltaPlan> {
+    let mut best: Option<DeltaPlan> = None;
+    for app_name in candidates {
+        if app_name.eq(&new.app_name_string) {
+            continue;
+        }
+        for vault in vaults {
+            let release_dir = PathBuf::from(vault).join(app_name);
+            let data = release_dir.join("data");
+            if !data.is_dir() {
+                continue;
+            }
+            let Some(old) = load_release_manifest(&release_dir) else {
+                continue;
+            };
+            let plan = diff(data, &old, new);
+            let better = best.as_ref().is_none_or(|b| {
+                plan.reuse.len() > b.reuse.len()
+                    || (plan.reuse.len() == b.reuse.len() && plan.delta_size < b.delta_size)
+            });
+            if better {
+                best = Some(plan);
+            }
+        }
+    }
+    if let Some(plan) = &best {
+        debug!(
+            "Planning update of {} from {:?}, reusing {} files",
+            new.app_name_string,
+            plan.source,
+            plan.reuse.len()
+        );
+    }
+    best.filter(|plan| !plan.reuse.is_empty())
+}
+
+/// Compare the file lists of two manifests, files with the same hash do not need to be downloaded again
+pub fn diff(source: PathBuf, old: &DownloadManifest, new: &DownloadManifest) -> DeltaPlan {
+    let existing: HashMap<&str, &str> = old
+        .file_manifest_list
+        .iter()
+        .map(|f| (f.file_hash.as_str(), f.filename.as_str()))
+        .collect();
+    let mut reuse = HashMap::new();
+    let mut needed_chunks: HashSet<&str> = HashSet::new();
+    for file in &new.file_manifest_list {
+        match existing.get(file.file_hash.as_str()) {
+            Some(name) if source.join(name).is_file() => {
+                reuse.insert(file.file_hash.clone(), source.join(name));
+            }
+            _ => {
+                needed_chunks.extend(file.file_chunk_parts.iter().map(|c| c.guid.as_str()));
+            }
+        }
+    }
+    let delta_size = needed_chunks
+        .iter()
+        .filter_map(|guid| new.chunk_filesize_list.get(*guid))
+        .sum();
+    DeltaPlan {
+        source,
+        reuse,
+        delta_size,
+    }
+}
+
+/// Put an unchanged file from the previous release in place, hard linking it when allowed
+pub fn reuse_file(from: &Path, to: &Path, hard_link: bool) -> bool {
+    if to.exists() {
+        return false;
+    }
+    let Some(parent) = to.parent() else {
+        return false;
+    };
+    if let Err(e) = std::fs::create_dir_all(parent) {
+        warn!("Unable to create directory {:?}: {}", parent, e);
+        return false;
+    }
+    if hard_link && std::fs::hard_link(from, to).is_ok() {
+        return true;
+    }
+    match std::fs::copy(from, to) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Unable to reuse {:?} for {:?}: {}", from, to, e);
+            false
+        }
+    }
+}
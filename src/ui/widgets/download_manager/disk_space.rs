@@ -0,0 +1,147 @@
+use crate::tools::disk_space::Shortfall;
+use crate::ui::widgets::download_manager::chunk_store::ChunkStore;
+use crate::ui::widgets::download_manager::notifications::Notifications;
+use glib::clone;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use log::{debug, warn};
+use std::path::PathBuf;
+
+/// How often free space is checked while downloads are running
+const MONITOR_INTERVAL_SECONDS: u32 = 30;
+
+pub trait DiskSpace {
+    /// Free space to keep on every volume we write to, in bytes
+    fn reserved_space(&self) -> u64 {
+        unimplemented!()
+    }
+
+    /// Check the space needed by a download on every volume it writes to before it starts.
+    /// Returns false if the download must not start.
+    fn preflight(&self, _id: &str, _requirements: &[(PathBuf, u64)]) -> bool {
+        unimplemented!()
+    }
+
+    fn setup_disk_space_monitor(&self) {
+        unimplemented!()
+    }
+
+    /// Pause all running downloads when a volume they write to runs low on space
+    fn check_free_space(&self) {
+        unimplemented!()
+    }
+}
+
+impl DiskSpace for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn reserved_space(&self) -> u64 {
+        let self_ = self.imp();
+        u64::try_from(self_.settings.int("min-free-space")).unwrap_or_default() * 1024 * 1024
+    }
+
+    fn preflight(&self, id: &str, requirements: &[(PathBuf, u64)]) -> bool {
+        let self_ = self.imp();
+        let shortfalls = crate::tools::disk_space::check(requirements, self.reserved_space());
+        if shortfalls.is_empty() {
+            if let Some(w) = self_.window.get() {
+                w.clear_notification("no space left on device");
+            }
+            return true;
+        }
+        let message = shortfalls
+            .iter()
+            .map(Shortfall::describe)
+            .collect::<Vec<String>>()
+            .join(", ");
+        warn!("Not enough disk space for {}: {}", id, message);
+        let refuse = self_.settings.boolean("low-space-refuse");
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "no space left on device",
+                &format!("Not enough disk space: {message}"),
+                if refuse {
+                    gtk4::MessageType::Error
+                } else {
+                    gtk4::MessageType::Warning
+                },
+            );
+        }
+        if refuse {
+            if let Some(item) = self.get_item(id) {
+                self.fail(&item, "not enough disk space");
+            }
+        }
+        !refuse
+    }
+
+    fn setup_disk_space_monitor(&self) {
+        glib::timeout_add_seconds_local(
+            MONITOR_INTERVAL_SECONDS,
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    dm.check_free_space();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    fn check_free_space(&self) {
+        let self_ = self.imp();
+        let reserve = self.reserved_space();
+        if reserve == 0 {
+            return;
+        }
+        let active: Vec<_> = self_
+            .state
+            .borrow()
+            .download_items
+            .values()
+            .filter(|i| !i.paused() && !i.canceled() && !i.failed())
+            .cloned()
+            .collect();
+        if active.is_empty() {
+            return;
+        }
+        let mut paths: Vec<PathBuf> = active
+            .iter()
+            .filter_map(super::download_item::EpicDownloadItem::path)
+            .map(PathBuf::from)
+            .collect();
+        paths.push(self.chunk_store_dir());
+        paths.push(PathBuf::from(
+            self_
+                .settings
+                .string("temporary-download-directory")
+                .to_string(),
+        ));
+        if let Some(engines) = self_.settings.strv("unreal-engine-directories").first() {
+            paths.push(PathBuf::from(engines.as_str()));
+        }
+        let requirements: Vec<(PathBuf, u64)> = paths.into_iter().map(|p| (p, 0)).collect();
+        let shortfalls = crate::tools::disk_space::check(&requirements, reserve);
+        let Some(low) = shortfalls.first() else {
+            return;
+        };
+        debug!("Pausing {} downloads, low on space", active.len());
+        for item in active {
+            item.pause_download();
+        }
+        let message = format!(
+            "Downloads paused, {} has less free space than the configured minimum",
+            low.path.display()
+        );
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "no space left on device",
+                &message,
+                gtk4::MessageType::Warning,
+            );
+        }
+        self.notify_attention("no-space", "Downloads paused", &message);
+    }
+}
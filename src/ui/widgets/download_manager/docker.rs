@@ -1,3 +1,7 @@
+use crate::ui::widgets::download_manager::disk_space::DiskSpace;
+use crate::ui::widgets::download_manager::network::Network;
+use crate::ui::widgets::download_manager::queue::{Queue, QueuedDownload};
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
 use crate::ui::widgets::download_manager::Msg::{DockerBlobFailed, DockerCanceled};
 use crate::ui::widgets::download_manager::{download_item, DownloadStatus, Msg, ThreadMessages};
 use crate::ui::widgets::logged_in::refresh::Refresh;
@@ -40,11 +44,16 @@ pub trait Docker {
         unimplemented!()
     }
 
-    fn docker_download_progress(&self, _version: &str, _progress: u64) {
+    fn docker_download_progress(&self, _version: &str, _digest: &str, _progress: u64) {
         unimplemented!()
     }
 
-    fn docker_blob_finished(&self, _version: &str, _digest: &str) {
+    /// Hash a downloaded blob against its `sha256:` digest
+    fn docker_blob_finished(&self, _version: &str, _digest: (String, u64)) {
+        unimplemented!()
+    }
+
+    fn docker_blob_verified(&self, _version: &str, _digest: (String, u64), _valid: bool) {
         unimplemented!()
     }
 
@@ -56,6 +65,11 @@ pub trait Docker {
         unimplemented!()
     }
 
+    /// Remember the extracted layers so a restored download skips them
+    fn save_docker_layers(&self, _version: &str) {
+        unimplemented!()
+    }
+
     fn docker_finished(&self, _item: &download_item::EpicDownloadItem) {
         unimplemented!()
     }
@@ -67,6 +81,8 @@ pub trait Docker {
     fn pause_docker_download(&self, _version: String) {
         unimplemented!()
     }
+
+    /// Continue paused layers, partially written blobs are resumed from their current size
     fn resume_docker_download(&self, _version: String) {
         unimplemented!()
     }
@@ -82,6 +98,21 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
         item.set_property("status", "waiting for download slot".to_string());
         item.set_total_size(u128::from(size));
         item.set_total_files(digests.len() as u64);
+        if let (Some(blobs), Some(engines)) = (
+            self.docker_target_directory(),
+            self_.settings.strv("unreal-engine-directories").first(),
+        ) {
+            let requirements = [
+                (blobs, size),
+                (
+                    PathBuf::from(engines.as_str()).join(version),
+                    size.saturating_mul(crate::tools::disk_space::EXTRACTION_FACTOR),
+                ),
+            ];
+            if !self.preflight(version, &requirements) {
+                return;
+            }
+        }
 
         let v = version.to_string();
         let should_download = {
@@ -89,34 +120,102 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
             !state.docker_digests.contains_key(version)
         };
         if should_download {
+            let restored = self_
+                .state
+                .borrow_mut()
+                .restored_docker_layers
+                .remove(version)
+                .unwrap_or_default();
             let mut vec: Vec<(String, DownloadStatus)> = Vec::new();
-            for digest in &digests {
-                vec.push((digest.0.clone(), DownloadStatus::Init));
-            }
+            let mut pending: Vec<(String, u64)> = Vec::new();
+            let target = self.docker_target_directory();
+            let mut extracted = 0;
             for digest in digests {
-                self.download_docker_digest(&v, digest);
+                let status = restored
+                    .iter()
+                    .find(|(d, _)| d.eq(&digest.0))
+                    .map(|(_, status)| status.clone());
+                match status {
+                    Some(DownloadStatus::Extracted) => {
+                        vec.push((digest.0.clone(), DownloadStatus::Extracted));
+                        item.add_restored_size(u128::from(digest.1));
+                        extracted += 1;
+                    }
+                    // Verified blobs are only trusted while they are still complete on disk
+                    Some(DownloadStatus::Verified)
+                        if target.as_ref().is_some_and(|t| {
+                            std::fs::metadata(t.join(&digest.0)).is_ok_and(|m| m.len() == digest.1)
+                        }) =>
+                    {
+                        vec.push((digest.0.clone(), DownloadStatus::Verified));
+                        item.add_restored_size(u128::from(digest.1));
+                    }
+                    _ => {
+                        vec.push((digest.0.clone(), DownloadStatus::Init));
+                        pending.push(digest);
+                    }
+                }
+            }
+            self_
+                .state
+                .borrow_mut()
+                .docker_digests
+                .insert(v.clone(), vec);
+            if !restored.is_empty() {
+                // Queueing the download again forgot the restored layers
+                self.save_docker_layers(&v);
+                for _ in 0..extracted {
+                    item.file_processed();
+                }
+                self.docker_extract_digests(&v);
+            }
+            if item.paused() {
+                item.set_property("status", "Paused".to_string());
+                self_
+                    .state
+                    .borrow_mut()
+                    .paused_docker_digests
+                    .insert(v, pending);
+            } else {
+                for digest in pending {
+                    self.download_docker_digest(&v, digest);
+                }
             }
-            self_.state.borrow_mut().docker_digests.insert(v, vec);
         }
     }
 
     #[cfg(target_os = "linux")]
     fn download_docker_digest(&self, version: &str, digest: (String, u64)) {
         let self_ = self.imp();
+        if self
+            .get_item(version)
+            .is_some_and(|item| item.paused() || item.failed())
+        {
+            self.pause_docker_digest(version.to_string(), digest);
+            return;
+        }
         if let Some(window) = self_.window.get() {
             let win_: &crate::window::imp::EpicAssetManagerWindow =
                 crate::window::imp::EpicAssetManagerWindow::from_obj(window);
-            if let Some(dclient) = &*win_.model.borrow().dclient.borrow() {
+            if let Some(credentials) = &*win_.model.borrow().dcredentials.borrow() {
                 let ver = version.to_string();
                 let d = digest.0.clone();
                 let size = digest.1;
-                let client = dclient.clone();
+                let credentials = credentials.clone();
                 let sender = self_.sender.clone();
                 let pool = self_.download_pool.clone();
+                let scheduler = self_.scheduler.clone();
                 let Some(target) = self.docker_target_directory() else {
                     return;
                 };
                 debug!("Going to download to {:?}", target);
+                let mut skip = self_
+                    .state
+                    .borrow()
+                    .docker_reported
+                    .get(&d)
+                    .copied()
+                    .unwrap_or_default();
                 let (send, recv) = std::sync::mpsc::channel::<ThreadMessages>();
                 self.add_thread_sender(ver.clone(), send);
                 thread::spawn(move || {
@@ -124,28 +223,23 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
                         std::sync::mpsc::channel();
                     let v = ver.clone();
                     let s = sender.clone();
-                    pool.execute(move || {
-                        match client.get_blob_with_progress_file(
-                            "epicgames/unreal-engine",
-                            &d,
-                            Some(size),
-                            Some(tx),
-                            target.as_path(),
-                        ) {
-                            Ok(_) => {
-                                let _ = s.send_blocking(Msg::DockerBlobFinished(v, d));
+                    let digest_id = d.clone();
+                    scheduler.execute(&pool, vec![ver.clone()], move || {
+                        match download_blob(&credentials, &d, size, &target, &mut |progress| {
+                            tx.send(progress).is_ok()
+                        }) {
+                            Ok(()) => {
+                                let _ = s.send_blocking(Msg::DockerBlobFinished(v, (d, size)));
+                            }
+                            Err(BlobError::Io(err)) => {
+                                error!("Failed blob download because: {:?}", err);
+                                let _ = s.send_blocking(Msg::IOError(err.to_string()));
+                            }
+                            Err(BlobError::Stopped) => {}
+                            Err(BlobError::Request(err)) => {
+                                error!("Failed blob download because: {}", err);
+                                let _ = s.send_blocking(DockerBlobFailed(v, (d, size)));
                             }
-                            Err(e) => match &e {
-                                ghregistry::errors::Error::IO(err) => {
-                                    error!("Failed blob download because: {:?}", err);
-                                    let _ = s.send_blocking(Msg::IOError(err.to_string()));
-                                }
-                                ghregistry::errors::Error::Sender(_e) => {}
-                                _ => {
-                                    error!("Failed blob download because: {:?}", e);
-                                    let _ = s.send_blocking(DockerBlobFailed(v, (d, size)));
-                                }
-                            },
                         };
                     });
                     while let Ok(progress) = rx.recv() {
@@ -157,8 +251,20 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
                             process_docker_thread_message(ver.clone(), digest.clone(), &sender, &m);
                             return;
                         }
-                        let _ = sender
-                            .send_blocking(Msg::DockerDownloadProgress(ver.clone(), progress));
+                        // Resumed blobs first report the part that is already on disk
+                        let progress = {
+                            let counted = progress.saturating_sub(skip);
+                            skip = skip.saturating_sub(progress);
+                            counted
+                        };
+                        if progress == 0 {
+                            continue;
+                        }
+                        let _ = sender.send_blocking(Msg::DockerDownloadProgress(
+                            ver.clone(),
+                            digest_id.clone(),
+                            progress,
+                        ));
                     }
                 });
             }
@@ -166,6 +272,12 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
     }
     #[cfg(target_os = "linux")]
     fn cancel_docker_digest(&self, _version: &str, digest: (String, u64)) {
+        let self_ = self.imp();
+        {
+            let mut state = self_.state.borrow_mut();
+            state.docker_reported.remove(&digest.0);
+            state.docker_retries.remove(&digest.0);
+        }
         let Some(mut target) = self.docker_target_directory() else {
             return;
         };
@@ -219,6 +331,16 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
             item.set_property("label", cap[1].to_string());
         }
         item.set_property("status", "initializing...".to_string());
+        self.queue_download(
+            version,
+            &QueuedDownload::Docker {
+                version: version.to_string(),
+                extracted: Vec::new(),
+                verified: Vec::new(),
+            },
+            &None,
+            &[],
+        );
 
         item.connect_local(
             "finished",
@@ -242,6 +364,8 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
                 )));
 
         self_.downloads.append(&item);
+        self.check_network(&item);
+        self.update_schedule();
 
         self.set_property("has-items", self_.downloads.first_child().is_some());
 
@@ -251,27 +375,29 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
                 let client = dclient.clone();
                 let sender = self_.sender.clone();
                 let v = version.to_string();
-                self_.download_pool.execute(move || {
-                    match client.get_manifest("epicgames/unreal-engine", &v) {
-                        Ok(manifest) => match manifest.layers_digests(None) {
-                            Ok(digests) => {
-                                sender
-                                    .send_blocking(Msg::PerformDockerEngineDownload(
-                                        v,
-                                        manifest.download_size().unwrap_or(0),
-                                        digests,
-                                    ))
-                                    .ok();
-                            }
+                self_
+                    .scheduler
+                    .execute(&self_.download_pool, vec![v.clone()], move || {
+                        match client.get_manifest("epicgames/unreal-engine", &v) {
+                            Ok(manifest) => match manifest.layers_digests(None) {
+                                Ok(digests) => {
+                                    sender
+                                        .send_blocking(Msg::PerformDockerEngineDownload(
+                                            v,
+                                            manifest.download_size().unwrap_or(0),
+                                            digests,
+                                        ))
+                                        .ok();
+                                }
+                                Err(e) => {
+                                    error!("Unable to get manifest layers: {:?}", e);
+                                }
+                            },
                             Err(e) => {
-                                error!("Unable to get manifest layers: {:?}", e);
+                                error!("Unable to get docker manifest {:?}", e);
                             }
-                        },
-                        Err(e) => {
-                            error!("Unable to get docker manifest {:?}", e);
-                        }
-                    };
-                });
+                        };
+                    });
             }
         }
     }
@@ -297,26 +423,108 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
     }
 
     #[cfg(target_os = "linux")]
-    fn docker_download_progress(&self, version: &str, progress: u64) {
+    fn docker_download_progress(&self, version: &str, digest: &str, progress: u64) {
+        let self_ = self.imp();
         let Some(item) = self.get_item(version) else {
             return;
         };
+        // Progress of paused items is dropped, it gets reported again when the blob resumes
+        if !item.paused() && !item.canceled() {
+            *self_
+                .state
+                .borrow_mut()
+                .docker_reported
+                .entry(digest.to_string())
+                .or_default() += progress;
+        }
         item.add_downloaded_size(u128::from(progress));
 
         self.emit_by_name::<()>("tick", &[]);
     }
 
     #[cfg(target_os = "linux")]
-    fn docker_blob_finished(&self, version: &str, digest: &str) {
+    fn docker_blob_finished(&self, version: &str, digest: (String, u64)) {
         let self_ = self.imp();
+        let Some(target) = self.docker_target_directory() else {
+            return;
+        };
         if let Some(digests) = self_.state.borrow_mut().docker_digests.get_mut(version) {
             for d in digests {
-                if d.0.eq(digest) {
-                    d.1 = DownloadStatus::Downloaded;
+                if d.0.eq(&digest.0) {
+                    d.1 = DownloadStatus::Verifying;
                 }
             }
         }
-        self.docker_extract_digests(version);
+        let sender = self_.sender.clone();
+        let v = version.to_string();
+        self_.file_pool.execute(move || {
+            let valid = blob_matches_digest(&target.join(&digest.0), &digest.0);
+            sender
+                .send_blocking(Msg::DockerBlobVerified(v, digest, valid))
+                .ok();
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    fn docker_blob_verified(&self, version: &str, digest: (String, u64), valid: bool) {
+        let self_ = self.imp();
+        let Some(item) = self.get_item(version) else {
+            return;
+        };
+        if item.canceled() {
+            return;
+        }
+        if valid {
+            debug!("Verified docker blob {}", digest.0);
+            if let Some(digests) = self_.state.borrow_mut().docker_digests.get_mut(version) {
+                for d in digests {
+                    if d.0.eq(&digest.0) {
+                        d.1 = DownloadStatus::Verified;
+                    }
+                }
+            }
+            self.save_docker_layers(version);
+            self.docker_extract_digests(version);
+            return;
+        }
+
+        let retries = {
+            let mut state = self_.state.borrow_mut();
+            state.docker_reported.remove(&digest.0);
+            if let Some(digests) = state.docker_digests.get_mut(version) {
+                for d in digests {
+                    if d.0.eq(&digest.0) {
+                        d.1 = DownloadStatus::Init;
+                    }
+                }
+            }
+            let retries = state.docker_retries.entry(digest.0.clone()).or_default();
+            *retries += 1;
+            *retries
+        };
+        warn!(
+            "Docker blob {} does not match its digest, download attempt {}",
+            digest.0, retries
+        );
+        if let Some(mut target) = self.docker_target_directory() {
+            target.push(&digest.0);
+            if let Err(e) = std::fs::remove_file(&target) {
+                warn!("Unable to remove docker file {:?}: {}", target, e);
+            }
+        }
+        item.remove_downloaded_size(u128::from(digest.1));
+        if retries > self.retry_limit() {
+            error!("Giving up on docker blob {}", digest.0);
+            self.fail(
+                &item,
+                &format!("layer {} does not match its digest", digest.0),
+            );
+            // Parked like a paused layer so retrying the download picks it up again
+            self_.state.borrow_mut().docker_retries.remove(&digest.0);
+            self.pause_docker_digest(version.to_string(), digest);
+            return;
+        }
+        self.download_docker_digest(version, digest);
     }
 
     #[cfg(target_os = "linux")]
@@ -330,24 +538,20 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
         let should_extract = {
             let mut state = self_.state.borrow_mut();
             if let Some(digests) = state.docker_digests.get_mut(version) {
+                // A broken layer would leave a half extracted engine, so wait until all of them verify
+                if !digests
+                    .iter()
+                    .all(|d| matches!(d.1, DownloadStatus::Verified | DownloadStatus::Extracted))
+                {
+                    return;
+                }
                 for d in digests {
-                    match d.1 {
-                        DownloadStatus::Init => {
-                            break;
-                        }
-                        DownloadStatus::Downloaded => {
-                            let mut p = target.clone();
-                            p.push(&d.0);
-                            to_extract.push(p.to_str().unwrap_or_default().to_string());
-                            d.1 = DownloadStatus::Extracting;
-                        }
-                        DownloadStatus::Extracted => {
-                            continue;
-                        }
-                        DownloadStatus::Extracting => {
-                            return;
-                        }
-                    };
+                    if matches!(d.1, DownloadStatus::Verified) {
+                        let mut p = target.clone();
+                        p.push(&d.0);
+                        to_extract.push(p.to_str().unwrap_or_default().to_string());
+                        d.1 = DownloadStatus::Extracting;
+                    }
                 }
             }
             if to_extract.is_empty() {
@@ -414,22 +618,30 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
             return;
         };
         let mut remaining = 0;
+        let mut extracted = 0;
         let mut load_engines = false;
-        if let Some(digests) = self_.state.borrow_mut().docker_digests.get_mut(version) {
+        let mut guard = self_.state.borrow_mut();
+        let state = &mut *guard;
+        if let Some(digests) = state.docker_digests.get_mut(version) {
             for d in digests {
                 match d.1 {
-                    DownloadStatus::Init | DownloadStatus::Downloaded => {
+                    DownloadStatus::Init
+                    | DownloadStatus::Downloaded
+                    | DownloadStatus::Verifying
+                    | DownloadStatus::Verified => {
                         remaining += 1;
                     }
                     DownloadStatus::Extracted => {}
                     DownloadStatus::Extracting => {
                         d.1 = DownloadStatus::Extracted;
+                        state.docker_reported.remove(&d.0);
+                        state.docker_retries.remove(&d.0);
                         let mut t = target.clone();
                         t.push(&d.0);
                         if let Err(e) = std::fs::remove_file(&t) {
                             error!("Unable to remove digest file {:?}: {}", t, e);
                         }
-                        item.file_processed();
+                        extracted += 1;
                     }
                 };
             }
@@ -437,6 +649,11 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
                 load_engines = true;
             }
         }
+        drop(guard);
+        self.save_docker_layers(version);
+        for _ in 0..extracted {
+            item.file_processed();
+        }
         if load_engines {
             if let Some(window) = self_.window.get() {
                 let win_: &crate::window::imp::EpicAssetManagerWindow = window.imp();
@@ -447,6 +664,35 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
         self.docker_extract_digests(version);
     }
 
+    #[cfg(target_os = "linux")]
+    fn save_docker_layers(&self, version: &str) {
+        let self_ = self.imp();
+        let mut extracted: Vec<String> = Vec::new();
+        let mut verified: Vec<String> = Vec::new();
+        if let Some(digests) = self_.state.borrow().docker_digests.get(version) {
+            for (digest, status) in digests {
+                match status {
+                    DownloadStatus::Extracted => extracted.push(digest.clone()),
+                    // Extraction is not finished yet, the blob has to be unpacked again
+                    DownloadStatus::Verified | DownloadStatus::Extracting => {
+                        verified.push(digest.clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.queue_download(
+            version,
+            &QueuedDownload::Docker {
+                version: version.to_string(),
+                extracted,
+                verified,
+            },
+            &None,
+            &[],
+        );
+    }
+
     fn docker_finished(&self, item: &download_item::EpicDownloadItem) {
         let self_ = self.imp();
         if let Some(window) = self_.window.get() {
@@ -472,7 +718,7 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
             if let Some(r) = item.release() {
                 state.download_items.remove(&r);
             }
-            if item.paused() {
+            if item.paused() || item.failed() {
                 if let Some(values) = state.paused_docker_digests.remove(&version) {
                     for digest in values {
                         self_
@@ -497,6 +743,7 @@ impl Docker for crate::ui::widgets::download_manager::EpicDownloadManager {
     #[cfg(target_os = "linux")]
     fn resume_docker_download(&self, version: String) {
         let self_ = self.imp();
+        // Queued through the channel so the item is no longer paused when they start
         if let Some(values) = self_
             .state
             .borrow_mut()
@@ -531,3 +778,138 @@ fn process_docker_thread_message(
         }
     }
 }
+
+/// Repository the engine images are published in
+#[cfg(target_os = "linux")]
+const DOCKER_REPOSITORY: &str = "epicgames/unreal-engine";
+
+#[cfg(target_os = "linux")]
+enum BlobError {
+    Io(std::io::Error),
+    Request(String),
+    /// Nobody listens to the progress anymore
+    Stopped,
+}
+
+#[cfg(target_os = "linux")]
+impl From<std::io::Error> for BlobError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<reqwest::Error> for BlobError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Request(e.to_string())
+    }
+}
+
+/// Pull token of the engine repository for a GitHub user
+#[cfg(target_os = "linux")]
+fn registry_token(credentials: &(String, String)) -> Result<String, BlobError> {
+    let response = crate::HTTP_CLIENT
+        .get(format!(
+            "https://ghcr.io/token?service=ghcr.io&scope=repository:{DOCKER_REPOSITORY}:pull"
+        ))
+        .basic_auth(&credentials.0, Some(&credentials.1))
+        .send()?
+        .error_for_status()?;
+    let body: serde_json::Value = response.json()?;
+    body.get("token")
+        .and_then(serde_json::Value::as_str)
+        .map(ToString::to_string)
+        .ok_or_else(|| BlobError::Request("no token in the registry response".to_string()))
+}
+
+/// Download a blob into `target_dir`, resuming a partial file, through the shared speed limit.
+///
+/// Like ghregistry, `progress` first gets the size already on disk when resuming and then every
+/// block read, it returns `false` to stop. The blob is verified by the caller.
+#[cfg(target_os = "linux")]
+fn download_blob(
+    credentials: &(String, String),
+    digest: &str,
+    size: u64,
+    target_dir: &std::path::Path,
+    progress: &mut impl FnMut(u64) -> bool,
+) -> Result<(), BlobError> {
+    use std::io::{Read, Write};
+    std::fs::create_dir_all(target_dir)?;
+    let target = target_dir.join(digest);
+    let existing = std::fs::metadata(&target)
+        .map(|m| m.len())
+        .unwrap_or_default();
+    if existing == size {
+        return if progress(existing) {
+            Ok(())
+        } else {
+            Err(BlobError::Stopped)
+        };
+    }
+    let token = registry_token(credentials)?;
+    let mut request = crate::HTTP_CLIENT
+        .get(format!(
+            "https://ghcr.io/v2/{DOCKER_REPOSITORY}/blobs/{digest}"
+        ))
+        .bearer_auth(token);
+    if existing > 0 && existing < size {
+        request = request.header(reqwest::header::RANGE, format!("bytes={existing}-"));
+    }
+    let mut response = request.send()?.error_for_status()?;
+    let mut file = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        if !progress(existing) {
+            return Err(BlobError::Stopped);
+        }
+        std::fs::OpenOptions::new().append(true).open(&target)?
+    } else {
+        if existing > 0 {
+            warn!("Registry does not resume {}, starting over", digest);
+        }
+        std::fs::File::create(&target)?
+    };
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = response.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        crate::DOWNLOAD_LIMITER.throttle(read);
+        file.write_all(&buffer[..read])?;
+        if !progress(read as u64) {
+            return Err(BlobError::Stopped);
+        }
+    }
+    Ok(())
+}
+
+/// Check a blob file against a `sha256:<hex>` digest
+#[cfg(target_os = "linux")]
+fn blob_matches_digest(path: &std::path::Path, digest: &str) -> bool {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+    let Some(expected) = digest.strip_prefix("sha256:") else {
+        warn!("Unsupported docker digest {}", digest);
+        return false;
+    };
+    let mut file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) => {
+            error!("Unable to open docker blob {:?}: {}", path, e);
+            return false;
+        }
+    };
+    let mut hasher = Sha256::new();
+    if let Err(e) = std::io::copy(&mut file, &mut hasher) {
+        error!("Unable to read docker blob {:?}: {}", path, e);
+        return false;
+    }
+    let hash = hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut output, b| {
+            let _ = write!(output, "{b:02x}");
+            output
+        });
+    hash.eq_ignore_ascii_case(expected)
+}
@@ -1,6 +1,11 @@
+use crate::tools::scheduler::Priority;
 use crate::ui::widgets::download_manager::asset::Asset;
 use crate::ui::widgets::download_manager::docker::Docker;
 use crate::ui::widgets::download_manager::epic_file::EpicFile;
+use crate::ui::widgets::download_manager::local_install::LocalInstall;
+use crate::ui::widgets::download_manager::network::Network;
+use crate::ui::widgets::download_manager::queue::Queue;
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
 use crate::ui::widgets::download_manager::PostDownloadAction;
 use gtk4::glib::clone;
 use gtk4::{gio, glib, prelude::*, subclass::prelude::*, CompositeTemplate};
@@ -14,6 +19,8 @@ pub enum ItemType {
     Asset,
     Docker,
     Epic,
+    /// Engine installed from an archive on disk
+    Local,
 }
 
 pub mod imp {
@@ -49,11 +56,27 @@ pub mod imp {
         pub extracted_files: RefCell<u64>,
         pub post_actions: RefCell<Vec<crate::ui::widgets::download_manager::PostDownloadAction>>,
         pub speed_queue: RefCell<VecDeque<(chrono::DateTime<chrono::Utc>, u128)>>,
+        /// Last measured speed in bytes per second
+        pub current_speed: RefCell<u128>,
+        pub priority: RefCell<crate::tools::scheduler::Priority>,
+        pub started: RefCell<chrono::DateTime<chrono::Utc>>,
+        /// Set once the item was written to the download history as finished or canceled
+        pub recorded: RefCell<bool>,
+        /// Given up on, cleared once the download is retried or resumed
+        pub failed: RefCell<bool>,
+        /// Paused because of the network state, resumed once it allows downloads again
+        pub network_hold: RefCell<bool>,
+        /// Keep downloading on metered connections
+        pub download_anyway: RefCell<bool>,
         #[allow(dead_code)]
         thumbnail: RefCell<Option<Texture>>,
         #[template_child]
         pub pause_button: TemplateChild<gtk4::Button>,
         #[template_child]
+        pub status_label: TemplateChild<gtk4::Label>,
+        #[template_child]
+        pub size_label: TemplateChild<gtk4::Label>,
+        #[template_child]
         pub download_progress: TemplateChild<gtk4::ProgressBar>,
         #[template_child]
         pub extraction_progress: TemplateChild<gtk4::ProgressBar>,
@@ -87,8 +110,17 @@ pub mod imp {
                 extracted_files: RefCell::new(0),
                 post_actions: RefCell::new(vec![]),
                 speed_queue: RefCell::new(VecDeque::new()),
+                current_speed: RefCell::new(0),
+                priority: RefCell::new(crate::tools::scheduler::Priority::Normal),
+                started: RefCell::new(chrono::Utc::now()),
+                recorded: RefCell::new(false),
+                failed: RefCell::new(false),
+                network_hold: RefCell::new(false),
+                download_anyway: RefCell::new(false),
                 thumbnail: RefCell::new(None),
                 pause_button: TemplateChild::default(),
+                status_label: TemplateChild::default(),
+                size_label: TemplateChild::default(),
                 download_progress: TemplateChild::default(),
                 extraction_progress: TemplateChild::default(),
             }
@@ -172,10 +204,12 @@ pub mod imp {
                     }
                 }
                 "status" => {
-                    let _status = value
+                    let status = value
                         .get::<Option<String>>()
-                        .expect("type conformity checked by `Object::set_property`")
-                        .map(|l| format!("{l}"));
+                        .expect("type conformity checked by `Object::set_property`");
+                    self.status_label
+                        .set_visible(status.as_ref().is_some_and(|s| !s.is_empty()));
+                    self.status.replace(status);
                 }
                 "target" => {
                     let target = value
@@ -251,6 +285,7 @@ pub mod imp {
             self.parent_constructed();
             let obj = self.obj();
             obj.setup_actions();
+            obj.setup_drag();
             obj.setup_messaging();
             obj.setup_timer();
         }
@@ -319,6 +354,7 @@ impl EpicDownloadItem {
     fn speed_update(&self) {
         let self_ = self.imp();
         if self.canceled() || self.paused() {
+            self_.current_speed.replace(0);
             self.set_property("speed", "Paused/Cancelled".to_string());
             return;
         }
@@ -349,6 +385,7 @@ impl EpicDownloadItem {
                 None
             }
         } {
+            self_.current_speed.replace(speed);
             let byte = byte_unit::Byte::from_u128(speed)
                 .unwrap_or_default()
                 .get_appropriate_unit(byte_unit::UnitType::Decimal);
@@ -386,6 +423,92 @@ impl EpicDownloadItem {
             )
         );
         get_action!(self_.actions, @pause).set_enabled(false);
+
+        let priority = gio::SimpleAction::new_stateful(
+            "priority",
+            Some(glib::VariantTy::STRING),
+            &Priority::Normal.name().to_variant(),
+        );
+        priority.connect_activate(clone!(
+            #[weak(rename_to=item)]
+            self,
+            move |_, value| {
+                let self_ = item.imp();
+                if let (Some(dm), Some(name)) = (
+                    self_.download_manager.get(),
+                    value.and_then(glib::Variant::str),
+                ) {
+                    dm.set_item_priority(&item, Priority::from_name(name));
+                }
+            }
+        ));
+        self_.actions.add_action(&priority);
+
+        let download_anyway =
+            gio::SimpleAction::new_stateful("download_anyway", None, &false.to_variant());
+        download_anyway.connect_activate(clone!(
+            #[weak(rename_to=item)]
+            self,
+            move |action, _| {
+                let self_ = item.imp();
+                let enabled = !*self_.download_anyway.borrow();
+                self_.download_anyway.replace(enabled);
+                action.set_state(&enabled.to_variant());
+                if let Some(dm) = self_.download_manager.get() {
+                    dm.apply_network_state();
+                }
+            }
+        ));
+        self_.actions.add_action(&download_anyway);
+
+        action!(
+            self_.actions,
+            "move_top",
+            clone!(
+                #[weak(rename_to=item)]
+                self,
+                move |_, _| {
+                    let self_ = item.imp();
+                    if let Some(dm) = self_.download_manager.get() {
+                        dm.move_to_top(&item);
+                    }
+                }
+            )
+        );
+    }
+
+    /// Items can be dragged within the download list to change their order
+    fn setup_drag(&self) {
+        let source = gtk4::DragSource::builder()
+            .actions(gtk4::gdk::DragAction::MOVE)
+            .build();
+        source.connect_prepare(clone!(
+            #[weak(rename_to=item)]
+            self,
+            #[upgrade_or]
+            None,
+            move |_, _, _| Some(gtk4::gdk::ContentProvider::for_value(&item.to_value()))
+        ));
+        source.connect_drag_begin(clone!(
+            #[weak(rename_to=item)]
+            self,
+            move |source, _| {
+                source.set_icon(Some(&gtk4::WidgetPaintable::new(Some(&item))), 0, 0);
+            }
+        ));
+        self.add_controller(source);
+    }
+
+    pub fn priority(&self) -> Priority {
+        *self.imp().priority.borrow()
+    }
+
+    pub fn set_priority(&self, priority: Priority) {
+        let self_ = self.imp();
+        self_.priority.replace(priority);
+        if let Some(action) = self_.actions.lookup_action("priority") {
+            action.change_state(&priority.name().to_variant());
+        }
     }
 
     fn cancel(&self) {
@@ -405,6 +528,7 @@ impl EpicDownloadItem {
         }
 
         if let Some(dm) = self_.download_manager.get() {
+            dm.unqueue_item(self);
             match self.item_type() {
                 ItemType::Unknown => {}
                 ItemType::Asset => {
@@ -422,13 +546,26 @@ impl EpicDownloadItem {
                         dm.cancel_epic_download(v);
                     }
                 }
+                ItemType::Local => {
+                    if let Some(v) = self.version() {
+                        dm.cancel_local_install(v);
+                    }
+                }
             }
         }
         self.remove_from_parent_with_timer(15);
     }
 
+    /// Pause the download unless it is already paused
+    pub fn pause_download(&self) {
+        if !self.paused() && !self.canceled() {
+            self.pause();
+        }
+    }
+
     fn pause(&self) {
         let self_ = self.imp();
+        self_.network_hold.replace(false);
         get_action!(self_.actions, @pause).set_enabled(false);
         glib::timeout_add_seconds_local(
             2,
@@ -492,9 +629,74 @@ impl EpicDownloadItem {
                         }
                     }
                 }
+                ItemType::Local => {}
             }
         }
         self.set_property("paused", !self.paused());
+        if !self.paused() {
+            self_.failed.replace(false);
+            self.set_property("status", String::new());
+        }
+        if let Some(dm) = self_.download_manager.get() {
+            dm.set_queued_paused(self, self.paused());
+        }
+    }
+
+    /// Pause the download because the network does not allow it, returns true if it was running
+    pub fn hold_for_network(&self, reason: &str) -> bool {
+        let self_ = self.imp();
+        if *self_.network_hold.borrow() {
+            self.set_property("status", format!("Paused: {reason}"));
+            return false;
+        }
+        if self.paused() || self.canceled() || self.failed() {
+            return false;
+        }
+        self.pause();
+        self_.network_hold.replace(true);
+        self.set_property("status", format!("Paused: {reason}"));
+        // Only a pause by the user is kept over a restart
+        if let Some(dm) = self_.download_manager.get() {
+            dm.set_queued_paused(self, false);
+        }
+        true
+    }
+
+    /// Resume the download if it was paused because of the network
+    pub fn release_network_hold(&self) {
+        let self_ = self.imp();
+        if self_.network_hold.replace(false) && self.paused() && !self.canceled() {
+            self.pause();
+        }
+    }
+
+    pub fn download_anyway(&self) -> bool {
+        *self.imp().download_anyway.borrow()
+    }
+
+    pub fn failed(&self) -> bool {
+        *self.imp().failed.borrow()
+    }
+
+    /// Apply the state saved in the download queue when the item is restored after a restart
+    pub fn restore_state(&self, paused: bool, downloaded: u128, total: u128) {
+        let self_ = self.imp();
+        if total > 0 {
+            self_
+                .download_progress
+                .set_fraction(downloaded as f64 / total as f64);
+        }
+        if paused {
+            // Paused by the user, not to be resumed with the network
+            self_.network_hold.replace(false);
+            self.set_property("paused", true);
+            self.set_property("status", "Paused".to_string());
+            self_
+                .pause_button
+                .set_icon_name("media-playback-start-symbolic");
+            get_action!(self_.actions, @pause).set_enabled(true);
+            get_action!(self_.actions, @cancel).set_enabled(true);
+        }
     }
 
     pub fn setup_messaging(&self) {
@@ -506,6 +708,22 @@ impl EpicDownloadItem {
         self_.total_size.replace(size);
     }
 
+    /// Show how much of the full size actually needs to be downloaded when updating from a previous release
+    pub fn set_delta_size(&self, delta: u128) {
+        let self_ = self.imp();
+        let format = |size: u128| {
+            byte_unit::Byte::from_u128(size)
+                .unwrap_or_default()
+                .get_appropriate_unit(byte_unit::UnitType::Decimal)
+        };
+        self_.size_label.set_label(&format!(
+            "Update: {:.1} of {:.1}",
+            format(delta),
+            format(self.total_size())
+        ));
+        self_.size_label.set_visible(true);
+    }
+
     pub fn total_size(&self) -> u128 {
         let self_ = self.imp();
         *self_.total_size.borrow()
@@ -520,6 +738,10 @@ impl EpicDownloadItem {
         self.property("path")
     }
 
+    pub fn label(&self) -> Option<String> {
+        self.property("label")
+    }
+
     pub fn target(&self) -> Option<String> {
         self.property("target")
     }
@@ -536,6 +758,10 @@ impl EpicDownloadItem {
         self.property("release")
     }
 
+    pub fn speed(&self) -> u128 {
+        *self.imp().current_speed.borrow()
+    }
+
     pub fn paused(&self) -> bool {
         self.property("paused")
     }
@@ -553,9 +779,10 @@ impl EpicDownloadItem {
         self_.total_files.replace(count);
     }
 
+    /// Files checked or extracted while the download is paused or held count as well
     pub fn file_processed(&self) {
         let self_ = self.imp();
-        if self.canceled() || self.paused() {
+        if self.canceled() {
             return;
         }
         let new_count = *self_.extracted_files.borrow() + 1;
@@ -605,28 +832,47 @@ impl EpicDownloadItem {
         );
     }
 
+    /// Count data that was downloaded before the application restarted
+    pub fn add_restored_size(&self, size: u128) {
+        let self_ = self.imp();
+        let downloaded = *self_.downloaded_size.borrow() + size;
+        self_.downloaded_size.replace(downloaded);
+        let total = *self_.total_size.borrow();
+        if total > 0 {
+            self_
+                .download_progress
+                .set_fraction(downloaded as f64 / total as f64);
+        }
+    }
+
     pub fn add_downloaded_size(&self, size: u128) {
         let self_ = self.imp();
-        if self.canceled() || self.paused() {
+        if self.canceled() {
             return;
         }
-        get_action!(self_.actions, @cancel).set_enabled(true);
-        match self.item_type() {
-            ItemType::Unknown => {}
-            ItemType::Asset | ItemType::Docker => {
-                get_action!(self_.actions, @pause).set_enabled(true);
+        let old_size = self.downloaded_size();
+        // Data reused while paused still counts, but it is not a running download
+        if !self.paused() {
+            get_action!(self_.actions, @cancel).set_enabled(true);
+            match self.item_type() {
+                ItemType::Unknown => {}
+                ItemType::Asset | ItemType::Docker => {
+                    get_action!(self_.actions, @pause).set_enabled(true);
+                }
+                ItemType::Epic | ItemType::Local => {
+                    get_action!(self_.actions, @pause).set_enabled(false);
+                }
             }
-            ItemType::Epic => {
-                get_action!(self_.actions, @pause).set_enabled(false);
+            // Download Speed
+            {
+                let queue = &mut *self_.speed_queue.borrow_mut();
+                queue.push_back((chrono::Utc::now(), size));
+            };
+            if old_size == 0 {
+                // Data is flowing, preparation messages are no longer relevant
+                self.set_property("status", String::new());
             }
         }
-        // Download Speed
-        {
-            let queue = &mut *self_.speed_queue.borrow_mut();
-            queue.push_back((chrono::Utc::now(), size));
-        };
-
-        let old_size = self.downloaded_size();
         let new_size = old_size + size;
         let total = self.total_size();
         self_
@@ -638,6 +884,19 @@ impl EpicDownloadItem {
         }
     }
 
+    /// Take back progress of data that has to be downloaded again
+    pub fn remove_downloaded_size(&self, size: u128) {
+        let self_ = self.imp();
+        let new_size = self.downloaded_size().saturating_sub(size);
+        let total = self.total_size();
+        if total > 0 {
+            self_
+                .download_progress
+                .set_fraction(new_size as f64 / total as f64);
+        }
+        self_.downloaded_size.replace(new_size);
+    }
+
     pub fn add_extracted_size(&self, size: u128) {
         let self_ = self.imp();
         if self.canceled() || self.paused() {
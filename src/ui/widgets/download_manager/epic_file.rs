@@ -1,3 +1,7 @@
+use crate::ui::widgets::download_manager::disk_space::DiskSpace;
+use crate::ui::widgets::download_manager::network::Network;
+use crate::ui::widgets::download_manager::queue::{Queue, QueuedDownload};
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
 use crate::ui::widgets::download_manager::{download_item, Msg, ThreadMessages};
 use crate::ui::widgets::logged_in::refresh::Refresh;
 use egs_api::api::types::engine_blob::EngineBlob;
@@ -74,9 +78,25 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
         let Some(item) = self.get_item(version) else {
             return;
         };
-        item.set_property("status", "waiting for download slot".to_string());
         item.set_total_size(u128::from(size));
         item.set_total_files(1);
+        if let Some(engines) = self.engine_target_directory() {
+            let requirements = [
+                (engines.join("epic"), size),
+                (
+                    engines.clone(),
+                    size.saturating_mul(crate::tools::disk_space::EXTRACTION_FACTOR),
+                ),
+            ];
+            if !self.preflight(version, &requirements) {
+                return;
+            }
+        }
+        if item.paused() {
+            item.set_property("status", "Paused".to_string());
+            return;
+        }
+        item.set_property("status", "waiting for download slot".to_string());
         let (send, recv) = std::sync::mpsc::channel::<ThreadMessages>();
         self.add_thread_sender(version.to_string(), send);
         let sender = self_.sender.clone();
@@ -97,9 +117,12 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
         };
         p.push("epic");
         p.push(version);
-        self_.download_pool.execute(move || {
-            run(size, &recv, &sender, &link, ver, &mut p);
-        });
+        let retries = self.retry_limit();
+        self_
+            .scheduler
+            .execute(&self_.download_pool, vec![ver.clone()], move || {
+                run(size, &recv, &sender, &link, ver, &mut p, retries);
+            });
     }
 
     fn engine_target_directory(&self) -> Option<PathBuf> {
@@ -163,6 +186,14 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
             );
         }
         item.set_property("status", "initializing...".to_string());
+        self.queue_download(
+            version,
+            &QueuedDownload::Epic {
+                version: version.to_string(),
+            },
+            &None,
+            &[],
+        );
 
         item.connect_local(
             "finished",
@@ -186,6 +217,8 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
         )));
 
         self_.downloads.append(&item);
+        self.check_network(&item);
+        self.update_schedule();
 
         self.set_property("has-items", self_.downloads.first_child().is_some());
         self.start_version_file_download(version);
@@ -364,7 +397,9 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
                     let (send, recv) = std::sync::mpsc::channel::<ThreadMessages>();
                     self.add_thread_sender(version.to_string(), send);
                     self_.file_pool.execute(move || {
-                        extract(&target, archive, &sender, ver, &recv);
+                        if extract(&target, archive, &sender, &ver, &recv) {
+                            let _ = sender.send_blocking(Msg::EpicFileFinished(ver));
+                        }
                     });
                 }
             }
@@ -377,16 +412,18 @@ impl EpicFile for crate::ui::widgets::download_manager::EpicDownloadManager {
     }
 }
 
-fn extract(
+/// Extract an engine zip, returns false when it was interrupted
+pub fn extract(
     target: &std::path::Path,
     mut archive: ZipArchive<File>,
     sender: &async_channel::Sender<Msg>,
-    ver: String,
+    ver: &str,
     recv: &Receiver<ThreadMessages>,
-) {
+) -> bool {
+    let ver = ver.to_string();
     for i in 0..archive.len() {
         if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-            return;
+            return false;
         }
         let mut file_target = target.to_path_buf();
         let mut file = match archive.by_index(i) {
@@ -442,18 +479,18 @@ fn extract(
             let mut buffer: [u8; 1024] = [0; 1024];
             loop {
                 if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-                    return;
+                    return false;
                 }
                 match file.read(&mut buffer) {
                     Ok(size) => {
                         if let Ok(m) = recv.try_recv() {
                             process_epic_thread_message(ver, sender, &m);
-                            return;
+                            return false;
                         }
                         if size > 0 {
                             if let Err(e) = outfile.write_all(&buffer[0..size]) {
                                 error!("Unable to write extracted file {:?}: {}", file_target, e);
-                                return;
+                                return false;
                             }
                             let _ = sender.send_blocking(Msg::EpicFileExtractionProgress(
                                 ver.clone(),
@@ -486,7 +523,7 @@ fn extract(
         }
         let _ = sender.send_blocking(Msg::EpicFileExtracted(ver.clone()));
     }
-    let _ = sender.send_blocking(Msg::EpicFileFinished(ver));
+    true
 }
 
 fn run(
@@ -496,6 +533,7 @@ fn run(
     link: &Url,
     ver: String,
     p: &mut PathBuf,
+    retries: u32,
 ) {
     if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
         return;
@@ -518,82 +556,115 @@ fn run(
         error!("Unable to create download directory {:?}: {}", parent, e);
         return;
     }
-    let mut client = if p.exists() {
-        let metadata = match fs::metadata(p.as_path()) {
-            Ok(m) => m,
-            Err(e) => {
-                error!("Unable to read metadata for {:?}: {}", p, e);
+    // Bytes already reported as progress, so resuming does not count them twice
+    let mut progress = crate::tools::resume::Progress::default();
+    let mut report = |position: u64| {
+        let new = progress.advance(&ver, u128::from(position));
+        if new > 0 {
+            let _ = sender.send_blocking(Msg::EpicDownloadProgress(
+                ver.clone(),
+                u64::try_from(new).unwrap_or(u64::MAX),
+            ));
+        }
+    };
+    'attempts: for attempt in 0..=retries {
+        if attempt > 0 {
+            let delay = crate::tools::retry::jittered_backoff(attempt);
+            warn!(
+                "Resuming engine download in {:?} (attempt {}/{})",
+                delay, attempt, retries
+            );
+            std::thread::sleep(delay);
+        }
+        let existing = if p.exists() {
+            let metadata = match fs::metadata(p.as_path()) {
+                Ok(m) => m,
+                Err(e) => {
+                    error!("Unable to read metadata for {:?}: {}", p, e);
+                    return;
+                }
+            };
+            if metadata.size() == size {
+                debug!("Already downloaded {}", p.to_str().unwrap_or_default());
+                report(size);
+                let _ = sender.send_blocking(Msg::EpicFileFinished(ver));
                 return;
+            };
+            // Anything bigger than the expected size cannot be resumed
+            if metadata.size() < size {
+                metadata.size()
+            } else {
+                0
             }
+        } else {
+            0
         };
-        if metadata.size() == size {
-            debug!("Already downloaded {}", p.to_str().unwrap_or_default());
-            let _ = sender.send_blocking(Msg::EpicDownloadProgress(ver.clone(), size));
-            let _ = sender.send_blocking(Msg::EpicFileFinished(ver));
-            return;
-        };
-
-        let c = &crate::HTTP_CLIENT;
-        debug!("Trying to resume download");
-        match c
-            .get(link.clone())
-            .header(
-                reqwest::header::RANGE,
-                format!("bytes={}-{}", metadata.size(), size),
-            )
-            .send()
-        {
+        let (mut client, offset) = match crate::tools::resume::resume_download(link, existing) {
             Ok(c) => c,
             Err(e) => {
-                error!("Failed to resume Engine download: {}", e);
+                error!("Failed to start Engine download: {}", e);
+                if attempt < retries {
+                    continue 'attempts;
+                }
+                let _ = sender.send_blocking(Msg::EpicFailed(
+                    ver,
+                    format!("download failed after {retries} retries: {e}"),
+                ));
                 return;
             }
-        }
-    } else {
-        match crate::HTTP_CLIENT.get(link.clone()).send() {
-            Ok(c) => c,
+        };
+        let mut buffer: [u8; 1024] = [0; 1024];
+        let file = if offset > 0 {
+            File::options().append(true).open(&p)
+        } else {
+            File::create(&p)
+        };
+        let mut file = match file {
+            Ok(file) => file,
             Err(e) => {
-                error!("Failed to start Engine download: {}", e);
+                error!("Unable to create download file {:?}: {}", p, e);
                 return;
             }
-        }
-    };
-    let mut buffer: [u8; 1024] = [0; 1024];
-    let mut file = match File::create(&p) {
-        Ok(file) => file,
-        Err(e) => {
-            error!("Unable to create download file {:?}: {}", p, e);
-            return;
-        }
-    };
-    loop {
-        if let Ok(m) = recv.try_recv() {
-            process_epic_thread_message(ver, sender, &m);
-            return;
-        }
-        if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
-            return;
-        }
-        match client.read(&mut buffer) {
-            Ok(size) => {
-                if let Ok(m) = recv.try_recv() {
-                    process_epic_thread_message(ver, sender, &m);
-                    return;
-                }
-                if size > 0 {
-                    if let Err(e) = file.write_all(&buffer[0..size]) {
-                        error!("Unable to write download file {:?}: {}", p, e);
+        };
+        let mut position = offset;
+        report(position);
+        loop {
+            if let Ok(m) = recv.try_recv() {
+                process_epic_thread_message(ver, sender, &m);
+                return;
+            }
+            if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+                return;
+            }
+            match client.read(&mut buffer) {
+                Ok(size) => {
+                    if let Ok(m) = recv.try_recv() {
+                        process_epic_thread_message(ver, sender, &m);
                         return;
                     }
-                    let _ =
-                        sender.send_blocking(Msg::EpicDownloadProgress(ver.clone(), size as u64));
-                } else {
-                    break;
+                    if size > 0 {
+                        crate::DOWNLOAD_LIMITER.throttle(size);
+                        if let Err(e) = file.write_all(&buffer[0..size]) {
+                            error!("Unable to write download file {:?}: {}", p, e);
+                            return;
+                        }
+                        position += size as u64;
+                        report(position);
+                    } else {
+                        break 'attempts;
+                    }
+                }
+                Err(e) => {
+                    error!("Download error: {:?}", e);
+                    if attempt < retries {
+                        continue 'attempts;
+                    }
+                    let _ = sender.send_blocking(Msg::EpicFailed(
+                        ver,
+                        format!("download failed after {retries} retries: {e}"),
+                    ));
+                    return;
                 }
-            }
-            Err(e) => {
-                error!("Download error: {:?}", e);
-                break;
             }
         }
     }
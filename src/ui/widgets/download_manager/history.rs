@@ -0,0 +1,80 @@
+use crate::models::history_data::Outcome;
+use crate::schema::download_history;
+use crate::ui::widgets::download_manager::download_item::{EpicDownloadItem, ItemType};
+use crate::ui::widgets::download_manager::PostDownloadAction;
+use diesel::{ExpressionMethods, RunQueryDsl};
+use gtk4::subclass::prelude::*;
+use log::{debug, error};
+
+pub trait History {
+    /// Store the result of a download in the history, an item finishes or gets canceled only once
+    /// while every time it is given up on counts as a failure
+    fn record_history(&self, _item: &EpicDownloadItem, _outcome: Outcome, _error: Option<String>) {
+        unimplemented!()
+    }
+}
+
+impl History for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn record_history(&self, item: &EpicDownloadItem, outcome: Outcome, error: Option<String>) {
+        let self_ = self.imp();
+        if outcome != Outcome::Failed && item.imp().recorded.replace(true) {
+            return;
+        }
+        let (item_id, item_type) = match item.item_type() {
+            ItemType::Unknown => return,
+            ItemType::Asset => (item.asset(), "asset"),
+            ItemType::Docker => (item.version(), "docker"),
+            ItemType::Epic => (item.version(), "epic"),
+            ItemType::Local => (item.version(), "local"),
+        };
+        let Some(item_id) = item_id else {
+            return;
+        };
+        let started_at = item.imp().started.borrow().timestamp();
+        let finished_at = chrono::Utc::now().timestamp();
+        let bytes = i64::try_from(*item.imp().downloaded_size.borrow()).unwrap_or(i64::MAX);
+        let average_speed = bytes / (finished_at - started_at).max(1);
+
+        let mut targets: Vec<String> = Vec::new();
+        let copies = item.actions().into_iter().filter_map(|a| match a {
+            PostDownloadAction::Copy(path, _) => Some(path),
+            PostDownloadAction::NoVault => None,
+        });
+        for path in item.target().into_iter().chain(item.path()).chain(copies) {
+            if !targets.contains(&path) {
+                targets.push(path);
+            }
+        }
+        let targets = serde_json::to_string(&targets).unwrap_or_else(|_| "[]".to_string());
+
+        debug!("Recording {} download of {}", outcome.name(), item_id);
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            if let Err(e) = diesel::insert_into(download_history::table)
+                .values((
+                    download_history::item_id.eq(&item_id),
+                    download_history::item_type.eq(item_type),
+                    download_history::label.eq(item.label().unwrap_or_else(|| item_id.clone())),
+                    download_history::release.eq(item.release().or_else(|| item.version())),
+                    download_history::bytes.eq(bytes),
+                    download_history::started_at.eq(started_at),
+                    download_history::finished_at.eq(finished_at),
+                    download_history::average_speed.eq(average_speed),
+                    download_history::outcome.eq(outcome.name()),
+                    download_history::error.eq(&error),
+                    download_history::targets.eq(&targets),
+                ))
+                .execute(&mut conn)
+            {
+                error!("Unable to record download history for {}: {}", item_id, e);
+                return;
+            }
+        }
+
+        if let Some(w) = self_.window.get() {
+            let w_ = w.imp();
+            let l = w_.logged_in_stack.clone();
+            l.imp().history.refresh();
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use crate::tools::image_archive::{self, ArchiveKind};
+use crate::ui::widgets::download_manager::epic_file::{extract, EpicFile};
+use crate::ui::widgets::download_manager::{download_item, Msg, ThreadMessages};
+use crate::ui::widgets::logged_in::refresh::Refresh;
+use glib::clone;
+use gtk4::glib;
+use gtk4::subclass::prelude::*;
+use gtk4::{self, prelude::*};
+use log::{debug, error, warn};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use zip::ZipArchive;
+
+pub trait LocalInstall {
+    /// Install an engine from an Epic zip or an image tarball without network access
+    fn install_engine_from_file(&self, _path: &Path) {
+        unimplemented!()
+    }
+
+    fn local_install_prepared(&self, _id: &str, _files: u64) {
+        unimplemented!()
+    }
+
+    fn local_install_progress(&self, _id: &str, _size: u64) {
+        unimplemented!()
+    }
+
+    fn local_install_failed(&self, _id: &str, _reason: &str) {
+        unimplemented!()
+    }
+
+    fn cancel_local_install(&self, _id: String) {
+        unimplemented!()
+    }
+
+    fn local_install_finished(&self, _item: &download_item::EpicDownloadItem) {
+        unimplemented!()
+    }
+}
+
+impl LocalInstall for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn install_engine_from_file(&self, path: &Path) {
+        let self_ = self.imp();
+        let id = path.to_string_lossy().to_string();
+        let mut header = [0u8; 512];
+        let kind = File::open(path)
+            .and_then(|mut f| f.read(&mut header))
+            .ok()
+            .and_then(|size| ArchiveKind::detect(&header[..size]));
+        let Some(kind) = kind else {
+            if let Some(w) = self_.window.get() {
+                w.add_notification(
+                    "local-install",
+                    &format!("{} is not an engine zip or image tarball", path.display()),
+                    gtk4::MessageType::Error,
+                );
+            }
+            return;
+        };
+        let Some(engines) = self.engine_target_directory() else {
+            return;
+        };
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+
+        let item = {
+            let mut state = self_.state.borrow_mut();
+            if state.download_items.contains_key(&id) {
+                return;
+            }
+            let item = download_item::EpicDownloadItem::new();
+            debug!("Adding local install to the list under: {}", id);
+            state.download_items.insert(id.clone(), item.clone());
+            item
+        };
+        if let Some(w) = self_.window.get() {
+            item.set_window(w);
+        }
+        item.set_download_manager(self);
+        item.set_property("version", id.as_str());
+        item.set_property("item-type", download_item::ItemType::Local);
+        item.set_property(
+            "label",
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| id.clone()),
+        );
+        item.set_property("status", "reading archive...".to_string());
+        item.set_property("thumbnail", Some(gtk4::gdk::Texture::from_resource(
+            "/io/github/achetagames/epic_asset_manager/icons/scalable/emblems/ue-logo-symbolic.svg",
+        )));
+        item.set_total_size(u128::from(size));
+
+        item.connect_local(
+            "finished",
+            false,
+            clone!(
+                #[weak(rename_to=edm)]
+                self,
+                #[weak]
+                item,
+                #[upgrade_or]
+                None,
+                move |_| {
+                    edm.local_install_finished(&item);
+                    None
+                }
+            ),
+        );
+
+        self_.downloads.append(&item);
+        self.set_property("has-items", self_.downloads.first_child().is_some());
+
+        let (send, recv) = std::sync::mpsc::channel::<ThreadMessages>();
+        self.add_thread_sender(id.clone(), send);
+        let sender = self_.sender.clone();
+        let archive = path.to_path_buf();
+        match kind {
+            ArchiveKind::Zip => {
+                // Nothing to download, the archive is already complete
+                item.add_downloaded_size(u128::from(size));
+                let Some(version) = image_archive::zip_engine_version(path) else {
+                    warn!("Unable to tell the engine version of {:?}", path);
+                    self_.state.borrow_mut().thread_senders.remove(&id);
+                    self.local_install_failed(&id, "unable to tell the engine version");
+                    return;
+                };
+                let target = engines.join(version);
+                self_.file_pool.execute(move || {
+                    install_zip(&archive, &target, &sender, &id, &recv);
+                });
+            }
+            ArchiveKind::Image => {
+                self_.file_pool.execute(move || {
+                    install_image(&archive, &engines, &sender, &id, &recv);
+                });
+            }
+        }
+    }
+
+    fn local_install_prepared(&self, id: &str, files: u64) {
+        if let Some(item) = self.get_item(id) {
+            item.set_total_files(files);
+            item.set_property("status", "extracting...".to_string());
+        }
+    }
+
+    fn local_install_progress(&self, id: &str, size: u64) {
+        if let Some(item) = self.get_item(id) {
+            item.add_downloaded_size(u128::from(size));
+            self.emit_by_name::<()>("tick", &[]);
+        }
+    }
+
+    fn local_install_failed(&self, id: &str, reason: &str) {
+        if let Some(item) = self.get_item(id) {
+            self.fail(&item, reason);
+        }
+    }
+
+    fn cancel_local_install(&self, id: String) {
+        let self_ = self.imp();
+        if let Some(item) = self.get_item(&id) {
+            self.send_to_thread_sender(&id, &ThreadMessages::Cancel);
+            item.set_property("status", "Canceled".to_string());
+            item.set_property("speed", String::new());
+            self_.state.borrow_mut().download_items.remove(&id);
+        }
+    }
+
+    fn local_install_finished(&self, item: &download_item::EpicDownloadItem) {
+        let self_ = self.imp();
+        if let Some(window) = self_.window.get() {
+            let win_ = window.imp();
+            let l = win_.logged_in_stack.imp();
+            l.engines.load_engines();
+            l.engines.run_refresh();
+        }
+        self.finish(item);
+    }
+}
+
+fn install_zip(
+    archive: &Path,
+    target: &Path,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    recv: &Receiver<ThreadMessages>,
+) {
+    let zip = match File::open(archive)
+        .map_err(|e| e.to_string())
+        .and_then(|f| ZipArchive::new(f).map_err(|e| e.to_string()))
+    {
+        Ok(zip) => zip,
+        Err(e) => {
+            error!("Unable to read zip archive {:?}: {}", archive, e);
+            let _ = sender.send_blocking(Msg::LocalInstallFailed(id.to_string(), e));
+            return;
+        }
+    };
+    if zip.is_empty() {
+        let _ = sender.send_blocking(Msg::LocalInstallFailed(
+            id.to_string(),
+            "the archive is empty".to_string(),
+        ));
+        return;
+    }
+    let _ = sender.send_blocking(Msg::LocalInstallPrepared(id.to_string(), zip.len() as u64));
+    extract(target, zip, sender, id, recv);
+}
+
+#[cfg(target_os = "linux")]
+fn install_image(
+    archive: &Path,
+    engines: &Path,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    recv: &Receiver<ThreadMessages>,
+) {
+    let name = archive
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // Layers are written next to the blobs of registry downloads and removed afterwards
+    let layers_dir = engines.join("docker").join(format!("local-{name}"));
+    let result = unpack_image(archive, engines, &layers_dir, &name, sender, id, recv);
+    if let Err(e) = std::fs::remove_dir_all(&layers_dir) {
+        warn!("Unable to remove {:?}: {}", layers_dir, e);
+    }
+    match result {
+        Ok(()) | Err(None) => {}
+        Err(Some(e)) => {
+            error!("Unable to install {:?}: {}", archive, e);
+            let _ = sender.send_blocking(Msg::LocalInstallFailed(id.to_string(), e));
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn install_image(
+    _archive: &Path,
+    _engines: &Path,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    _recv: &Receiver<ThreadMessages>,
+) {
+    let _ = sender.send_blocking(Msg::LocalInstallFailed(
+        id.to_string(),
+        "image tarballs are only supported on Linux".to_string(),
+    ));
+}
+
+/// Check if the install was canceled or the application is closing
+#[cfg(target_os = "linux")]
+fn interrupted(recv: &Receiver<ThreadMessages>) -> bool {
+    !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) || recv.try_recv().is_ok()
+}
+
+/// Copy the layers out of the tarball and render them into the engine directory.
+/// `Err(None)` means the install was interrupted.
+#[cfg(target_os = "linux")]
+fn unpack_image(
+    archive: &Path,
+    engines: &Path,
+    layers_dir: &Path,
+    name: &str,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    recv: &Receiver<ThreadMessages>,
+) -> Result<(), Option<String>> {
+    use std::collections::HashMap;
+
+    let open = || -> Result<tar::Archive<File>, Option<String>> {
+        File::open(archive)
+            .map(tar::Archive::new)
+            .map_err(|e| Some(e.to_string()))
+    };
+
+    // The manifests are small, read them first to learn the layer order
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    for entry in open()?
+        .entries_with_seek()
+        .map_err(|e| Some(e.to_string()))?
+    {
+        let mut entry = entry.map_err(|e| Some(e.to_string()))?;
+        if entry.size() > image_archive::MAX_MANIFEST_SIZE {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map_err(|e| Some(e.to_string()))?
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .to_string();
+        let mut data = Vec::new();
+        if entry.read_to_end(&mut data).is_ok() {
+            files.insert(path, data);
+        }
+    }
+    let layout = image_archive::image_layout(&files)
+        .ok_or_else(|| Some("no image manifest found in the archive".to_string()))?;
+    if layout.layers.is_empty() {
+        return Err(Some("the image has no layers".to_string()));
+    }
+    let _ = sender.send_blocking(Msg::LocalInstallPrepared(
+        id.to_string(),
+        layout.layers.len() as u64,
+    ));
+
+    std::fs::create_dir_all(layers_dir).map_err(|e| Some(e.to_string()))?;
+    let mut layer_files: HashMap<String, PathBuf> = HashMap::new();
+    for entry in open()?.entries().map_err(|e| Some(e.to_string()))? {
+        if interrupted(recv) {
+            return Err(None);
+        }
+        let mut entry = entry.map_err(|e| Some(e.to_string()))?;
+        let path = entry
+            .path()
+            .map_err(|e| Some(e.to_string()))?
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .to_string();
+        let Some(position) = layout.layers.iter().position(|l| l.eq(&path)) else {
+            let _ = sender.send_blocking(Msg::LocalInstallProgress(id.to_string(), entry.size()));
+            continue;
+        };
+        let target = layers_dir.join(format!("{position:04}"));
+        copy_layer(&mut entry, &target, sender, id, recv)?;
+        layer_files.insert(path, target);
+    }
+
+    let tag = layout.tag.clone().unwrap_or_else(|| name.to_string());
+    let target = engines.join(&tag);
+    std::fs::create_dir_all(&target).map_err(|e| Some(e.to_string()))?;
+    let target = target.canonicalize().map_err(|e| Some(e.to_string()))?;
+    for layer in &layout.layers {
+        if interrupted(recv) {
+            return Err(None);
+        }
+        let file = layer_files
+            .get(layer)
+            .ok_or_else(|| Some(format!("layer {layer} is missing from the archive")))?;
+        ghregistry::render::unpack_partial_files(
+            vec![file.to_string_lossy().to_string()],
+            &target,
+            "home/ue4/UnrealEngine/",
+        )
+        .map_err(|e| Some(e.to_string()))?;
+        if let Err(e) = std::fs::remove_file(file) {
+            warn!("Unable to remove layer {:?}: {}", file, e);
+        }
+        let _ = sender.send_blocking(Msg::EpicFileExtracted(id.to_string()));
+    }
+    Ok(())
+}
+
+/// Write a layer to disk, `docker save` stores them uncompressed but the renderer expects gzip
+#[cfg(target_os = "linux")]
+fn copy_layer(
+    entry: &mut impl Read,
+    target: &Path,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    recv: &Receiver<ThreadMessages>,
+) -> Result<(), Option<String>> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(File::create(target).map_err(|e| Some(e.to_string()))?);
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let size = entry.read(&mut buffer).map_err(|e| Some(e.to_string()))?;
+    if image_archive::is_gzip(&buffer[..size]) {
+        pump(entry, &mut file, &mut buffer, size, sender, id, recv)?;
+        file.flush().map_err(|e| Some(e.to_string()))
+    } else {
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+        pump(entry, &mut encoder, &mut buffer, size, sender, id, recv)?;
+        encoder
+            .finish()
+            .and_then(|mut f| f.flush())
+            .map_err(|e| Some(e.to_string()))
+    }
+}
+
+/// Write the `size` bytes already in `buffer` and the rest of `entry` to `output`
+#[cfg(target_os = "linux")]
+fn pump(
+    entry: &mut impl Read,
+    output: &mut impl std::io::Write,
+    buffer: &mut [u8],
+    mut size: usize,
+    sender: &async_channel::Sender<Msg>,
+    id: &str,
+    recv: &Receiver<ThreadMessages>,
+) -> Result<(), Option<String>> {
+    while size > 0 {
+        if interrupted(recv) {
+            return Err(None);
+        }
+        output
+            .write_all(&buffer[..size])
+            .map_err(|e| Some(e.to_string()))?;
+        let _ = sender.send_blocking(Msg::LocalInstallProgress(id.to_string(), size as u64));
+        size = entry.read(buffer).map_err(|e| Some(e.to_string()))?;
+    }
+    Ok(())
+}
@@ -1,12 +1,35 @@
 pub mod asset;
+pub mod chunk_store;
+pub mod delta;
+pub mod disk_space;
 pub mod docker;
 mod download_item;
 pub mod epic_file;
-
+pub mod history;
+pub mod local_install;
+pub mod network;
+pub mod notifications;
+pub mod pools;
+pub mod queue;
+pub mod scheduling;
+pub mod updates;
+pub mod vault;
+
+use crate::models::history_data::Outcome;
 use crate::ui::widgets::download_manager::asset::Asset;
+use crate::ui::widgets::download_manager::chunk_store::ChunkStore;
+use crate::ui::widgets::download_manager::disk_space::DiskSpace;
 use crate::ui::widgets::download_manager::docker::Docker;
 use crate::ui::widgets::download_manager::download_item::EpicDownloadItem;
 use crate::ui::widgets::download_manager::epic_file::EpicFile;
+use crate::ui::widgets::download_manager::history::History;
+use crate::ui::widgets::download_manager::local_install::LocalInstall;
+use crate::ui::widgets::download_manager::network::Network;
+use crate::ui::widgets::download_manager::notifications::{Event, Notifications};
+use crate::ui::widgets::download_manager::pools::Pools;
+use crate::ui::widgets::download_manager::queue::Queue;
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
+use crate::ui::widgets::download_manager::vault::Vault;
 use glib::clone;
 use gtk4::gdk::Texture;
 use gtk4::subclass::prelude::*;
@@ -14,6 +37,7 @@ use gtk4::{self, gio, glib, prelude::*, CompositeTemplate};
 use gtk_macros::action;
 use log::{debug, error, info, warn};
 use reqwest::Url;
+use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::Write;
@@ -41,13 +65,17 @@ pub enum Msg {
     RedownloadChunk(Url, PathBuf, String),
     PauseChunk(Url, PathBuf, String),
     CancelChunk(Url, PathBuf, String),
+    ChunkCorrupted(Url, PathBuf, String),
+    ChunkFailed(Url, PathBuf, String, String),
+    /// Bytes of the chunk on disk and whether it is complete
     ChunkDownloadProgress(String, u128, bool),
     FinalizeFileDownload(String, asset::DownloadedFile),
     FileAlreadyDownloaded(String, u128, String, String),
     FileExtracted(String),
     PerformDockerEngineDownload(String, u64, Vec<(String, u64)>),
-    DockerDownloadProgress(String, u64),
-    DockerBlobFinished(String, String),
+    DockerDownloadProgress(String, String, u64),
+    DockerBlobFinished(String, (String, u64)),
+    DockerBlobVerified(String, (String, u64), bool),
     DockerBlobFailed(String, (String, u64)),
     DockerExtractionFinished(String),
     DockerCanceled(String, (String, u64)),
@@ -55,23 +83,45 @@ pub enum Msg {
     EpicDownloadStart(String, String, u64),
     EpicCanceled(String),
     EpicPaused(String),
+    EpicFailed(String, String),
     EpicFileFinished(String),
     EpicFileExtracted(String),
     EpicFileExtractionProgress(String, u64),
+    LocalInstallPrepared(String, u64),
+    LocalInstallProgress(String, u64),
+    LocalInstallFailed(String, String),
     EpicDownloadProgress(String, u64),
     IOError(String),
     FileHashMismatch(String, asset::DownloadedFile),
+    DeltaPlanned(
+        String,
+        PathBuf,
+        bool,
+        Box<egs_api::api::types::download_manifest::DownloadManifest>,
+        Option<std::sync::Arc<delta::DeltaPlan>>,
+    ),
+    VaultVerified(
+        Option<Box<egs_api::api::types::asset_info::AssetInfo>>,
+        PathBuf,
+        vault::VerifyReport,
+        bool,
+    ),
+    VaultsVerified(usize, usize),
+    CopyEstimated(Vec<crate::tools::copy::Estimate>),
+    VaultsCleaned(u64, usize),
 }
 
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
     Init,
     Downloaded,
+    Verifying,
+    Verified,
     Extracting,
     Extracted,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PostDownloadAction {
     Copy(String, bool),
     NoVault,
@@ -90,7 +140,7 @@ pub mod imp {
     use gtk4::glib::{ParamSpec, ParamSpecBoolean};
     use once_cell::sync::OnceCell;
     use std::cell::RefCell;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use threadpool::ThreadPool;
 
     #[derive(Debug, Default)]
@@ -103,11 +153,41 @@ pub mod imp {
         pub paused_docker_digests: HashMap<String, Vec<(String, u64)>>,
         pub thread_senders: HashMap<String, Vec<std::sync::mpsc::Sender<super::ThreadMessages>>>,
         pub chunk_urls: HashMap<String, Vec<Url>>,
+        /// Chunk hashes from the manifests, part of the chunk store key (key: chunk guid)
+        pub chunk_hashes: HashMap<String, u128>,
+        /// Chunks that are downloaded and waiting in the chunk store for extraction
+        pub completed_chunks: HashSet<String>,
         pub docker_digests: HashMap<String, Vec<(String, super::DownloadStatus)>>,
+        /// Bytes of a docker blob already counted as progress, resumed blobs report them again (key: digest)
+        pub docker_reported: HashMap<String, u64>,
+        /// Layers extracted or verified before the application restarted (key: version)
+        pub restored_docker_layers: HashMap<String, Vec<(String, super::DownloadStatus)>>,
+        /// Downloads of a docker blob that did not match its digest (key: digest)
+        pub docker_retries: HashMap<String, u32>,
+        /// Bytes of each chunk already counted towards the progress of its item (key: chunk guid)
+        pub chunk_progress: crate::tools::resume::Progress,
         /// Retry counts for chunk downloads (key: chunk guid)
         pub chunk_retries: HashMap<String, u32>,
+        /// Last error of a chunk download, reported when giving up (key: chunk guid)
+        pub chunk_errors: HashMap<String, String>,
+        /// Mirror a chunk is currently downloaded from (key: chunk guid)
+        pub chunk_links: HashMap<String, Url>,
+        /// Failures of the CDN hosts, used to switch to another mirror
+        pub host_health: crate::tools::retry::HostHealth,
         /// Retry counts for file hash validation (key: file path)
         pub file_retries: HashMap<String, u32>,
+        /// App names of the other releases of an asset, used to find files to reuse (key: release id)
+        pub previous_releases: HashMap<String, Vec<String>>,
+        /// Files to download again when repairing a vault copy (key: release id)
+        pub repair_files: HashMap<String, HashSet<String>>,
+        /// Overall progress at the last stall check
+        pub progress_seen: f32,
+        /// When the overall progress last changed
+        pub progress_changed: chrono::DateTime<chrono::Utc>,
+        /// Stuck downloads were already reported since the last progress
+        pub stall_reported: bool,
+        /// Progress in per mille and item count last published to docks
+        pub launcher_entry: Option<(i64, i64)>,
     }
 
     #[derive(Debug, CompositeTemplate)]
@@ -117,9 +197,12 @@ pub mod imp {
         pub settings: gio::Settings,
         pub window: OnceCell<EpicAssetManagerWindow>,
         pub download_pool: ThreadPool,
+        /// Picks the next job for the download pool by item priority and position
+        pub scheduler: crate::tools::scheduler::Scheduler,
         pub thumbnail_pool: ThreadPool,
         pub image_pool: ThreadPool,
         pub file_pool: ThreadPool,
+        pub auto_concurrency: RefCell<super::pools::AutoConcurrency>,
         pub sender: async_channel::Sender<Msg>,
         pub receiver: RefCell<Option<async_channel::Receiver<Msg>>>,
         pub state: RefCell<DownloadState>,
@@ -142,12 +225,14 @@ pub mod imp {
                 window: OnceCell::new(),
                 sender,
                 download_pool: ThreadPool::with_name("Download Pool".to_string(), 5),
+                scheduler: crate::tools::scheduler::Scheduler::new(),
                 receiver: RefCell::new(Some(receiver)),
                 state: RefCell::new(DownloadState::default()),
                 downloads: TemplateChild::default(),
                 thumbnail_pool: ThreadPool::with_name("Thumbnail Pool".to_string(), 5),
                 image_pool: ThreadPool::with_name("Image Pool".to_string(), 5),
                 file_pool: ThreadPool::with_name("File Pool".to_string(), 1),
+                auto_concurrency: RefCell::new(super::pools::AutoConcurrency::default()),
                 has_children: RefCell::new(false),
             }
         }
@@ -168,6 +253,12 @@ pub mod imp {
             let obj = self.obj();
             obj.setup_actions();
             obj.setup_messaging();
+            obj.setup_bandwidth_limit();
+            obj.setup_pools();
+            obj.setup_reordering();
+            obj.setup_disk_space_monitor();
+            obj.setup_notifications();
+            obj.setup_network_monitor();
         }
 
         fn signals() -> &'static [gtk4::glib::subclass::Signal] {
@@ -274,6 +365,7 @@ impl EpicDownloadManager {
         }
 
         self_.window.set(window.clone()).unwrap();
+        self.restore_downloads();
     }
 
     pub fn setup_actions(&self) {
@@ -298,6 +390,38 @@ impl EpicDownloadManager {
         self.insert_action_group("download_manager", Some(&self_.actions));
     }
 
+    /// Keep the shared download limiter in sync with the settings
+    fn setup_bandwidth_limit(&self) {
+        let self_ = self.imp();
+        Self::apply_bandwidth_limit(&self_.settings);
+        for key in [
+            "download-speed-limit",
+            "download-schedule-enabled",
+            "download-schedule-start",
+            "download-schedule-end",
+            "download-schedule-limit",
+        ] {
+            self_.settings.connect_changed(Some(key), |settings, _key| {
+                Self::apply_bandwidth_limit(settings);
+            });
+        }
+    }
+
+    fn apply_bandwidth_limit(settings: &gio::Settings) {
+        let limit = u64::try_from(settings.int("download-speed-limit")).unwrap_or_default() * 1024;
+        let schedule = if settings.boolean("download-schedule-enabled") {
+            crate::tools::bandwidth::Schedule::new(
+                &settings.string("download-schedule-start"),
+                &settings.string("download-schedule-end"),
+                u64::try_from(settings.int("download-schedule-limit")).unwrap_or_default() * 1024,
+            )
+        } else {
+            None
+        };
+        debug!("Download limit {} B/s, schedule {:?}", limit, schedule);
+        crate::DOWNLOAD_LIMITER.configure(limit, schedule);
+    }
+
     pub fn setup_messaging(&self) {
         let self_ = self.imp();
         let receiver = self_.receiver.borrow_mut().take().unwrap();
@@ -337,6 +461,9 @@ impl EpicDownloadManager {
             Msg::RedownloadChunk(link, path, guid) => {
                 self.redownload_chunk(&link, path, &guid);
             }
+            Msg::ChunkFailed(link, path, guid, reason) => {
+                self.chunk_failed(&link, path, &guid, reason);
+            }
             Msg::ChunkDownloadProgress(guid, size, finished) => {
                 self.chunk_progress_report(&guid, size, finished);
             }
@@ -356,12 +483,15 @@ impl EpicDownloadManager {
             Msg::PerformDockerEngineDownload(version, size, digests) => {
                 self.perform_docker_blob_downloads(&version, size, digests);
             }
-            Msg::DockerDownloadProgress(version, progress) => {
-                self.docker_download_progress(&version, progress);
+            Msg::DockerDownloadProgress(version, digest, progress) => {
+                self.docker_download_progress(&version, &digest, progress);
             }
             Msg::DockerBlobFinished(version, digest) => {
-                debug!("Finished download of {} digest {}", version, digest);
-                self.docker_blob_finished(&version, &digest);
+                debug!("Finished download of {} digest {}", version, digest.0);
+                self.docker_blob_finished(&version, digest);
+            }
+            Msg::DockerBlobVerified(version, digest, valid) => {
+                self.docker_blob_verified(&version, digest, valid);
             }
             Msg::DockerBlobFailed(version, digest) => {
                 self.download_docker_digest(&version, digest);
@@ -384,9 +514,44 @@ impl EpicDownloadManager {
             Msg::CancelChunk(_url, path, guid) => {
                 self.remove_chunk(path, guid);
             }
+            Msg::ChunkCorrupted(url, path, guid) => {
+                self.chunk_corrupted(&url, path, &guid);
+            }
             Msg::FileHashMismatch(file, file_details) => {
                 self.handle_file_hash_mismatch(&file, file_details);
             }
+            Msg::DeltaPlanned(id, target, to_vault, manifest, plan) => {
+                if let (Some(item), Some(p)) = (self.get_item(&id), &plan) {
+                    item.set_delta_size(p.delta_size);
+                }
+                self.start_file_downloads(&id, &manifest, &target, to_vault, plan);
+            }
+            Msg::CopyEstimated(estimates) => self.copy_estimated(&estimates),
+            Msg::VaultVerified(asset, data_dir, report, notify) => {
+                self.vault_verified(asset.map(|a| *a), &data_dir, &report, notify);
+            }
+            Msg::VaultsVerified(checked, broken) => {
+                if let Some(w) = self_.window.get() {
+                    w.add_notification(
+                        "vault-verify",
+                        &format!("Verified {checked} vault releases, {broken} with problems"),
+                        gtk4::MessageType::Info,
+                    );
+                }
+            }
+            Msg::VaultsCleaned(freed, failed) => {
+                if let Some(w) = self_.window.get() {
+                    let mut message = format!(
+                        "Vault cleanup freed {:.2}",
+                        byte_unit::Byte::from_u64(freed)
+                            .get_appropriate_unit(byte_unit::UnitType::Decimal)
+                    );
+                    if failed > 0 {
+                        message.push_str(&format!(", {failed} entries could not be removed"));
+                    }
+                    w.add_notification("vault-cleanup", &message, gtk4::MessageType::Info);
+                }
+            }
             Msg::DockerCanceled(version, digest) => {
                 self.cancel_docker_digest(&version, digest);
             }
@@ -397,6 +562,11 @@ impl EpicDownloadManager {
                 self.perform_file_download(&url, size, &version);
             }
             Msg::EpicCanceled(_) | Msg::EpicPaused(_) => {}
+            Msg::EpicFailed(version, reason) => {
+                if let Some(item) = self.get_item(&version) {
+                    self.fail(&item, &reason);
+                }
+            }
             Msg::EpicDownloadProgress(ver, size) => {
                 self.epic_download_progress(&ver, size);
             }
@@ -407,6 +577,15 @@ impl EpicDownloadManager {
             Msg::EpicFileExtractionProgress(version, data) => {
                 self.epic_file_extraction_progress(&version, data);
             }
+            Msg::LocalInstallPrepared(id, files) => {
+                self.local_install_prepared(&id, files);
+            }
+            Msg::LocalInstallProgress(id, size) => {
+                self.local_install_progress(&id, size);
+            }
+            Msg::LocalInstallFailed(id, reason) => {
+                self.local_install_failed(&id, &reason);
+            }
         }
     }
 
@@ -416,8 +595,68 @@ impl EpicDownloadManager {
         state.download_items.get(id).cloned()
     }
 
+    fn item_key(&self, item: &EpicDownloadItem) -> Option<String> {
+        let self_ = self.imp();
+        let state = self_.state.borrow();
+        state
+            .download_items
+            .iter()
+            .find(|(_, i)| (*i).eq(item))
+            .map(|(k, _)| k.clone())
+    }
+
+    /// Give up on a download, it stays in the list until it is retried or canceled
+    fn fail(&self, item: &download_item::EpicDownloadItem, reason: &str) {
+        if item.imp().failed.replace(true) {
+            return;
+        }
+        // Park the remaining work like a pause, retrying the download picks it up again
+        match item.item_type() {
+            download_item::ItemType::Asset => {
+                if let Some(release) = item.release() {
+                    self.pause_asset_download(release);
+                }
+            }
+            #[cfg(target_os = "linux")]
+            download_item::ItemType::Docker => {
+                if let Some(version) = item.version() {
+                    self.pause_docker_download(version);
+                }
+            }
+            _ => {}
+        }
+        item.set_property("status", format!("Failed: {reason}"));
+        item.set_property("speed", String::new());
+        self.record_history(item, Outcome::Failed, Some(reason.to_string()));
+        self.notify_download(item, Event::Failed(reason.to_string()));
+    }
+
     fn finish(&self, item: &download_item::EpicDownloadItem) {
+        let outcome = if item.canceled() {
+            Outcome::Canceled
+        } else {
+            Outcome::Finished
+        };
+        self.record_history(item, outcome, None);
+        if outcome == Outcome::Finished {
+            self.notify_download(item, Event::Finished);
+        }
+        self.remove_item(item);
+    }
+
+    /// Fail a download that has nothing to retry, it leaves the list and the queue right away
+    fn discard(&self, item: &download_item::EpicDownloadItem, reason: &str) {
+        self.record_history(item, Outcome::Failed, Some(reason.to_string()));
+        self.notify_download(item, Event::Failed(reason.to_string()));
+        self.remove_item(item);
+    }
+
+    fn remove_item(&self, item: &download_item::EpicDownloadItem) {
         let self_: &imp::EpicDownloadManager = self.imp();
+        if let Some(key) = self.item_key(item) {
+            self.unqueue_download(&key);
+            self_.state.borrow_mut().download_items.remove(&key);
+        }
         if let Some(mut child) = self_.downloads.first_child() {
             loop {
                 let row = child.clone().downcast::<gtk4::ListBoxRow>().unwrap();
@@ -438,6 +677,12 @@ impl EpicDownloadManager {
         self.emit_by_name::<()>("tick", &[]);
     }
 
+    /// Number of times a failing download is retried before it is marked as failed
+    fn retry_limit(&self) -> u32 {
+        let self_ = self.imp();
+        u32::try_from(self_.settings.int("download-retry-limit")).unwrap_or_default()
+    }
+
     fn unreal_vault_dir(&self, asset: &str) -> Option<String> {
         let self_ = self.imp();
         if let Some(i) = self.get_item(asset) {
@@ -455,16 +700,6 @@ impl EpicDownloadManager {
     fn finalize_file_download(&self, file: &str, file_details: asset::DownloadedFile) {
         let self_ = self.imp();
         info!("File finished: {}", file);
-        let vaults = self_.settings.strv("unreal-vault-directories");
-        let temp_dir = std::path::PathBuf::from(vaults.first().map_or_else(
-            || {
-                self_
-                    .settings
-                    .string("temporary-download-directory")
-                    .to_string()
-            },
-            std::string::ToString::to_string,
-        ));
         let mut empty_chunks = Vec::new();
         {
             let mut state = self_.state.borrow_mut();
@@ -473,31 +708,14 @@ impl EpicDownloadManager {
                 if let Some(ch) = state.downloaded_chunks.get_mut(&chunk.guid) {
                     ch.retain(|x| !x.eq(file));
                     if ch.is_empty() {
+                        state.downloaded_chunks.remove(&chunk.guid);
                         empty_chunks.push(chunk.guid);
                     }
                 }
             }
         }
         for guid in empty_chunks {
-            let mut p = temp_dir.clone();
-            p.push(&file_details.release);
-            p.push("temp");
-
-            p.push(format!("{guid}.chunk"));
-            debug!("Removing chunk {}", p.as_path().to_string_lossy());
-            if let Err(e) = std::fs::remove_file(p.clone()) {
-                error!("Unable to remove chunk file: {}", e);
-            };
-            if let Some(parent) = p.parent() {
-                if let Err(e) = std::fs::remove_dir(parent) {
-                    debug!("Unable to remove the temp directory(yet): {}", e);
-                };
-                if let Some(grandparent) = parent.parent() {
-                    if let Err(e) = std::fs::remove_dir(grandparent) {
-                        debug!("Unable to remove the temp directory(yet): {}", e);
-                    };
-                }
-            }
+            self.release_chunk(&guid);
         }
         self_
             .sender
@@ -521,10 +739,10 @@ impl EpicDownloadManager {
                 asset::MAX_FILE_HASH_RETRIES
             );
             if let Some(item) = self.get_item(&file_details.asset) {
-                item.set_property(
-                    "status",
-                    format!(
-                        "Failed: hash validation failed after {} retries",
+                self.fail(
+                    &item,
+                    &format!(
+                        "hash validation failed after {} retries",
                         asset::MAX_FILE_HASH_RETRIES
                     ),
                 );
@@ -558,16 +776,32 @@ impl EpicDownloadManager {
             }
             for chunk in &file_details.chunks {
                 state.chunk_retries.remove(&chunk.guid);
+                state.completed_chunks.remove(&chunk.guid);
             }
         }
 
+        let mut chunks = Vec::new();
         for chunk in &file_details.chunks {
-            let _ = self_.sender.send_blocking(Msg::RedownloadChunk(
-                reqwest::Url::parse("unix:/").unwrap(),
-                PathBuf::from(format!("{}.chunk", chunk.guid)),
-                chunk.guid.clone(),
-            ));
+            // The stored chunk would be reused otherwise
+            let p = self.chunk_path(&chunk.guid);
+            if let Err(e) = std::fs::remove_file(&p) {
+                debug!("Unable to remove chunk {:?}: {}", p, e);
+            }
+            chunks.push((p, chunk.guid.clone()));
         }
+        let sender = self_.sender.clone();
+        glib::timeout_add_local_once(
+            crate::tools::retry::jittered_backoff(retry_count),
+            move || {
+                for (p, guid) in chunks {
+                    let _ = sender.send_blocking(Msg::RedownloadChunk(
+                        reqwest::Url::parse("unix:/").unwrap(),
+                        p,
+                        guid,
+                    ));
+                }
+            },
+        );
     }
 
     pub fn progress(&self) -> f32 {
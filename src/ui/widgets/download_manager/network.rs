@@ -0,0 +1,114 @@
+use crate::ui::widgets::download_manager::download_item::{EpicDownloadItem, ItemType};
+use crate::ui::widgets::download_manager::notifications::Notifications;
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
+use glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{gio, glib, prelude::*};
+use log::debug;
+
+pub trait Network {
+    /// Follow the connection state to pause and resume downloads
+    fn setup_network_monitor(&self) {
+        unimplemented!()
+    }
+
+    /// Why the download can not use the network right now
+    fn network_block(&self, _item: &EpicDownloadItem) -> Option<&'static str> {
+        unimplemented!()
+    }
+
+    /// Pause a new download right away if the network does not allow it
+    fn check_network(&self, _item: &EpicDownloadItem) {
+        unimplemented!()
+    }
+
+    /// Pause downloads the connection does not allow and resume the ones it allows again
+    fn apply_network_state(&self) {
+        unimplemented!()
+    }
+}
+
+impl Network for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn setup_network_monitor(&self) {
+        let self_ = self.imp();
+        let monitor = gio::NetworkMonitor::default();
+        monitor.connect_network_changed(clone!(
+            #[weak(rename_to=dm)]
+            self,
+            move |_, available| {
+                debug!("Network changed, available: {}", available);
+                dm.apply_network_state();
+            }
+        ));
+        monitor.connect_network_metered_notify(clone!(
+            #[weak(rename_to=dm)]
+            self,
+            move |monitor| {
+                debug!("Network metered: {}", monitor.is_network_metered());
+                dm.apply_network_state();
+            }
+        ));
+        self_.settings.connect_changed(
+            Some("pause-on-metered"),
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                move |_, _| {
+                    dm.apply_network_state();
+                }
+            ),
+        );
+    }
+
+    fn network_block(&self, item: &EpicDownloadItem) -> Option<&'static str> {
+        let self_ = self.imp();
+        if item.item_type() == ItemType::Local {
+            return None;
+        }
+        let monitor = gio::NetworkMonitor::default();
+        if !monitor.is_network_available() {
+            return Some("no network connection");
+        }
+        if monitor.is_network_metered()
+            && self_.settings.boolean("pause-on-metered")
+            && !item.download_anyway()
+        {
+            return Some("metered connection");
+        }
+        None
+    }
+
+    fn check_network(&self, item: &EpicDownloadItem) {
+        if let Some(reason) = self.network_block(item) {
+            debug!("Holding new download, {}", reason);
+            item.hold_for_network(reason);
+        }
+    }
+
+    fn apply_network_state(&self) {
+        let self_ = self.imp();
+        let mut held = None;
+        let mut waiting = false;
+        for item in self.ordered_items() {
+            match self.network_block(&item) {
+                Some(reason) => {
+                    if item.hold_for_network(reason) {
+                        held = Some(reason);
+                    }
+                    waiting |= *item.imp().network_hold.borrow();
+                }
+                None => item.release_network_hold(),
+            }
+        }
+        let Some(w) = self_.window.get() else {
+            return;
+        };
+        if let Some(reason) = held {
+            let message = format!("Downloads paused, {reason}");
+            w.add_notification("network", &message, gtk4::MessageType::Warning);
+            self.notify_attention("network", "Downloads paused", &message);
+        } else if !waiting {
+            w.clear_notification("network");
+        }
+    }
+}
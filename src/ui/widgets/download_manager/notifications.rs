@@ -0,0 +1,265 @@
+use crate::ui::widgets::download_manager::asset::Asset;
+use crate::ui::widgets::download_manager::docker::Docker;
+use crate::ui::widgets::download_manager::download_item::{EpicDownloadItem, ItemType};
+use crate::ui::widgets::download_manager::epic_file::EpicFile;
+use glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{gio, glib, prelude::*};
+use log::debug;
+
+/// How often running downloads are checked for progress
+const STALL_CHECK_SECONDS: u32 = 60;
+/// Downloads without any progress for this long are reported as stuck
+const STALL_MINUTES: i64 = 10;
+/// Object path the dock progress is published on
+const LAUNCHER_ENTRY_PATH: &str = "/io/github/achetagames/epic_asset_manager/LauncherEntry";
+
+/// Download events the user is told about through a desktop notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Finished,
+    Failed(String),
+}
+
+pub trait Notifications {
+    fn setup_notifications(&self) {
+        unimplemented!()
+    }
+
+    /// Send a desktop notification about a download while the main window is in the background
+    fn notify_download(&self, _item: &EpicDownloadItem, _event: Event) {
+        unimplemented!()
+    }
+
+    /// Send a desktop notification about something that needs the user, e.g. a full disk
+    fn notify_attention(&self, _id: &str, _title: &str, _body: &str) {
+        unimplemented!()
+    }
+
+    /// Continue the parts of a failed download that were given up on
+    fn retry_download(&self, _id: &str) {
+        unimplemented!()
+    }
+
+    /// Notify once when running downloads made no progress for a while
+    fn check_stalled(&self) {
+        unimplemented!()
+    }
+
+    /// Publish the overall progress through the `com.canonical.Unity.LauncherEntry` API used by docks
+    fn update_launcher_entry(&self) {
+        unimplemented!()
+    }
+}
+
+impl Notifications for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn setup_notifications(&self) {
+        self.connect_local(
+            "tick",
+            false,
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                #[upgrade_or]
+                None,
+                move |_| {
+                    dm.update_launcher_entry();
+                    None
+                }
+            ),
+        );
+        glib::timeout_add_seconds_local(
+            STALL_CHECK_SECONDS,
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    dm.check_stalled();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    fn notify_download(&self, item: &EpicDownloadItem, event: Event) {
+        let Some(key) = self.item_key(item) else {
+            return;
+        };
+        let label = item.label().unwrap_or_else(|| key.clone());
+        let notification = match &event {
+            Event::Finished => {
+                let notification = gio::Notification::new("Download finished");
+                notification.set_body(Some(&label));
+                if let Some(path) = item.path().or_else(|| item.target()) {
+                    notification.add_button_with_target_value(
+                        "Open Folder",
+                        "app.open-download-folder",
+                        Some(&path.to_variant()),
+                    );
+                }
+                notification
+            }
+            Event::Failed(reason) => {
+                let notification = gio::Notification::new("Download failed");
+                notification.set_body(Some(&format!("{label}\n{reason}")));
+                notification.set_priority(gio::NotificationPriority::High);
+                if item.item_type() != ItemType::Local {
+                    notification.add_button_with_target_value(
+                        "Retry",
+                        "app.retry-download",
+                        Some(&key.to_variant()),
+                    );
+                }
+                notification
+            }
+        };
+        send(self, &format!("download-{key}"), &notification);
+    }
+
+    fn notify_attention(&self, id: &str, title: &str, body: &str) {
+        let notification = gio::Notification::new(title);
+        notification.set_body(Some(body));
+        notification.set_priority(gio::NotificationPriority::High);
+        notification.add_button("Show Downloads", "app.show-downloads");
+        send(self, id, &notification);
+    }
+
+    fn retry_download(&self, id: &str) {
+        let Some(item) = self.get_item(id) else {
+            return;
+        };
+        debug!("Retrying download of {}", id);
+        item.imp().failed.replace(false);
+        item.set_property("status", String::new());
+        match item.item_type() {
+            ItemType::Unknown | ItemType::Local => {}
+            ItemType::Asset => {
+                if let Some(release) = item.release() {
+                    self.resume_asset_download(release);
+                }
+            }
+            ItemType::Docker => {
+                if let Some(version) = item.version() {
+                    self.resume_docker_download(version);
+                }
+            }
+            ItemType::Epic => {
+                if let Some(version) = item.version() {
+                    self.resume_epic_download(version);
+                }
+            }
+        }
+        if let Some(app) = application(self) {
+            app.withdraw_notification(&format!("download-{id}"));
+        }
+    }
+
+    fn check_stalled(&self) {
+        let self_ = self.imp();
+        let now = chrono::Utc::now();
+        let running: Vec<String> = self_
+            .state
+            .borrow()
+            .download_items
+            .values()
+            .filter(|i| !i.paused() && !i.canceled() && !i.failed())
+            .filter_map(EpicDownloadItem::label)
+            .collect();
+        let progress = self.progress();
+        {
+            let mut state = self_.state.borrow_mut();
+            if running.is_empty() || (progress - state.progress_seen).abs() > f32::EPSILON {
+                state.progress_seen = progress;
+                state.progress_changed = now;
+                state.stall_reported = false;
+                return;
+            }
+            if state.stall_reported
+                || now - state.progress_changed < chrono::Duration::minutes(STALL_MINUTES)
+            {
+                return;
+            }
+            state.stall_reported = true;
+        }
+        let mut running = running;
+        running.sort();
+        running.dedup();
+        debug!("Downloads made no progress for {} minutes", STALL_MINUTES);
+        self.notify_attention(
+            "downloads-stalled",
+            "Downloads are stuck",
+            &format!(
+                "No progress for {STALL_MINUTES} minutes: {}",
+                running.join(", ")
+            ),
+        );
+    }
+
+    fn update_launcher_entry(&self) {
+        let self_ = self.imp();
+        let Some(connection) = application(self).and_then(|app| app.dbus_connection()) else {
+            return;
+        };
+        let mut count = 0_i64;
+        let mut child = self_.downloads.first_child();
+        while let Some(row) = child {
+            count += 1;
+            child = row.next_sibling();
+        }
+        let progress = f64::from(self.progress());
+        let published = ((progress * 1000.0).round() as i64, count);
+        if self_.state.borrow().launcher_entry == Some(published) {
+            return;
+        }
+        self_.state.borrow_mut().launcher_entry = Some(published);
+
+        let properties = glib::VariantDict::new(None);
+        properties.insert("progress", progress);
+        properties.insert("progress-visible", count > 0);
+        properties.insert("count", count);
+        properties.insert("count-visible", count > 0);
+        let parameters = glib::Variant::tuple_from_iter([
+            format!("application://{}.desktop", crate::config::APP_ID).to_variant(),
+            properties.end(),
+        ]);
+        if let Err(e) = connection.emit_signal(
+            None,
+            LAUNCHER_ENTRY_PATH,
+            "com.canonical.Unity.LauncherEntry",
+            "Update",
+            Some(&parameters),
+        ) {
+            debug!("Unable to publish launcher progress: {}", e);
+        }
+    }
+}
+
+fn application(
+    dm: &crate::ui::widgets::download_manager::EpicDownloadManager,
+) -> Option<gtk4::Application> {
+    dm.imp().window.get().and_then(GtkWindowExt::application)
+}
+
+/// Only send notifications while the user is not looking at the window, the banners cover the rest
+fn send(
+    dm: &crate::ui::widgets::download_manager::EpicDownloadManager,
+    id: &str,
+    notification: &gio::Notification,
+) {
+    let self_ = dm.imp();
+    if !self_.settings.boolean("download-notifications") {
+        return;
+    }
+    let Some(window) = self_.window.get() else {
+        return;
+    };
+    if window.is_active() {
+        return;
+    }
+    notification.set_default_action("app.show-downloads");
+    if let Some(app) = window.application() {
+        app.send_notification(Some(id), notification);
+    }
+}
@@ -0,0 +1,135 @@
+use glib::clone;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use log::debug;
+
+/// Upper bound for the number of chunk connections chosen by the auto mode
+const MAX_AUTO_DOWNLOAD_THREADS: usize = 16;
+/// How often the auto mode re-evaluates the throughput
+const AUTO_INTERVAL_SECONDS: u32 = 5;
+
+/// State of the throughput based chunk concurrency tuning
+#[derive(Debug, Default)]
+pub struct AutoConcurrency {
+    pub threads: usize,
+    pub last_throughput: u128,
+    pub step: isize,
+}
+
+pub trait Pools {
+    /// Apply pool sizes from the settings and follow their changes
+    fn setup_pools(&self) {
+        unimplemented!()
+    }
+
+    fn apply_pool_sizes(&self) {
+        unimplemented!()
+    }
+
+    /// Grow or shrink the download pool depending on how the throughput changed since the last check
+    fn adjust_download_concurrency(&self) {
+        unimplemented!()
+    }
+}
+
+impl Pools for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn setup_pools(&self) {
+        let self_ = self.imp();
+        self.apply_pool_sizes();
+        for key in [
+            "download-threads",
+            "download-threads-auto",
+            "image-threads",
+            "file-threads",
+        ] {
+            self_.settings.connect_changed(
+                Some(key),
+                clone!(
+                    #[weak(rename_to=dm)]
+                    self,
+                    move |_, _| {
+                        dm.apply_pool_sizes();
+                    }
+                ),
+            );
+        }
+        glib::timeout_add_seconds_local(
+            AUTO_INTERVAL_SECONDS,
+            clone!(
+                #[weak(rename_to=dm)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    dm.adjust_download_concurrency();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    fn apply_pool_sizes(&self) {
+        let self_ = self.imp();
+        let size = |key: &str| usize::try_from(self_.settings.int(key)).unwrap_or(1).max(1);
+        let download = size("download-threads");
+        let image = size("image-threads");
+        let file = size("file-threads");
+        debug!(
+            "Pool sizes: download {}, image {}, file {}",
+            download, image, file
+        );
+        // Starting point for the auto mode, it adjusts from here
+        self_.auto_concurrency.replace(AutoConcurrency {
+            threads: download,
+            last_throughput: 0,
+            step: 1,
+        });
+        self_.download_pool.clone().set_num_threads(download);
+        self_.image_pool.clone().set_num_threads(image);
+        self_.thumbnail_pool.clone().set_num_threads(image);
+        self_.file_pool.clone().set_num_threads(file);
+    }
+
+    fn adjust_download_concurrency(&self) {
+        let self_ = self.imp();
+        if !self_.settings.boolean("download-threads-auto") {
+            return;
+        }
+        let throughput: u128 = self_
+            .state
+            .borrow()
+            .download_items
+            .values()
+            .map(super::download_item::EpicDownloadItem::speed)
+            .sum();
+        let mut auto = self_.auto_concurrency.borrow_mut();
+        if throughput == 0 || self_.download_pool.queued_count() == 0 {
+            // Nothing to measure
+            auto.last_throughput = 0;
+            return;
+        }
+        if auto.last_throughput > 0 {
+            let previous = auto.last_throughput;
+            if throughput < previous - previous / 10 {
+                // Last change made things worse, go the other way
+                auto.step = -auto.step;
+            } else if throughput <= previous + previous / 10 {
+                // No significant difference, stay where we are
+                auto.last_throughput = throughput;
+                return;
+            }
+        }
+        let threads = auto
+            .threads
+            .saturating_add_signed(auto.step)
+            .clamp(1, MAX_AUTO_DOWNLOAD_THREADS);
+        debug!(
+            "Throughput {} B/s, changing download threads from {} to {}",
+            throughput, auto.threads, threads
+        );
+        auto.threads = threads;
+        auto.last_throughput = throughput;
+        self_.download_pool.clone().set_num_threads(threads);
+    }
+}
@@ -0,0 +1,349 @@
+use crate::schema::download_queue;
+use crate::tools::scheduler::Priority;
+use crate::ui::widgets::download_manager::asset::Asset;
+#[cfg(target_os = "linux")]
+use crate::ui::widgets::download_manager::docker::Docker;
+use crate::ui::widgets::download_manager::download_item::EpicDownloadItem;
+use crate::ui::widgets::download_manager::epic_file::EpicFile;
+use crate::ui::widgets::download_manager::scheduling::Scheduling;
+use crate::ui::widgets::download_manager::PostDownloadAction;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use gtk4::subclass::prelude::*;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Everything required to start a download again after the application restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueuedDownload {
+    Asset {
+        release_id: String,
+        asset: Box<egs_api::api::types::asset_info::AssetInfo>,
+    },
+    Fab {
+        asset: Box<egs_api::api::types::fab_library::FabAsset>,
+        artifact_id: String,
+        platform: String,
+    },
+    Epic {
+        version: String,
+    },
+    Docker {
+        version: String,
+        /// Layers that are already extracted and do not need to be downloaded again
+        #[serde(default)]
+        extracted: Vec<String>,
+        /// Downloaded layers that already matched their digest
+        #[serde(default)]
+        verified: Vec<String>,
+    },
+}
+
+type QueueColumns = (String, String, Option<String>, String, bool, i64, i64, i32);
+
+struct QueueRow {
+    id: String,
+    download: QueuedDownload,
+    target: Option<String>,
+    actions: Vec<PostDownloadAction>,
+    paused: bool,
+    downloaded_size: u128,
+    total_size: u128,
+    priority: Priority,
+}
+
+pub trait Queue {
+    /// Store the download in the database so it survives an application restart
+    fn queue_download(
+        &self,
+        _id: &str,
+        _download: &QueuedDownload,
+        _target: &Option<String>,
+        _actions: &[PostDownloadAction],
+    ) {
+        unimplemented!()
+    }
+
+    /// Remove the download from the database, used when it finishes or gets canceled
+    fn unqueue_download(&self, _id: &str) {
+        unimplemented!()
+    }
+
+    fn unqueue_item(&self, _item: &EpicDownloadItem) {
+        unimplemented!()
+    }
+
+    fn set_queued_paused(&self, _item: &EpicDownloadItem, _paused: bool) {
+        unimplemented!()
+    }
+
+    /// Write the current progress of all items so it can be displayed after a restart
+    fn save_queue_progress(&self) {
+        unimplemented!()
+    }
+
+    /// Remember priorities and list positions for the next start
+    fn save_queue_order(&self, _order: &HashMap<String, (Priority, u32)>) {
+        unimplemented!()
+    }
+
+    /// Add all downloads saved in the database back to the download manager
+    fn restore_downloads(&self) {
+        unimplemented!()
+    }
+
+    /// Docker downloads can only be restored once we have a registry client
+    fn restore_docker_downloads(&self) {
+        unimplemented!()
+    }
+}
+
+impl Queue for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn queue_download(
+        &self,
+        id: &str,
+        download: &QueuedDownload,
+        target: &Option<String>,
+        actions: &[PostDownloadAction],
+    ) {
+        let payload = match serde_json::to_string(download) {
+            Ok(p) => p,
+            Err(e) => {
+                error!("Unable to serialize queued download {}: {}", id, e);
+                return;
+            }
+        };
+        let actions = serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string());
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            // Keep the paused state and progress of a download that is being restored
+            if let Err(e) = diesel::insert_into(download_queue::table)
+                .values((
+                    download_queue::id.eq(id),
+                    download_queue::payload.eq(&payload),
+                    download_queue::target.eq(target),
+                    download_queue::actions.eq(&actions),
+                ))
+                .on_conflict(download_queue::id)
+                .do_update()
+                .set((
+                    download_queue::payload.eq(&payload),
+                    download_queue::target.eq(target),
+                    download_queue::actions.eq(&actions),
+                ))
+                .execute(&mut conn)
+            {
+                error!("Unable to save download {} to the DB: {}", id, e);
+            }
+        }
+    }
+
+    fn unqueue_download(&self, id: &str) {
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            if let Err(e) = diesel::delete(download_queue::table.filter(download_queue::id.eq(id)))
+                .execute(&mut conn)
+            {
+                error!("Unable to remove download {} from the DB: {}", id, e);
+            }
+        }
+    }
+
+    fn unqueue_item(&self, item: &EpicDownloadItem) {
+        if let Some(id) = self.item_key(item) {
+            self.unqueue_download(&id);
+        }
+    }
+
+    fn set_queued_paused(&self, item: &EpicDownloadItem, paused: bool) {
+        let Some(id) = self.item_key(item) else {
+            return;
+        };
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            if let Err(e) = diesel::update(download_queue::table.filter(download_queue::id.eq(&id)))
+                .set((
+                    download_queue::paused.eq(paused),
+                    download_queue::downloaded_size
+                        .eq(i64::try_from(item.downloaded_size()).unwrap_or_default()),
+                    download_queue::total_size
+                        .eq(i64::try_from(item.total_size()).unwrap_or_default()),
+                ))
+                .execute(&mut conn)
+            {
+                error!("Unable to update paused state of {} in the DB: {}", id, e);
+            }
+        }
+    }
+
+    fn save_queue_progress(&self) {
+        let self_ = self.imp();
+        let items: Vec<(String, EpicDownloadItem)> = self_
+            .state
+            .borrow()
+            .download_items
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            for (id, item) in items {
+                if let Err(e) =
+                    diesel::update(download_queue::table.filter(download_queue::id.eq(&id)))
+                        .set((
+                            download_queue::downloaded_size
+                                .eq(i64::try_from(item.downloaded_size()).unwrap_or_default()),
+                            download_queue::total_size
+                                .eq(i64::try_from(item.total_size()).unwrap_or_default()),
+                        ))
+                        .execute(&mut conn)
+                {
+                    error!("Unable to save progress of {} to the DB: {}", id, e);
+                }
+            }
+        }
+    }
+
+    fn save_queue_order(&self, order: &HashMap<String, (Priority, u32)>) {
+        let db = crate::models::database::connection();
+        if let Ok(mut conn) = db.get() {
+            for (id, (priority, position)) in order {
+                if let Err(e) =
+                    diesel::update(download_queue::table.filter(download_queue::id.eq(id)))
+                        .set((
+                            download_queue::priority.eq(priority.value()),
+                            download_queue::position
+                                .eq(i32::try_from(*position).unwrap_or(i32::MAX)),
+                        ))
+                        .execute(&mut conn)
+                {
+                    error!("Unable to save queue position of {} to the DB: {}", id, e);
+                }
+            }
+        }
+    }
+
+    fn restore_downloads(&self) {
+        for row in load_queue() {
+            debug!("Restoring download {}", row.id);
+            match row.download {
+                QueuedDownload::Asset { release_id, asset } => {
+                    self.add_asset_download(release_id, *asset, &row.target, Some(row.actions));
+                }
+                QueuedDownload::Fab {
+                    asset,
+                    artifact_id,
+                    platform,
+                } => {
+                    self.add_fab_asset_download(*asset, artifact_id, platform, &row.target);
+                }
+                QueuedDownload::Epic { version } => {
+                    self.download_engine_from_epic(&version);
+                }
+                QueuedDownload::Docker { .. } => {
+                    continue;
+                }
+            }
+            if let Some(item) = self.get_item(&row.id) {
+                item.restore_state(row.paused, row.downloaded_size, row.total_size);
+                item.set_priority(row.priority);
+            }
+        }
+        self.update_schedule();
+        #[cfg(target_os = "linux")]
+        self.restore_docker_downloads();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn restore_docker_downloads(&self) {
+        use crate::ui::widgets::download_manager::DownloadStatus;
+        let self_ = self.imp();
+        let Some(window) = self_.window.get() else {
+            return;
+        };
+        if window.imp().model.borrow().dclient.borrow().is_none() {
+            return;
+        }
+        for row in load_queue() {
+            if let QueuedDownload::Docker {
+                version,
+                extracted,
+                verified,
+            } = row.download
+            {
+                if self.get_item(&version).is_some() {
+                    continue;
+                }
+                debug!("Restoring docker download {}", version);
+                let layers: Vec<(String, DownloadStatus)> = extracted
+                    .into_iter()
+                    .map(|d| (d, DownloadStatus::Extracted))
+                    .chain(verified.into_iter().map(|d| (d, DownloadStatus::Verified)))
+                    .collect();
+                if !layers.is_empty() {
+                    self_
+                        .state
+                        .borrow_mut()
+                        .restored_docker_layers
+                        .insert(version.clone(), layers);
+                }
+                self.download_engine_from_docker(&version);
+                if let Some(item) = self.get_item(&row.id) {
+                    item.restore_state(row.paused, row.downloaded_size, row.total_size);
+                    item.set_priority(row.priority);
+                }
+            }
+        }
+        self.update_schedule();
+    }
+}
+
+fn load_queue() -> Vec<QueueRow> {
+    let db = crate::models::database::connection();
+    let Ok(mut conn) = db.get() else {
+        return Vec::new();
+    };
+    let rows: Result<Vec<QueueColumns>, _> = download_queue::table
+        .select((
+            download_queue::id,
+            download_queue::payload,
+            download_queue::target,
+            download_queue::actions,
+            download_queue::paused,
+            download_queue::downloaded_size,
+            download_queue::total_size,
+            download_queue::priority,
+        ))
+        .order(download_queue::position.asc())
+        .load(&mut conn);
+    match rows {
+        Ok(rows) => rows
+            .into_iter()
+            .filter_map(
+                |(id, payload, target, actions, paused, downloaded_size, total_size, priority)| {
+                    let download = match serde_json::from_str::<QueuedDownload>(&payload) {
+                        Ok(d) => d,
+                        Err(e) => {
+                            warn!("Dropping unreadable queued download {}: {}", id, e);
+                            return None;
+                        }
+                    };
+                    Some(QueueRow {
+                        id,
+                        download,
+                        target,
+                        actions: serde_json::from_str(&actions).unwrap_or_default(),
+                        paused,
+                        downloaded_size: u128::try_from(downloaded_size).unwrap_or_default(),
+                        total_size: u128::try_from(total_size).unwrap_or_default(),
+                        priority: Priority::from_value(priority),
+                    })
+                },
+            )
+            .collect(),
+        Err(e) => {
+            error!("Unable to load download queue from the DB: {}", e);
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::tools::scheduler::Priority;
+use crate::ui::widgets::download_manager::download_item::EpicDownloadItem;
+use crate::ui::widgets::download_manager::queue::Queue;
+use glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{gdk, glib, prelude::*};
+use std::collections::HashMap;
+
+pub trait Scheduling {
+    /// Accept download items dropped on the list to reorder them
+    fn setup_reordering(&self) {
+        unimplemented!()
+    }
+
+    /// Items in the order they are displayed in
+    fn ordered_items(&self) -> Vec<EpicDownloadItem> {
+        unimplemented!()
+    }
+
+    fn move_item(&self, _item: &EpicDownloadItem, _index: i32) {
+        unimplemented!()
+    }
+
+    /// Put the item in front of everything else, its chunks get picked as soon as a download slot frees up
+    fn move_to_top(&self, _item: &EpicDownloadItem) {
+        unimplemented!()
+    }
+
+    fn set_item_priority(&self, _item: &EpicDownloadItem, _priority: Priority) {
+        unimplemented!()
+    }
+
+    /// Keep the list sorted by priority and hand the resulting order to the scheduler
+    fn update_schedule(&self) {
+        unimplemented!()
+    }
+
+    /// Downloads waiting for a chunk
+    fn chunk_owners(&self, _guid: &str) -> Vec<String> {
+        unimplemented!()
+    }
+}
+
+impl Scheduling for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn setup_reordering(&self) {
+        let self_ = self.imp();
+        let target = gtk4::DropTarget::new(EpicDownloadItem::static_type(), gdk::DragAction::MOVE);
+        target.connect_drop(clone!(
+            #[weak(rename_to=dm)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, value, _, y| {
+                let Ok(item) = value.get::<EpicDownloadItem>() else {
+                    return false;
+                };
+                let self_ = dm.imp();
+                let Some(row) = self_.downloads.row_at_y(y as i32) else {
+                    dm.move_item(&item, -1);
+                    return true;
+                };
+                // Items dropped between others take over their priority
+                if let Some(neighbour) = row
+                    .child()
+                    .and_then(|c| c.downcast::<EpicDownloadItem>().ok())
+                {
+                    item.set_priority(neighbour.priority());
+                }
+                dm.move_item(&item, row.index());
+                true
+            }
+        ));
+        self_.downloads.add_controller(target);
+    }
+
+    fn ordered_items(&self) -> Vec<EpicDownloadItem> {
+        let self_ = self.imp();
+        let mut items = Vec::new();
+        let mut child = self_.downloads.first_child();
+        while let Some(row) = child {
+            if let Some(item) = row
+                .downcast_ref::<gtk4::ListBoxRow>()
+                .and_then(gtk4::ListBoxRow::child)
+                .and_then(|c| c.downcast::<EpicDownloadItem>().ok())
+            {
+                items.push(item);
+            }
+            child = row.next_sibling();
+        }
+        items
+    }
+
+    fn move_item(&self, item: &EpicDownloadItem, index: i32) {
+        let self_ = self.imp();
+        let Some(row) = item
+            .parent()
+            .and_then(|p| p.downcast::<gtk4::ListBoxRow>().ok())
+        else {
+            return;
+        };
+        if row.index() == index {
+            self.update_schedule();
+            return;
+        }
+        self_.downloads.remove(&row);
+        row.set_child(None::<&gtk4::Widget>);
+        self_.downloads.insert(item, index);
+        self.update_schedule();
+    }
+
+    fn move_to_top(&self, item: &EpicDownloadItem) {
+        item.set_priority(Priority::High);
+        self.move_item(item, 0);
+    }
+
+    fn set_item_priority(&self, item: &EpicDownloadItem, priority: Priority) {
+        item.set_priority(priority);
+        self.update_schedule();
+    }
+
+    fn update_schedule(&self) {
+        let self_ = self.imp();
+        let items = self.ordered_items();
+        let mut sorted = items.clone();
+        sorted.sort_by_key(EpicDownloadItem::priority);
+        if sorted != items {
+            for item in &sorted {
+                if let Some(row) = item.parent() {
+                    self_.downloads.remove(&row);
+                    if let Ok(row) = row.downcast::<gtk4::ListBoxRow>() {
+                        row.set_child(None::<&gtk4::Widget>);
+                    }
+                }
+                self_.downloads.append(item);
+            }
+        }
+        let order: HashMap<String, (Priority, u32)> = sorted
+            .iter()
+            .enumerate()
+            .filter_map(|(position, item)| {
+                self.item_key(item).map(|key| {
+                    (
+                        key,
+                        (item.priority(), u32::try_from(position).unwrap_or(u32::MAX)),
+                    )
+                })
+            })
+            .collect();
+        self.save_queue_order(&order);
+        self_.scheduler.set_order(order);
+    }
+
+    fn chunk_owners(&self, guid: &str) -> Vec<String> {
+        let self_ = self.imp();
+        let state = self_.state.borrow();
+        let mut owners: Vec<String> = state
+            .downloaded_chunks
+            .get(guid)
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|f| state.downloaded_files.get(f))
+                    .map(|f| f.asset.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        owners.sort();
+        owners.dedup();
+        owners
+    }
+}
@@ -0,0 +1,284 @@
+use crate::tools::asset_updates::{self, Release};
+use crate::ui::widgets::download_manager::asset::Asset;
+use crate::ui::widgets::download_manager::PostDownloadAction;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use log::debug;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Where an outdated release of an asset is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Release directory in a vault
+    Vault(PathBuf),
+    /// Project the release was copied into
+    Project(PathBuf),
+}
+
+/// Outdated release together with the release replacing it
+#[derive(Debug, Clone)]
+pub struct AssetUpdate {
+    pub asset: egs_api::api::types::asset_info::AssetInfo,
+    pub installed: String,
+    pub latest: String,
+    pub location: Location,
+}
+
+impl AssetUpdate {
+    pub fn title(&self) -> String {
+        self.asset
+            .title
+            .clone()
+            .unwrap_or_else(|| self.asset.id.clone())
+    }
+
+    /// Version title of the latest release, its app name if there is none
+    pub fn latest_title(&self) -> String {
+        self.asset
+            .release_info(&self.latest)
+            .and_then(|r| r.version_title)
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| self.latest.clone())
+    }
+}
+
+pub trait Updates {
+    /// Look for newer releases of the assets in the vaults and of the copies added to projects
+    fn scan_updates(&self, _sender: async_channel::Sender<Vec<AssetUpdate>>) {
+        unimplemented!()
+    }
+
+    /// Download the latest releases, copies in projects get replaced once downloaded
+    fn update_assets(&self, _updates: Vec<AssetUpdate>) {
+        unimplemented!()
+    }
+
+    fn is_updating(&self, _release: &str) -> bool {
+        unimplemented!()
+    }
+}
+
+impl Updates for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn scan_updates(&self, sender: async_channel::Sender<Vec<AssetUpdate>>) {
+        let self_ = self.imp();
+        let vaults: Vec<PathBuf> = self_
+            .settings
+            .strv("unreal-vault-directories")
+            .iter()
+            .map(|v| PathBuf::from(v.as_str()))
+            .collect();
+        let cache_dir = PathBuf::from(self_.settings.string("cache-directory").to_string());
+        let projects: Vec<PathBuf> = self_
+            .window
+            .get()
+            .map(|w| {
+                let w_: &crate::window::imp::EpicAssetManagerWindow = w.imp();
+                let l = w_.logged_in_stack.imp();
+                l.projects
+                    .imp()
+                    .projects
+                    .borrow()
+                    .keys()
+                    .map(PathBuf::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self_.file_pool.execute(move || {
+            let _ = sender.send_blocking(find_updates(&vaults, &cache_dir, &projects));
+        });
+    }
+
+    fn update_assets(&self, updates: Vec<AssetUpdate>) {
+        // One download per release, it goes to the vault and into every project using it
+        let mut releases: Vec<(
+            String,
+            egs_api::api::types::asset_info::AssetInfo,
+            Option<String>,
+            Vec<PostDownloadAction>,
+        )> = Vec::new();
+        for update in updates {
+            if self.is_updating(&update.latest) {
+                debug!("{} is already downloading", update.latest);
+                continue;
+            }
+            let index = match releases.iter().position(|r| r.0 == update.latest) {
+                Some(index) => index,
+                None => {
+                    releases.push((update.latest.clone(), update.asset, None, Vec::new()));
+                    releases.len() - 1
+                }
+            };
+            let release = &mut releases[index];
+            match update.location {
+                Location::Vault(path) => {
+                    if release.2.is_none() {
+                        release.2 = path
+                            .parent()
+                            .map(|vault| vault.to_string_lossy().to_string());
+                    }
+                }
+                Location::Project(path) => release.3.push(PostDownloadAction::Copy(
+                    path.to_string_lossy().to_string(),
+                    true,
+                )),
+            }
+        }
+        for (latest, asset, target, actions) in releases {
+            debug!("Updating {} to {}", asset.id, latest);
+            self.add_asset_download(
+                latest,
+                asset,
+                &target,
+                (!actions.is_empty()).then_some(actions),
+            );
+        }
+    }
+
+    fn is_updating(&self, release: &str) -> bool {
+        self.get_item(release).is_some()
+    }
+}
+
+/// `.uproject` file of a project directory
+fn uproject_file(path: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|p| p.extension().is_some_and(|e| e == "uproject"))
+}
+
+/// Files of a release manifest with their sizes
+type ReleaseFiles = Vec<(String, u128)>;
+
+/// Number of files of a release a project has with the same size, `0` when the top folder of
+/// the release is missing from the project
+fn matching_files(project: &Path, files: &ReleaseFiles) -> usize {
+    let Some((first, _)) = files.first() else {
+        return 0;
+    };
+    let top: PathBuf = Path::new(first).iter().take(2).collect();
+    if !project.join(top).exists() {
+        return 0;
+    }
+    files
+        .iter()
+        .filter(|(name, size)| {
+            std::fs::metadata(project.join(name)).is_ok_and(|m| u128::from(m.len()) == *size)
+        })
+        .count()
+}
+
+/// Release of an asset copied into a project, the one of `releases` (newest first) with the
+/// most matching files
+fn installed_release<'a>(
+    project: &Path,
+    releases: &'a [(String, ReleaseFiles)],
+) -> Option<&'a str> {
+    let mut best: Option<(&str, usize)> = None;
+    for (app, files) in releases {
+        let matching = matching_files(project, files);
+        if matching > 0 && best.is_none_or(|(_, most)| matching > most) {
+            best = Some((app, matching));
+        }
+    }
+    best.map(|(app, _)| app)
+}
+
+/// Compare vault releases and project copies against the latest release of each asset
+///
+/// The release copied into a project is recognized by its files, so only releases with a
+/// saved manifest in a vault are found there.
+pub fn find_updates(
+    vaults: &[PathBuf],
+    cache_dir: &Path,
+    projects: &[PathBuf],
+) -> Vec<AssetUpdate> {
+    let assets = super::vault::cached_assets(cache_dir);
+    let mut updates = Vec::new();
+    // Files of the releases in the vaults by asset
+    let mut manifests: HashMap<String, Vec<(String, ReleaseFiles)>> = HashMap::new();
+
+    let present: HashSet<String> = vaults
+        .iter()
+        .filter_map(|vault| std::fs::read_dir(vault).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| entry.path().join("data").is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    for vault in vaults {
+        let Ok(entries) = std::fs::read_dir(vault) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let app = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().join("data").is_dir() {
+                continue;
+            }
+            let Some(asset) = assets.get(&app) else {
+                continue;
+            };
+            let known = manifests.entry(asset.id.clone()).or_default();
+            // The same release can be in more than one vault
+            if !known.iter().any(|(a, _)| *a == app) {
+                if let Some(manifest) = super::delta::load_release_manifest(&entry.path()) {
+                    let files = manifest
+                        .file_manifest_list
+                        .iter()
+                        .map(|f| (f.filename.replace('\\', "/"), f.size()))
+                        .collect();
+                    known.push((app.clone(), files));
+                }
+            }
+            let releases = Release::of(asset);
+            let Some(newer) = asset_updates::newer_release(&releases, &app, None) else {
+                continue;
+            };
+            // Already downloaded, the old release is only left for the vault cleanup
+            if present.contains(&newer.app) {
+                continue;
+            }
+            updates.push(AssetUpdate {
+                asset: asset.clone(),
+                installed: app,
+                latest: newer.app.clone(),
+                location: Location::Vault(entry.path()),
+            });
+        }
+    }
+
+    // Newest first, releases matching the same files count as the newer one
+    for known in manifests.values_mut() {
+        if let Some(asset) = known.first().and_then(|(app, _)| assets.get(app)) {
+            let releases = Release::of(asset);
+            known.sort_by_key(|(app, _)| releases.iter().position(|r| &r.app == app));
+        }
+    }
+    for project in projects {
+        let Some(file) = uproject_file(project) else {
+            continue;
+        };
+        let uproject =
+            crate::models::project_data::ProjectData::read_uproject(&file.to_string_lossy());
+        let engine = asset_updates::engine_app(&uproject.engine_association);
+        for known in manifests.values() {
+            let Some(asset) = known.first().and_then(|(app, _)| assets.get(app)) else {
+                continue;
+            };
+            let releases = Release::of(asset);
+            let Some(app) = installed_release(project, known) else {
+                continue;
+            };
+            if let Some(newer) = asset_updates::newer_release(&releases, app, engine.as_deref()) {
+                updates.push(AssetUpdate {
+                    asset: asset.clone(),
+                    installed: app.to_string(),
+                    latest: newer.app.clone(),
+                    location: Location::Project(project.clone()),
+                });
+            }
+        }
+    }
+    updates
+}
@@ -0,0 +1,599 @@
+use crate::tools::asset_updates::Release;
+use crate::tools::vault_cleanup::{Candidate, Category};
+use crate::ui::widgets::download_manager::asset::Asset;
+use crate::ui::widgets::download_manager::download_item::ItemType;
+use crate::ui::widgets::download_manager::Msg;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use log::{debug, info, warn};
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Result of comparing a vault copy against its saved manifest
+#[derive(Debug, Default, Clone)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub missing: Vec<String>,
+    pub corrupt: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Files that need to be downloaded again
+    pub fn broken(&self) -> HashSet<String> {
+        self.missing.iter().chain(&self.corrupt).cloned().collect()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} files checked, {} missing, {} corrupt, {} extra",
+            self.checked,
+            self.missing.len(),
+            self.corrupt.len(),
+            self.extra.len()
+        )
+    }
+}
+
+pub trait Vault {
+    /// Re-hash a release in the vault against its saved manifest, broken files get downloaded again
+    fn verify_release(
+        &self,
+        _asset: Option<egs_api::api::types::asset_info::AssetInfo>,
+        _data_dir: PathBuf,
+    ) {
+        unimplemented!()
+    }
+
+    /// Verify every release found in all configured vaults
+    fn verify_all_vaults(&self) {
+        unimplemented!()
+    }
+
+    fn vault_verified(
+        &self,
+        _asset: Option<egs_api::api::types::asset_info::AssetInfo>,
+        _data_dir: &Path,
+        _report: &VerifyReport,
+        _notify: bool,
+    ) {
+        unimplemented!()
+    }
+
+    /// Download only the listed files of a release into the vault it is in
+    fn repair_release(
+        &self,
+        _asset: egs_api::api::types::asset_info::AssetInfo,
+        _data_dir: &Path,
+        _files: HashSet<String>,
+    ) {
+        unimplemented!()
+    }
+
+    /// Look for releases, leftover chunks and folders that can be removed from the vaults
+    fn scan_vault_cleanup(&self, _sender: async_channel::Sender<Vec<Candidate>>) {
+        unimplemented!()
+    }
+
+    /// Delete the selected candidates in the background
+    fn clean_vaults(&self, _candidates: Vec<Candidate>) {
+        unimplemented!()
+    }
+
+    /// Owned marketplace assets by app name of each release, and the ids of the owned Fab
+    /// assets and their artifacts
+    fn owned_library(
+        &self,
+    ) -> (
+        HashMap<String, egs_api::api::types::asset_info::AssetInfo>,
+        HashSet<String>,
+    ) {
+        unimplemented!()
+    }
+}
+
+impl Vault for crate::ui::widgets::download_manager::EpicDownloadManager {
+    fn verify_release(
+        &self,
+        asset: Option<egs_api::api::types::asset_info::AssetInfo>,
+        data_dir: PathBuf,
+    ) {
+        let self_ = self.imp();
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "vault-verify",
+                &format!("Verifying {}", data_dir.display()),
+                gtk4::MessageType::Info,
+            );
+        }
+        let sender = self_.sender.clone();
+        self_.file_pool.execute(move || {
+            if let Some(report) = verify_release(&data_dir) {
+                let _ = sender.send_blocking(Msg::VaultVerified(
+                    asset.map(Box::new),
+                    data_dir,
+                    report,
+                    true,
+                ));
+            } else {
+                let _ = sender.send_blocking(Msg::IOError(format!(
+                    "No saved manifest for {}",
+                    data_dir.display()
+                )));
+            }
+        });
+    }
+
+    fn verify_all_vaults(&self) {
+        let self_ = self.imp();
+        let vaults: Vec<String> = self_
+            .settings
+            .strv("unreal-vault-directories")
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect();
+        let cache_dir = PathBuf::from(self_.settings.string("cache-directory").to_string());
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "vault-verify",
+                "Verifying all vaults",
+                gtk4::MessageType::Info,
+            );
+        }
+        let sender = self_.sender.clone();
+        self_.file_pool.execute(move || {
+            let mut assets = cached_assets(&cache_dir);
+            let mut checked = 0;
+            let mut broken = 0;
+            for vault in vaults {
+                let Ok(entries) = std::fs::read_dir(&vault) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    if !crate::RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    let data_dir = entry.path().join("data");
+                    if !data_dir.is_dir() {
+                        continue;
+                    }
+                    let Some(report) = verify_release(&data_dir) else {
+                        continue;
+                    };
+                    checked += 1;
+                    if report.broken().is_empty() && report.extra.is_empty() {
+                        continue;
+                    }
+                    broken += 1;
+                    let app = entry.file_name().to_string_lossy().to_string();
+                    let _ = sender.send_blocking(Msg::VaultVerified(
+                        assets.remove(&app).map(Box::new),
+                        data_dir,
+                        report,
+                        false,
+                    ));
+                }
+            }
+            let _ = sender.send_blocking(Msg::VaultsVerified(checked, broken));
+        });
+    }
+
+    fn vault_verified(
+        &self,
+        asset: Option<egs_api::api::types::asset_info::AssetInfo>,
+        data_dir: &Path,
+        report: &VerifyReport,
+        notify: bool,
+    ) {
+        let self_ = self.imp();
+        info!("Verified {}: {}", data_dir.display(), report.summary());
+        for file in &report.missing {
+            warn!("Missing file in {}: {}", data_dir.display(), file);
+        }
+        for file in &report.corrupt {
+            warn!("Corrupt file in {}: {}", data_dir.display(), file);
+        }
+        for file in &report.extra {
+            warn!("Extra file in {}: {}", data_dir.display(), file);
+        }
+        if notify {
+            if let Some(w) = self_.window.get() {
+                w.add_notification(
+                    "vault-verify",
+                    &format!("{}: {}", data_dir.display(), report.summary()),
+                    gtk4::MessageType::Info,
+                );
+            }
+        }
+        let broken = report.broken();
+        if broken.is_empty() {
+            return;
+        }
+        match asset {
+            Some(asset) => self.repair_release(asset, data_dir, broken),
+            None => warn!(
+                "Unable to repair {}, asset information is not available",
+                data_dir.display()
+            ),
+        }
+    }
+
+    fn repair_release(
+        &self,
+        asset: egs_api::api::types::asset_info::AssetInfo,
+        data_dir: &Path,
+        files: HashSet<String>,
+    ) {
+        let self_ = self.imp();
+        let Some(release_dir) = data_dir.parent() else {
+            return;
+        };
+        let (Some(app), Some(vault)) = (release_dir.file_name(), release_dir.parent()) else {
+            return;
+        };
+        let app = app.to_string_lossy().to_string();
+        if self.get_item(&app).is_some() {
+            debug!("{} is already downloading, not repairing", app);
+            return;
+        }
+        debug!("Repairing {} files of {}", files.len(), app);
+        self_
+            .state
+            .borrow_mut()
+            .repair_files
+            .insert(app.clone(), files);
+        self.add_asset_download(
+            app.clone(),
+            asset,
+            &Some(vault.to_string_lossy().to_string()),
+            None,
+        );
+        // Repairs only touch the broken files, nothing to take from other releases
+        self_.state.borrow_mut().previous_releases.remove(&app);
+    }
+
+    fn scan_vault_cleanup(&self, sender: async_channel::Sender<Vec<Candidate>>) {
+        let self_ = self.imp();
+        let vaults: Vec<PathBuf> = self_
+            .settings
+            .strv("unreal-vault-directories")
+            .iter()
+            .map(|v| PathBuf::from(v.as_str()))
+            .collect();
+        let temp_dir = PathBuf::from(
+            self_
+                .settings
+                .string("temporary-download-directory")
+                .to_string(),
+        );
+        let (owned, fab_owned) = self.owned_library();
+        let (busy, chunks) = {
+            let state = self_.state.borrow();
+            let busy: HashSet<String> = state.download_items.keys().cloned().collect();
+            // Downloads that did not read their manifest yet may need any stored chunk
+            let planned = state
+                .download_items
+                .iter()
+                .filter(|(_, item)| item.item_type() == ItemType::Asset)
+                .all(|(id, _)| state.asset_guids.contains_key(id));
+            let chunks: Option<HashSet<String>> = planned.then(|| {
+                state
+                    .chunk_urls
+                    .keys()
+                    .chain(state.downloaded_chunks.keys())
+                    .chain(state.paused_asset_chunks.keys())
+                    .chain(state.asset_guids.values().flatten())
+                    .cloned()
+                    .collect()
+            });
+            (busy, chunks)
+        };
+        self_.file_pool.execute(move || {
+            let candidates = cleanup_candidates(
+                &vaults,
+                &temp_dir,
+                &owned,
+                &fab_owned,
+                &busy,
+                chunks.as_ref(),
+            );
+            let _ = sender.send_blocking(candidates);
+        });
+    }
+
+    fn owned_library(
+        &self,
+    ) -> (
+        HashMap<String, egs_api::api::types::asset_info::AssetInfo>,
+        HashSet<String>,
+    ) {
+        let mut owned = HashMap::new();
+        let mut fab_owned = HashSet::new();
+        let Some(w) = self.imp().window.get() else {
+            return (owned, fab_owned);
+        };
+        let w_: &crate::window::imp::EpicAssetManagerWindow = w.imp();
+        let l = w_.logged_in_stack.imp();
+        for asset in l.library.imp().loaded_assets.borrow().values() {
+            for release in asset.release_info.clone().unwrap_or_default() {
+                if let Some(app) = release.app_id {
+                    owned.insert(app, asset.clone());
+                }
+            }
+        }
+        let fab_model = &l.fab.imp().grid_model;
+        for i in 0..fab_model.n_items() {
+            let Some(data) = fab_model
+                .item(i)
+                .and_downcast::<crate::models::fab_data::FabData>()
+            else {
+                continue;
+            };
+            let asset = data.imp().asset.borrow();
+            if let Some(asset) = asset.as_ref() {
+                fab_owned.insert(asset.asset_id.clone());
+                for version in &asset.project_versions {
+                    fab_owned.insert(version.artifact_id.clone());
+                }
+            }
+        }
+        (owned, fab_owned)
+    }
+
+    fn clean_vaults(&self, mut candidates: Vec<Candidate>) {
+        let self_ = self.imp();
+        if let Some(w) = self_.window.get() {
+            w.add_notification(
+                "vault-cleanup",
+                &format!("Cleaning up {} vault entries", candidates.len()),
+                gtk4::MessageType::Info,
+            );
+        }
+        crate::tools::vault_cleanup::deletion_order(&mut candidates);
+        let sender = self_.sender.clone();
+        self_.file_pool.execute(move || {
+            let mut freed = 0;
+            let mut failed = 0;
+            for candidate in &candidates {
+                match crate::tools::vault_cleanup::remove(candidate) {
+                    Ok(size) => freed += size,
+                    Err(e) => {
+                        warn!("Unable to remove {}: {}", candidate.path.display(), e);
+                        failed += 1;
+                    }
+                }
+            }
+            let _ = sender.send_blocking(Msg::VaultsCleaned(freed, failed));
+        });
+    }
+}
+
+/// Compare the files in the `data` directory of a release with the manifest saved next to it
+pub fn verify_release(data_dir: &Path) -> Option<VerifyReport> {
+    let manifest = super::delta::load_release_manifest(data_dir.parent()?)?;
+    let mut report = VerifyReport::default();
+    let mut expected = HashSet::new();
+    for file in &manifest.file_manifest_list {
+        let name = file.filename.replace('\\', "/");
+        let path = data_dir.join(&name);
+        report.checked += 1;
+        match file_sha1(&path) {
+            None => report.missing.push(file.filename.clone()),
+            Some(hash) if !hash.eq(&file.file_hash) => report.corrupt.push(file.filename.clone()),
+            Some(_) => {}
+        }
+        expected.insert(name);
+    }
+    let mut existing = Vec::new();
+    list_files(data_dir, data_dir, &mut existing);
+    report.extra = existing
+        .into_iter()
+        .filter(|f| !expected.contains(f))
+        .collect();
+    Some(report)
+}
+
+fn file_sha1(path: &Path) -> Option<String> {
+    let mut f = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha1::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        match f.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(size) => hasher.update(&buffer[..size]),
+            Err(e) => {
+                warn!("Unable to read {:?}: {}", path, e);
+                return Some(String::new());
+            }
+        }
+    }
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .fold(String::new(), |mut output, b| {
+                let _ = write!(output, "{b:02x}");
+                output
+            }),
+    )
+}
+
+fn list_files(base: &Path, dir: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            list_files(base, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            files.push(
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+    }
+}
+
+/// Find what can be removed from the vaults, releases that are downloading are left alone
+///
+/// `owned` maps the app name of every release of the owned marketplace assets to the asset,
+/// `fab_owned` holds the asset and artifact ids of the owned Fab assets. Stored chunks are only
+/// looked at when the chunks of all queued downloads are known.
+pub fn cleanup_candidates(
+    vaults: &[PathBuf],
+    temp_dir: &Path,
+    owned: &HashMap<String, egs_api::api::types::asset_info::AssetInfo>,
+    fab_owned: &HashSet<String>,
+    busy: &HashSet<String>,
+    chunks: Option<&HashSet<String>>,
+) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    let mut present: HashMap<String, Vec<(String, PathBuf)>> = HashMap::new();
+    let mut roots: Vec<&Path> = vaults.iter().map(PathBuf::as_path).collect();
+    if !roots.contains(&temp_dir) {
+        roots.push(temp_dir);
+    }
+    for root in &roots {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let app = entry.file_name().to_string_lossy().to_string();
+            if app == "chunks" {
+                if let Some(referenced) = chunks {
+                    for chunk in crate::tools::vault_cleanup::orphaned_chunks(&path, referenced) {
+                        candidates.push(Candidate {
+                            category: Category::Temporary,
+                            label: chunk
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_default(),
+                            path: chunk,
+                            size: 0,
+                        });
+                    }
+                }
+                continue;
+            }
+            if busy.contains(&app) {
+                continue;
+            }
+            // Chunks of downloads from before the shared chunk store
+            let temp = path.join("temp");
+            if temp.is_dir() {
+                candidates.push(Candidate {
+                    category: Category::Temporary,
+                    label: app.clone(),
+                    path: temp,
+                    size: 0,
+                });
+            }
+            if !path.join("data").is_dir() {
+                continue;
+            }
+            match owned.get(&app) {
+                Some(asset) => present
+                    .entry(asset.id.clone())
+                    .or_default()
+                    .push((app, path)),
+                None if fab_owned.contains(&app) => {}
+                // Until both library lists are loaded everything would look unowned
+                None if !owned.is_empty() && !fab_owned.is_empty() => candidates.push(Candidate {
+                    category: Category::NotOwned,
+                    label: app,
+                    path,
+                    size: 0,
+                }),
+                None => {}
+            }
+        }
+    }
+
+    for releases in present.values() {
+        let Some(asset) = owned.get(&releases[0].0) else {
+            continue;
+        };
+        let ordered: Vec<Release> = Release::of(asset)
+            .into_iter()
+            .filter(|r| releases.iter().any(|(a, _)| *a == r.app))
+            .collect();
+        for release in crate::tools::vault_cleanup::superseded(&ordered) {
+            // The same release can be in more than one vault
+            for (_, path) in releases.iter().filter(|(a, _)| *a == release.app) {
+                candidates.push(Candidate {
+                    category: Category::Superseded,
+                    label: format!(
+                        "{} ({})",
+                        asset.title.clone().unwrap_or_default(),
+                        release.app
+                    ),
+                    path: path.clone(),
+                    size: 0,
+                });
+            }
+        }
+    }
+
+    for root in &roots {
+        for path in crate::tools::vault_cleanup::empty_dirs(root) {
+            let removed = candidates.iter().any(|c| path.starts_with(&c.path));
+            let in_use = path
+                .strip_prefix(root)
+                .ok()
+                .and_then(|p| p.components().next())
+                .is_some_and(|c| {
+                    let first = c.as_os_str().to_string_lossy();
+                    first == "chunks" || busy.contains(first.as_ref())
+                });
+            if !removed && !in_use {
+                candidates.push(Candidate {
+                    category: Category::Empty,
+                    label: path.display().to_string(),
+                    path,
+                    size: 0,
+                });
+            }
+        }
+    }
+
+    for candidate in &mut candidates {
+        candidate.size = crate::tools::vault_cleanup::dir_size(&candidate.path);
+    }
+    candidates
+}
+
+/// Asset information from the library cache by app name of each release
+pub fn cached_assets(
+    cache_dir: &Path,
+) -> HashMap<String, egs_api::api::types::asset_info::AssetInfo> {
+    let mut result = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return result;
+    };
+    for entry in entries.flatten() {
+        let Ok(f) = std::fs::File::open(entry.path().join("asset_info.json")) else {
+            continue;
+        };
+        let Ok(asset) = serde_json::from_reader::<_, egs_api::api::types::asset_info::AssetInfo>(f)
+        else {
+            continue;
+        };
+        for release in asset.release_info.clone().unwrap_or_default() {
+            if let Some(app) = release.app_id {
+                result.insert(app, asset.clone());
+            }
+        }
+    }
+    result
+}
@@ -1,6 +1,9 @@
+use crate::ui::widgets::download_manager::local_install::LocalInstall;
+use gtk4::glib::clone;
 use gtk4::subclass::prelude::*;
-use gtk4::{self, gio};
+use gtk4::{self, gio, prelude::*};
 use gtk4::{glib, CompositeTemplate};
+use gtk_macros::action;
 
 pub mod imp {
     use super::*;
@@ -52,6 +55,7 @@ pub mod imp {
     impl ObjectImpl for EpicEngineInstall {
         fn constructed(&self) {
             self.parent_constructed();
+            self.obj().setup_actions();
         }
     }
 
@@ -104,6 +108,58 @@ impl EpicEngineInstall {
         self_.download_manager.set(dm.clone()).unwrap();
     }
 
+    pub fn setup_actions(&self) {
+        let self_ = self.imp();
+        let actions = &self_.actions;
+        self.insert_action_group("engine_install", Some(actions));
+
+        action!(
+            actions,
+            "from_file",
+            clone!(
+                #[weak(rename_to=install)]
+                self,
+                move |_, _| {
+                    install.choose_file();
+                }
+            )
+        );
+    }
+
+    /// Pick a local engine archive and install it
+    fn choose_file(&self) {
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some("Engine archives"));
+        filter.add_suffix("zip");
+        filter.add_suffix("tar");
+        filter.add_mime_type("application/zip");
+        filter.add_mime_type("application/x-tar");
+        let filters = gio::ListStore::new::<gtk4::FileFilter>();
+        filters.append(&filter);
+        let dialog = gtk4::FileDialog::builder()
+            .title("Install Engine from File")
+            .modal(true)
+            .filters(&filters)
+            .default_filter(&filter)
+            .build();
+        let parent = self.root().and_downcast::<gtk4::Window>();
+        dialog.open(
+            parent.as_ref(),
+            None::<&gio::Cancellable>,
+            clone!(
+                #[weak(rename_to=install)]
+                self,
+                move |result| {
+                    if let Some(path) = result.ok().and_then(|file| file.path()) {
+                        if let Some(dm) = install.imp().download_manager.get() {
+                            dm.install_engine_from_file(&path);
+                        }
+                    }
+                }
+            ),
+        );
+    }
+
     pub fn update_docker(&self) {
         let self_ = self.imp();
         self_.docker.update_docker();
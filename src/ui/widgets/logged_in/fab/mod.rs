@@ -1,3 +1,5 @@
+use crate::tools::asset_info::Search;
+use crate::tools::category_filter::Expr;
 use glib::clone;
 use gtk4::subclass::prelude::*;
 use gtk4::{self, gio, prelude::*};
@@ -57,6 +59,8 @@ pub mod imp {
         pub browse_cursor: RefCell<Option<String>>,
         pub browse_known_ids: RefCell<HashSet<String>>,
         pub search: RefCell<Option<String>>,
+        /// Full-text index of the library assets
+        pub search_index: RefCell<crate::tools::search::Index>,
         /// Tracks asset IDs already in the grid to avoid duplicates on refresh
         pub known_asset_ids: RefCell<HashSet<String>>,
         /// Sorted set of known category names for the dropdown
@@ -106,6 +110,7 @@ pub mod imp {
                 browse_cursor: RefCell::new(None),
                 browse_known_ids: RefCell::new(HashSet::new()),
                 search: RefCell::new(None),
+                search_index: RefCell::new(crate::tools::search::Index::default()),
                 known_asset_ids: RefCell::new(HashSet::new()),
                 category_names: RefCell::new(BTreeSet::new()),
                 category_filter_names: RefCell::new(vec![String::new()]),
@@ -420,7 +425,7 @@ impl FabLibraryBox {
         let favorites_only = self_.favorites_filter.is_active();
 
         let selected_cat = self_.category_dropdown.selected();
-        let category_filter: Option<String> =
+        let category_filter: Option<Expr> =
             if selected_cat == 0 || selected_cat == gtk4::INVALID_LIST_POSITION {
                 None
             } else {
@@ -429,7 +434,7 @@ impl FabLibraryBox {
                     .borrow()
                     .get(selected_cat as usize)
                     .filter(|n| !n.is_empty())
-                    .cloned()
+                    .map(|n| Expr::Category(n.clone()))
             };
 
         if *self_.browse_mode.borrow() {
@@ -440,7 +445,12 @@ impl FabLibraryBox {
             return;
         }
 
-        if search.is_none() && !downloaded_only && !favorites_only && category_filter.is_none() {
+        let matches = search
+            .map(|s| crate::tools::search::Query::parse(&s))
+            .filter(|query| !query.is_empty())
+            .map(|query| self_.search_index.borrow().search(&query));
+
+        if matches.is_none() && !downloaded_only && !favorites_only && category_filter.is_none() {
             self_.filter_model.set_filter(None::<&gtk4::CustomFilter>);
             self.update_count();
             return;
@@ -448,9 +458,7 @@ impl FabLibraryBox {
 
         let filter = gtk4::CustomFilter::new(move |obj| {
             if let Some(data) = obj.downcast_ref::<crate::models::fab_data::FabData>() {
-                let matches_search = search
-                    .as_ref()
-                    .is_none_or(|s| data.name().to_lowercase().contains(&s.to_lowercase()));
+                let matches_search = matches.as_ref().is_none_or(|m| m.contains_key(&data.id()));
                 let matches_downloaded = !downloaded_only || data.downloaded();
                 let matches_favorites = !favorites_only || data.favorite();
                 let matches_category = category_filter
@@ -569,6 +577,15 @@ impl FabLibraryBox {
             model.append(name);
             filter_names.push(name.clone());
         }
+        drop(names);
+
+        for (label, filter) in crate::models::collections::dropdown_entries(
+            &crate::models::collections::collections(),
+            &crate::models::collections::tags(),
+        ) {
+            model.append(&label);
+            filter_names.push(filter);
+        }
 
         let new_selected = filter_names
             .iter()
@@ -838,11 +855,36 @@ impl FabLibraryBox {
             return;
         }
         self.add_asset_categories(asset);
+        self_
+            .search_index
+            .borrow_mut()
+            .add(&asset.asset_id, &asset.search_fields());
         let data = crate::models::fab_data::FabData::new(asset, image);
         self_.grid_model.append(&data);
         self.update_count();
     }
 
+    /// Show new or removed collections and tags in the category dropdown
+    pub fn refresh_labels(&self) {
+        // Browsing Fab lists its own tags, collections only apply to the library
+        if !*self.imp().browse_mode.borrow() {
+            self.rebuild_category_dropdown();
+        }
+    }
+
+    /// Load the collections and tags of every asset again, e.g. after a collection was deleted
+    pub fn reload_labels(&self) {
+        let self_ = self.imp();
+        for i in 0..self_.grid_model.n_items() {
+            if let Some(obj) = self_.grid_model.item(i) {
+                if let Some(data) = obj.downcast_ref::<crate::models::fab_data::FabData>() {
+                    data.check_labels();
+                }
+            }
+        }
+        self.update_filter();
+    }
+
     pub fn refresh_fab_asset(&self, id: &str) {
         let self_ = self.imp();
         for i in 0..self_.grid_model.n_items() {
@@ -861,6 +903,10 @@ impl FabLibraryBox {
     pub fn flush_fab_assets(&self) {
         let self_ = self.imp();
         self_.refresh_progress.set_visible(false);
+        // Search results were computed before these assets were indexed
+        if self_.search.borrow().is_some() {
+            self.update_filter();
+        }
         self.update_count();
     }
 
@@ -1076,6 +1122,7 @@ impl FabLibraryBox {
         let self_ = self.imp();
         self_.grid_model.remove_all();
         self_.known_asset_ids.borrow_mut().clear();
+        self_.search_index.borrow_mut().clear();
         self_.category_names.borrow_mut().clear();
         self_.category_filter_names.replace(vec![String::new()]);
         let model = gtk4::StringList::new(&["All"]);
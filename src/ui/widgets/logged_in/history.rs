@@ -0,0 +1,309 @@
+use crate::models::history_data::{HistoryData, HistoryRecord, Outcome};
+use adw::prelude::{ActionRowExt, PreferencesRowExt};
+use chrono::{Local, TimeZone};
+use gtk4::glib::clone;
+use gtk4::{self, gio, glib, prelude::*, subclass::prelude::*, CompositeTemplate};
+use gtk_macros::action;
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Loaded(Vec<HistoryRecord>),
+}
+
+pub mod imp {
+    use super::*;
+    use std::cell::RefCell;
+    use threadpool::ThreadPool;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/io/github/achetagames/epic_asset_manager/history.ui")]
+    pub struct EpicHistory {
+        pub actions: gio::SimpleActionGroup,
+        #[template_child]
+        pub search: TemplateChild<gtk4::SearchEntry>,
+        #[template_child]
+        pub outcome: TemplateChild<gtk4::DropDown>,
+        #[template_child]
+        pub stack: TemplateChild<gtk4::Stack>,
+        #[template_child]
+        pub entries: TemplateChild<gtk4::ListView>,
+        #[template_child]
+        pub total_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub months: TemplateChild<gtk4::ListBox>,
+        #[template_child]
+        pub assets: TemplateChild<gtk4::ListBox>,
+        pub model: gio::ListStore,
+        pub records: RefCell<Vec<HistoryRecord>>,
+        pub sender: async_channel::Sender<Msg>,
+        pub receiver: RefCell<Option<async_channel::Receiver<Msg>>>,
+        pub load_pool: ThreadPool,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EpicHistory {
+        const NAME: &'static str = "EpicHistory";
+        type Type = super::EpicHistory;
+        type ParentType = gtk4::Box;
+
+        fn new() -> Self {
+            let (sender, receiver) = async_channel::unbounded();
+            Self {
+                actions: gio::SimpleActionGroup::new(),
+                search: TemplateChild::default(),
+                outcome: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                entries: TemplateChild::default(),
+                total_row: TemplateChild::default(),
+                months: TemplateChild::default(),
+                assets: TemplateChild::default(),
+                model: gio::ListStore::new::<HistoryData>(),
+                records: RefCell::new(Vec::new()),
+                sender,
+                receiver: RefCell::new(Some(receiver)),
+                load_pool: ThreadPool::with_name("History Load Pool".to_string(), 1),
+            }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for EpicHistory {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.setup_actions();
+            obj.setup_messaging();
+            obj.setup_view();
+            obj.refresh();
+        }
+    }
+
+    impl WidgetImpl for EpicHistory {}
+    impl BoxImpl for EpicHistory {}
+}
+
+glib::wrapper! {
+    pub struct EpicHistory(ObjectSubclass<imp::EpicHistory>)
+        @extends gtk4::Widget, gtk4::Box,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Orientable;
+}
+
+impl Default for EpicHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpicHistory {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn setup_actions(&self) {
+        let self_ = self.imp();
+        self.insert_action_group("history", Some(&self_.actions));
+
+        action!(
+            self_.actions,
+            "refresh",
+            clone!(
+                #[weak(rename_to=history)]
+                self,
+                move |_, _| {
+                    history.refresh();
+                }
+            )
+        );
+
+        self_.search.connect_search_changed(clone!(
+            #[weak(rename_to=history)]
+            self,
+            move |_| {
+                history.apply_filter();
+            }
+        ));
+        self_.outcome.connect_selected_notify(clone!(
+            #[weak(rename_to=history)]
+            self,
+            move |_| {
+                history.apply_filter();
+            }
+        ));
+    }
+
+    pub fn setup_messaging(&self) {
+        glib::MainContext::default().spawn_local(clone!(
+            #[weak(rename_to=history)]
+            self,
+            async move {
+                let self_ = history.imp();
+                let receiver = self_.receiver.borrow_mut().take().unwrap();
+                while let Ok(msg) = receiver.recv().await {
+                    history.update(msg);
+                }
+            }
+        ));
+    }
+
+    pub fn update(&self, msg: Msg) {
+        let self_ = self.imp();
+        match msg {
+            Msg::Loaded(records) => {
+                self_.records.replace(records);
+                self.apply_filter();
+            }
+        }
+    }
+
+    fn setup_view(&self) {
+        let self_ = self.imp();
+        let factory = gtk4::SignalListItemFactory::new();
+        factory.connect_setup(move |_factory, item| {
+            let row = adw::ActionRow::new();
+            row.set_activatable(false);
+            let item = item.downcast_ref::<gtk4::ListItem>().unwrap();
+            item.set_child(Some(&row));
+        });
+
+        factory.connect_bind(move |_, list_item| {
+            let item = list_item.downcast_ref::<gtk4::ListItem>().unwrap();
+            Self::populate_row(item);
+        });
+
+        let selection_model = gtk4::NoSelection::new(Some(self_.model.clone()));
+        self_.entries.set_model(Some(&selection_model));
+        self_.entries.set_factory(Some(&factory));
+    }
+
+    fn populate_row(list_item: &gtk4::ListItem) {
+        let Some(record) = list_item
+            .item()
+            .and_then(|i| i.downcast::<HistoryData>().ok())
+            .and_then(|d| d.record())
+        else {
+            return;
+        };
+        let Some(row) = list_item
+            .child()
+            .and_then(|c| c.downcast::<adw::ActionRow>().ok())
+        else {
+            return;
+        };
+
+        let when = Local
+            .timestamp_opt(record.finished_at, 0)
+            .single()
+            .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        row.set_title(&glib::markup_escape_text(&record.label));
+        let mut details = vec![record.outcome.title().to_string(), when];
+        if let Some(release) = &record.release {
+            details.push(release.clone());
+        }
+        details.push(format_size(record.bytes));
+        details.push(format_duration(record.duration()));
+        details.push(format!("{}/s", format_size(record.average_speed)));
+        row.set_subtitle(&glib::markup_escape_text(&details.join(" · ")));
+
+        let mut tooltip = record.targets.join("\n");
+        if let Some(error) = &record.error {
+            tooltip = format!("{error}\n{tooltip}");
+        }
+        row.set_tooltip_text((!tooltip.trim().is_empty()).then_some(tooltip.trim()));
+        if record.outcome == Outcome::Failed {
+            row.add_css_class("error");
+        } else {
+            row.remove_css_class("error");
+        }
+    }
+
+    /// Reload the history from the database
+    pub fn refresh(&self) {
+        let self_ = self.imp();
+        let sender = self_.sender.clone();
+        self_.load_pool.execute(move || {
+            let _ = sender.send_blocking(Msg::Loaded(HistoryRecord::load_all()));
+        });
+    }
+
+    fn selected_outcome(&self) -> Option<Outcome> {
+        let self_ = self.imp();
+        match self_.outcome.selected() {
+            1 => Some(Outcome::Finished),
+            2 => Some(Outcome::Canceled),
+            3 => Some(Outcome::Failed),
+            _ => None,
+        }
+    }
+
+    /// Show the records matching the filters and summarize them
+    fn apply_filter(&self) {
+        let self_ = self.imp();
+        let text = self_.search.text();
+        let outcome = self.selected_outcome();
+        let records: Vec<HistoryRecord> = self_
+            .records
+            .borrow()
+            .iter()
+            .filter(|r| r.matches(&text, outcome))
+            .cloned()
+            .collect();
+
+        let items: Vec<HistoryData> = records.iter().map(HistoryData::new).collect();
+        self_.model.splice(0, self_.model.n_items(), &items);
+        self_.stack.set_visible_child_name(if records.is_empty() {
+            "empty"
+        } else {
+            "entries"
+        });
+
+        let total: u64 = records.iter().map(|r| r.bytes).sum();
+        self_.total_row.set_subtitle(&format_size(total));
+        Self::fill_totals(
+            &self_.months,
+            &crate::models::history_data::monthly_totals(&records),
+        );
+        Self::fill_totals(
+            &self_.assets,
+            &crate::models::history_data::asset_totals(&records),
+        );
+    }
+
+    fn fill_totals(list: &gtk4::ListBox, totals: &[(String, u64)]) {
+        list.remove_all();
+        for (name, bytes) in totals {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(name))
+                .build();
+            row.add_suffix(&gtk4::Label::new(Some(&format_size(*bytes))));
+            list.append(&row);
+        }
+        list.set_visible(!totals.is_empty());
+    }
+}
+
+fn format_size(size: u64) -> String {
+    format!(
+        "{:.2}",
+        byte_unit::Byte::from_u64(size).get_appropriate_unit(byte_unit::UnitType::Decimal)
+    )
+}
+
+fn format_duration(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
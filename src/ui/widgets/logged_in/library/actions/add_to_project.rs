@@ -187,6 +187,18 @@ impl EpicAddToProject {
             )
         );
 
+        action!(
+            actions,
+            "choose_files",
+            clone!(
+                #[weak(rename_to=download_details)]
+                self,
+                move |_, _| {
+                    download_details.choose_files();
+                }
+            )
+        );
+
         self_
             .select_target_directory
             .connect_selected_notify(clone!(
@@ -220,6 +232,17 @@ impl EpicAddToProject {
         }
     }
 
+    /// Pick the files of the asset to install, remembered for later downloads and updates
+    fn choose_files(&self) {
+        let self_ = self.imp();
+        let Some(asset) = &*self_.asset.borrow() else {
+            return;
+        };
+        if let Some(manifest) = &*self_.manifest.borrow() {
+            super::file_selection_dialog::EpicFileSelectionDialog::open(self, &asset.id, manifest);
+        }
+    }
+
     pub fn set_asset(&self, asset: &egs_api::api::types::asset_info::AssetInfo) {
         let self_ = self.imp();
         if let Some(asset_info) = &*self_.asset.borrow() {
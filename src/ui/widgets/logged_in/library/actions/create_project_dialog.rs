@@ -508,10 +508,18 @@ impl EpicCreateProjectDialog {
 
                             let source = source_path.clone();
                             let target = target_path.clone();
+                            if let Some(dm) = self_.download_manager.get() {
+                                dm.estimate_copy(source.clone(), vec![target.clone()], None);
+                            }
+                            let strategy = crate::tools::copy::CopyStrategy::from_setting(
+                                &gio::Settings::new(crate::config::APP_ID).string("copy-strategy"),
+                            );
 
                             // Perform copy in background thread and launch project when done
                             std::thread::spawn(move || {
-                                if let Err(e) = Self::copy_directory(&source, &target, overwrite) {
+                                if let Err(e) =
+                                    Self::copy_directory(&source, &target, overwrite, strategy)
+                                {
                                     log::error!("Failed to copy project: {:?}", e);
                                 } else {
                                     log::info!("Project created successfully at {:?}", target);
@@ -554,7 +562,12 @@ impl EpicCreateProjectDialog {
         }
     }
 
-    fn copy_directory(source: &PathBuf, target: &PathBuf, overwrite: bool) -> std::io::Result<()> {
+    fn copy_directory(
+        source: &PathBuf,
+        target: &PathBuf,
+        overwrite: bool,
+        strategy: crate::tools::copy::CopyStrategy,
+    ) -> std::io::Result<()> {
         if target.exists() {
             if overwrite {
                 std::fs::remove_dir_all(target)?;
@@ -576,9 +589,9 @@ impl EpicCreateProjectDialog {
             let target_path = target.join(&file_name);
 
             if file_type.is_dir() {
-                Self::copy_directory(&source_path, &target_path, overwrite)?;
+                Self::copy_directory(&source_path, &target_path, overwrite, strategy)?;
             } else {
-                std::fs::copy(&source_path, &target_path)?;
+                crate::tools::copy::place(&source_path, &target_path, strategy)?;
             }
         }
 
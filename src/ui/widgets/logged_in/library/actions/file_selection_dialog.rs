@@ -0,0 +1,426 @@
+use crate::models::file_selection::{tree, FileSelection, TreeEntry};
+use adw::prelude::ActionRowExt;
+use adw::subclass::prelude::AdwWindowImpl;
+use gtk4::glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{self, gio, prelude::*};
+use gtk4::{glib, CompositeTemplate};
+use gtk_macros::{action, get_action};
+use std::collections::HashMap;
+
+pub mod imp {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/io/github/achetagames/epic_asset_manager/file_selection_dialog.ui")]
+    pub struct EpicFileSelectionDialog {
+        pub asset: RefCell<Option<String>>,
+        pub selection: RefCell<FileSelection>,
+        pub entries: RefCell<HashMap<String, TreeEntry>>,
+        /// Manifest files with their size
+        pub files: RefCell<Vec<(String, u128)>>,
+        /// Included files and their size below every entry of the tree
+        pub included: RefCell<HashMap<String, (usize, u128)>>,
+        pub model: gtk4::StringList,
+        /// Rows are being filled, the check buttons did not change from a click
+        pub binding: Cell<bool>,
+        pub actions: gio::SimpleActionGroup,
+        #[template_child]
+        pub summary: TemplateChild<gtk4::Label>,
+        #[template_child]
+        pub files_view: TemplateChild<gtk4::ListView>,
+        #[template_child]
+        pub pattern: TemplateChild<gtk4::Entry>,
+        #[template_child]
+        pub rules: TemplateChild<gtk4::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EpicFileSelectionDialog {
+        const NAME: &'static str = "EpicFileSelectionDialog";
+        type Type = super::EpicFileSelectionDialog;
+        type ParentType = adw::Window;
+
+        fn new() -> Self {
+            Self {
+                asset: RefCell::new(None),
+                selection: RefCell::new(FileSelection::default()),
+                entries: RefCell::new(HashMap::new()),
+                files: RefCell::new(Vec::new()),
+                included: RefCell::new(HashMap::new()),
+                model: gtk4::StringList::new(&[] as &[&str]),
+                binding: Cell::new(false),
+                actions: gio::SimpleActionGroup::new(),
+                summary: TemplateChild::default(),
+                files_view: TemplateChild::default(),
+                pattern: TemplateChild::default(),
+                rules: TemplateChild::default(),
+            }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for EpicFileSelectionDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup_view();
+            self.obj().setup_actions();
+        }
+    }
+
+    impl WidgetImpl for EpicFileSelectionDialog {}
+    impl WindowImpl for EpicFileSelectionDialog {}
+    impl AdwWindowImpl for EpicFileSelectionDialog {}
+}
+
+glib::wrapper! {
+    pub struct EpicFileSelectionDialog(ObjectSubclass<imp::EpicFileSelectionDialog>)
+        @extends gtk4::Widget, gtk4::Window, adw::Window,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Native, gtk4::Root, gtk4::ShortcutManager;
+}
+
+impl Default for EpicFileSelectionDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpicFileSelectionDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Open the picker for an asset next to the widget that asked for it
+    pub fn open(
+        parent: &impl IsA<gtk4::Widget>,
+        asset: &str,
+        manifest: &egs_api::api::types::download_manifest::DownloadManifest,
+    ) {
+        let dialog = Self::new();
+        if let Some(window) = parent
+            .root()
+            .and_then(|r| r.downcast::<gtk4::Window>().ok())
+        {
+            dialog.set_transient_for(Some(&window));
+        }
+        dialog.set_files(
+            asset,
+            manifest
+                .file_manifest_list
+                .iter()
+                .map(|f| (f.filename.clone(), f.size()))
+                .collect(),
+        );
+        dialog.present();
+    }
+
+    fn setup_actions(&self) {
+        let self_ = self.imp();
+        self.insert_action_group("file_selection", Some(&self_.actions));
+
+        action!(
+            self_.actions,
+            "include",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    dialog.add_rule(true);
+                }
+            )
+        );
+        action!(
+            self_.actions,
+            "exclude",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    dialog.add_rule(false);
+                }
+            )
+        );
+        action!(
+            self_.actions,
+            "reset",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    dialog.imp().selection.replace(FileSelection::default());
+                    dialog.refresh();
+                }
+            )
+        );
+        action!(
+            self_.actions,
+            "save",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    let self_ = dialog.imp();
+                    if let Some(asset) = &*self_.asset.borrow() {
+                        self_.selection.borrow().save(asset);
+                    }
+                    dialog.close();
+                }
+            )
+        );
+        self_.pattern.connect_activate(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |_| {
+                dialog.add_rule(false);
+            }
+        ));
+    }
+
+    fn setup_view(&self) {
+        let self_ = self.imp();
+        let factory = gtk4::SignalListItemFactory::new();
+        factory.connect_setup(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |_factory, item| {
+                let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+                row.set_margin_top(2);
+                row.set_margin_bottom(2);
+                row.set_margin_end(12);
+                let check = gtk4::CheckButton::new();
+                let name = gtk4::Label::new(None);
+                name.set_hexpand(true);
+                name.set_xalign(0.0);
+                name.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+                let size = gtk4::Label::new(None);
+                size.add_css_class("dim-label");
+                size.add_css_class("numeric");
+                row.append(&check);
+                row.append(&name);
+                row.append(&size);
+                let item = item.downcast_ref::<gtk4::ListItem>().unwrap();
+                item.set_activatable(false);
+                item.set_child(Some(&row));
+                check.connect_toggled(clone!(
+                    #[weak]
+                    dialog,
+                    #[weak]
+                    item,
+                    move |check| {
+                        dialog.toggled(&item, check.is_active());
+                    }
+                ));
+            }
+        ));
+
+        factory.connect_bind(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |_, list_item| {
+                let item = list_item.downcast_ref::<gtk4::ListItem>().unwrap();
+                dialog.bind_row(item);
+            }
+        ));
+
+        let selection_model = gtk4::NoSelection::new(Some(self_.model.clone()));
+        self_.files_view.set_model(Some(&selection_model));
+        self_.files_view.set_factory(Some(&factory));
+    }
+
+    fn bind_row(&self, list_item: &gtk4::ListItem) {
+        let self_ = self.imp();
+        let Some(path) = list_item
+            .item()
+            .and_then(|i| i.downcast::<gtk4::StringObject>().ok())
+            .map(|s| s.string().to_string())
+        else {
+            return;
+        };
+        let Some(row) = list_item
+            .child()
+            .and_then(|c| c.downcast::<gtk4::Box>().ok())
+        else {
+            return;
+        };
+        let Some(entry) = self_.entries.borrow().get(&path).cloned() else {
+            return;
+        };
+        let Some(check) = row
+            .first_child()
+            .and_then(|c| c.downcast::<gtk4::CheckButton>().ok())
+        else {
+            return;
+        };
+        let Some(name) = check
+            .next_sibling()
+            .and_then(|c| c.downcast::<gtk4::Label>().ok())
+        else {
+            return;
+        };
+        let Some(size) = name
+            .next_sibling()
+            .and_then(|c| c.downcast::<gtk4::Label>().ok())
+        else {
+            return;
+        };
+
+        let (included, included_size) = self_
+            .included
+            .borrow()
+            .get(&path)
+            .copied()
+            .unwrap_or_default();
+        row.set_margin_start(12 + 18 * i32::try_from(entry.depth).unwrap_or_default());
+        name.set_label(&entry.name);
+        name.set_tooltip_text(Some(&entry.path));
+        if entry.folder {
+            name.add_css_class("heading");
+        } else {
+            name.remove_css_class("heading");
+        }
+        size.set_label(&if entry.folder && included > 0 && included < entry.files {
+            format!(
+                "{} of {}",
+                format_size(included_size),
+                format_size(entry.size)
+            )
+        } else {
+            format_size(entry.size)
+        });
+
+        self_.binding.set(true);
+        check.set_active(included > 0);
+        check.set_inconsistent(included > 0 && included < entry.files);
+        self_.binding.set(false);
+    }
+
+    fn toggled(&self, list_item: &gtk4::ListItem, include: bool) {
+        let self_ = self.imp();
+        if self_.binding.get() {
+            return;
+        }
+        let Some(path) = list_item
+            .item()
+            .and_then(|i| i.downcast::<gtk4::StringObject>().ok())
+        else {
+            return;
+        };
+        self_.selection.borrow_mut().set(&path.string(), include);
+        self.refresh();
+    }
+
+    fn set_files(&self, asset: &str, files: Vec<(String, u128)>) {
+        let self_ = self.imp();
+        let entries = tree(&files);
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        self_.model.splice(0, self_.model.n_items(), &paths);
+        self_.entries.replace(
+            entries
+                .iter()
+                .map(|e| (e.path.clone(), e.clone()))
+                .collect(),
+        );
+        self_.files.replace(files);
+        self_.asset.replace(Some(asset.to_string()));
+        self_.selection.replace(FileSelection::load(asset));
+        self.refresh();
+    }
+
+    fn add_rule(&self, include: bool) {
+        let self_ = self.imp();
+        let pattern = self_.pattern.text();
+        if pattern.trim().is_empty() {
+            return;
+        }
+        self_.selection.borrow_mut().set(&pattern, include);
+        self_.pattern.set_text("");
+        self.refresh();
+    }
+
+    /// Recount the included files and redraw the tree and the rules
+    fn refresh(&self) {
+        let self_ = self.imp();
+        let mut included: HashMap<String, (usize, u128)> = HashMap::new();
+        let (mut files, mut size, mut total) = (0, 0, 0);
+        {
+            let selection = self_.selection.borrow();
+            for (path, file_size) in self_.files.borrow().iter() {
+                total += file_size;
+                if !selection.includes(path) {
+                    continue;
+                }
+                files += 1;
+                size += file_size;
+                let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+                for depth in 0..parts.len() {
+                    let counted = included.entry(parts[..=depth].join("/")).or_default();
+                    counted.0 += 1;
+                    counted.1 += file_size;
+                }
+            }
+        }
+        self_.included.replace(included);
+        let count = self_.model.n_items();
+        self_.model.items_changed(0, count, count);
+        let nothing = files == 0 && !self_.files.borrow().is_empty();
+        self_.summary.set_label(&if nothing {
+            "Select at least one file to download".to_string()
+        } else {
+            format!(
+                "{} of {} files selected, {} of {}",
+                files,
+                self_.files.borrow().len(),
+                format_size(size),
+                format_size(total)
+            )
+        });
+        // A selection without files would leave nothing to download
+        get_action!(self_.actions, @save).set_enabled(!nothing);
+        self.fill_rules();
+    }
+
+    fn fill_rules(&self) {
+        let self_ = self.imp();
+        self_.rules.remove_all();
+        let rules = self_.selection.borrow().rules();
+        for rule in &rules {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&rule.pattern))
+                .subtitle(if rule.include { "Included" } else { "Excluded" })
+                .build();
+            let remove = gtk4::Button::from_icon_name("user-trash-symbolic");
+            remove.set_valign(gtk4::Align::Center);
+            remove.add_css_class("flat");
+            remove.set_tooltip_text(Some("Remove rule"));
+            let pattern = rule.pattern.clone();
+            remove.connect_clicked(clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_| {
+                    dialog.imp().selection.borrow_mut().remove(&pattern);
+                    dialog.refresh();
+                }
+            ));
+            row.add_suffix(&remove);
+            self_.rules.append(&row);
+        }
+        self_.rules.set_visible(!rules.is_empty());
+    }
+}
+
+fn format_size(size: u128) -> String {
+    format!(
+        "{:.2}",
+        byte_unit::Byte::from_u128(size)
+            .unwrap_or_default()
+            .get_appropriate_unit(byte_unit::UnitType::Decimal)
+    )
+}
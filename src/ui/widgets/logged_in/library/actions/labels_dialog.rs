@@ -0,0 +1,288 @@
+use crate::models::collections::{self, Labels};
+use adw::prelude::ActionRowExt;
+use adw::subclass::prelude::AdwWindowImpl;
+use gtk4::glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{self, gio, prelude::*};
+use gtk4::{glib, CompositeTemplate};
+use gtk_macros::action;
+
+pub mod imp {
+    use super::*;
+    use crate::window::EpicAssetManagerWindow;
+    use once_cell::sync::OnceCell;
+    use std::cell::RefCell;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/io/github/achetagames/epic_asset_manager/labels_dialog.ui")]
+    pub struct EpicLabelsDialog {
+        pub asset: RefCell<Option<String>>,
+        pub window: OnceCell<EpicAssetManagerWindow>,
+        pub actions: gio::SimpleActionGroup,
+        #[template_child]
+        pub asset_name: TemplateChild<gtk4::Label>,
+        #[template_child]
+        pub collections: TemplateChild<gtk4::ListBox>,
+        #[template_child]
+        pub collection_name: TemplateChild<gtk4::Entry>,
+        #[template_child]
+        pub tags: TemplateChild<gtk4::ListBox>,
+        #[template_child]
+        pub tag_name: TemplateChild<gtk4::Entry>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EpicLabelsDialog {
+        const NAME: &'static str = "EpicLabelsDialog";
+        type Type = super::EpicLabelsDialog;
+        type ParentType = adw::Window;
+
+        fn new() -> Self {
+            Self {
+                asset: RefCell::new(None),
+                window: OnceCell::new(),
+                actions: gio::SimpleActionGroup::new(),
+                asset_name: TemplateChild::default(),
+                collections: TemplateChild::default(),
+                collection_name: TemplateChild::default(),
+                tags: TemplateChild::default(),
+                tag_name: TemplateChild::default(),
+            }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for EpicLabelsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup_actions();
+        }
+    }
+
+    impl WidgetImpl for EpicLabelsDialog {}
+    impl WindowImpl for EpicLabelsDialog {}
+    impl AdwWindowImpl for EpicLabelsDialog {}
+}
+
+glib::wrapper! {
+    pub struct EpicLabelsDialog(ObjectSubclass<imp::EpicLabelsDialog>)
+        @extends gtk4::Widget, gtk4::Window, adw::Window,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Native, gtk4::Root, gtk4::ShortcutManager;
+}
+
+impl Default for EpicLabelsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Update the library and Fab views after the collections or tags of an asset changed
+pub fn labels_changed(window: &crate::window::EpicAssetManagerWindow, asset: &str) {
+    let l = window.imp().logged_in_stack.clone();
+    let l_ = l.imp();
+    l_.library.refresh_labels();
+    l_.library.refresh_asset(asset);
+    l_.fab.refresh_labels();
+    l_.fab.refresh_fab_asset(asset);
+}
+
+impl EpicLabelsDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Open the editor for an asset of the library or of Fab
+    pub fn open(parent: &impl IsA<gtk4::Widget>, asset: &str, name: &str) -> Self {
+        let dialog = Self::new();
+        let dialog_ = dialog.imp();
+        if let Some(window) = parent
+            .root()
+            .and_then(|r| r.downcast::<crate::window::EpicAssetManagerWindow>().ok())
+        {
+            dialog.set_transient_for(Some(&window));
+            dialog_.window.set(window).unwrap();
+        }
+        dialog_.asset.replace(Some(asset.to_string()));
+        dialog_.asset_name.set_label(name);
+        dialog.refresh();
+        dialog.present();
+        dialog
+    }
+
+    fn setup_actions(&self) {
+        let self_ = self.imp();
+        self.insert_action_group("labels", Some(&self_.actions));
+
+        action!(
+            self_.actions,
+            "add_collection",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    dialog.add_collection();
+                }
+            )
+        );
+        action!(
+            self_.actions,
+            "add_tag",
+            clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                move |_, _| {
+                    dialog.add_tag();
+                }
+            )
+        );
+        self_.collection_name.connect_activate(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |_| {
+                dialog.add_collection();
+            }
+        ));
+        self_.tag_name.connect_activate(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |_| {
+                dialog.add_tag();
+            }
+        ));
+    }
+
+    fn asset(&self) -> Option<String> {
+        self.imp().asset.borrow().clone()
+    }
+
+    fn changed(&self) {
+        let self_ = self.imp();
+        if let (Some(window), Some(asset)) = (self_.window.get(), self.asset()) {
+            labels_changed(window, &asset);
+        }
+    }
+
+    /// Every asset of the collection lost it, not only the one being edited
+    fn collection_deleted(&self) {
+        let self_ = self.imp();
+        if let Some(window) = self_.window.get() {
+            let l = window.imp().logged_in_stack.clone();
+            let l_ = l.imp();
+            l_.library.reload_labels();
+            l_.fab.reload_labels();
+        }
+        self.changed();
+    }
+
+    fn add_collection(&self) {
+        let self_ = self.imp();
+        let Some(asset) = self.asset() else {
+            return;
+        };
+        if let Some(name) = collections::create_collection(&self_.collection_name.text()) {
+            collections::set_member(&asset, &name, true);
+            self_.collection_name.set_text("");
+            self.refresh();
+            self.changed();
+        }
+    }
+
+    fn add_tag(&self) {
+        let self_ = self.imp();
+        let Some(asset) = self.asset() else {
+            return;
+        };
+        if collections::add_tag(&asset, &self_.tag_name.text()).is_some() {
+            self_.tag_name.set_text("");
+            self.refresh();
+            self.changed();
+        }
+    }
+
+    /// List every collection and tag, checked when the asset has it
+    fn refresh(&self) {
+        let self_ = self.imp();
+        let Some(asset) = self.asset() else {
+            return;
+        };
+        let labels = Labels::load(&asset);
+
+        self_.collections.remove_all();
+        let all_collections = collections::collections();
+        for name in &all_collections {
+            let row = self.check_row(name, labels.collections.contains(name), {
+                let asset = asset.clone();
+                let name = name.clone();
+                move |member| collections::set_member(&asset, &name, member)
+            });
+            let delete = gtk4::Button::from_icon_name("user-trash-symbolic");
+            delete.set_valign(gtk4::Align::Center);
+            delete.add_css_class("flat");
+            delete.set_tooltip_text(Some("Delete the collection, its assets are kept"));
+            delete.connect_clicked(clone!(
+                #[weak(rename_to=dialog)]
+                self,
+                #[to_owned]
+                name,
+                move |_| {
+                    collections::delete_collection(&name);
+                    dialog.refresh();
+                    dialog.collection_deleted();
+                }
+            ));
+            row.add_suffix(&delete);
+            self_.collections.append(&row);
+        }
+        self_.collections.set_visible(!all_collections.is_empty());
+
+        self_.tags.remove_all();
+        let all_tags = collections::tags();
+        for tag in &all_tags {
+            let row = self.check_row(tag, labels.tags.contains(tag), {
+                let asset = asset.clone();
+                let tag = tag.clone();
+                move |tagged| {
+                    if tagged {
+                        collections::add_tag(&asset, &tag);
+                    } else {
+                        collections::remove_tag(&asset, &tag);
+                    }
+                }
+            });
+            self_.tags.append(&row);
+        }
+        self_.tags.set_visible(!all_tags.is_empty());
+    }
+
+    fn check_row(
+        &self,
+        title: &str,
+        active: bool,
+        toggled: impl Fn(bool) + 'static,
+    ) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(title))
+            .build();
+        let check = gtk4::CheckButton::new();
+        check.set_valign(gtk4::Align::Center);
+        check.set_active(active);
+        check.connect_toggled(clone!(
+            #[weak(rename_to=dialog)]
+            self,
+            move |check| {
+                toggled(check.is_active());
+                dialog.changed();
+            }
+        ));
+        row.add_prefix(&check);
+        row.set_activatable_widget(Some(&check));
+        row
+    }
+}
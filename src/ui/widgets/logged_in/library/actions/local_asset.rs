@@ -50,9 +50,14 @@ pub mod imp {
         fn signals() -> &'static [glib::subclass::Signal] {
             static SIGNALS: once_cell::sync::Lazy<Vec<glib::subclass::Signal>> =
                 once_cell::sync::Lazy::new(|| {
-                    vec![glib::subclass::Signal::builder("delete")
-                        .flags(glib::SignalFlags::ACTION)
-                        .build()]
+                    vec![
+                        glib::subclass::Signal::builder("delete")
+                            .flags(glib::SignalFlags::ACTION)
+                            .build(),
+                        glib::subclass::Signal::builder("verify")
+                            .flags(glib::SignalFlags::ACTION)
+                            .build(),
+                    ]
                 });
             SIGNALS.as_ref()
         }
@@ -134,6 +139,18 @@ impl EpicLocalAsset {
             )
         );
 
+        action!(
+            self_.actions,
+            "verify",
+            clone!(
+                #[weak(rename_to=local_asset)]
+                self,
+                move |_, _| {
+                    local_asset.verify();
+                }
+            )
+        );
+
         action!(
             self_.actions,
             "delete",
@@ -147,6 +164,10 @@ impl EpicLocalAsset {
         );
     }
 
+    pub fn verify(&self) {
+        self.emit_by_name::<()>("verify", &[]);
+    }
+
     pub fn delete(&self) {
         self.emit_by_name::<()>("delete", &[]);
     }
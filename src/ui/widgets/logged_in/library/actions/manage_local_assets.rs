@@ -1,3 +1,4 @@
+use crate::ui::widgets::download_manager::vault::Vault;
 use gtk4::glib::clone;
 use gtk4::subclass::prelude::*;
 use gtk4::{self, gio, prelude::*};
@@ -151,6 +152,22 @@ impl EpicLocalAssets {
                                     }
                                 ),
                             );
+                            row.connect_local(
+                                "verify",
+                                false,
+                                clone!(
+                                    #[weak(rename_to=mla)]
+                                    self,
+                                    #[weak]
+                                    row,
+                                    #[upgrade_or]
+                                    None,
+                                    move |_| {
+                                        mla.verify(&row);
+                                        None
+                                    }
+                                ),
+                            );
                         }
                     }
                 }
@@ -158,6 +175,27 @@ impl EpicLocalAssets {
         }
     }
 
+    pub fn set_download_manager(
+        &self,
+        dm: &crate::ui::widgets::download_manager::EpicDownloadManager,
+    ) {
+        let self_ = self.imp();
+        // Do not run this twice
+        if self_.download_manager.get().is_some() {
+            return;
+        }
+
+        self_.download_manager.set(dm.clone()).unwrap();
+    }
+
+    pub fn verify(&self, widget: &super::local_asset::EpicLocalAsset) {
+        let self_ = self.imp();
+        let (Some(dm), Some(p)) = (self_.download_manager.get(), widget.path()) else {
+            return;
+        };
+        dm.verify_release(self_.asset.borrow().clone(), PathBuf::from(p));
+    }
+
     pub fn delete(&self, widget: &super::local_asset::EpicLocalAsset) {
         let self_ = self.imp();
         remove_from_list_box(&self_.local_list, widget);
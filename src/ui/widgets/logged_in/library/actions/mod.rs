@@ -3,6 +3,8 @@ pub mod add_to_project_dialog;
 mod create_asset_project;
 pub mod create_project_dialog;
 mod download_detail;
+pub mod file_selection_dialog;
+pub mod labels_dialog;
 mod local_asset;
 mod manage_local_assets;
 
@@ -255,6 +257,7 @@ impl EpicAssetActions {
         self_.download_details.set_download_manager(&dm.clone());
         self_.create_asset_project.set_download_manager(&dm.clone());
         self_.add_to_project.set_download_manager(&dm.clone());
+        self_.local_assets.set_download_manager(&dm.clone());
         self_.download_manager.set(dm.clone()).unwrap();
     }
 
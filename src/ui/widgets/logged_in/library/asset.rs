@@ -1,6 +1,10 @@
+use crate::models::collections::{self, Labels};
+use crate::ui::widgets::logged_in::library::actions::labels_dialog::{
+    labels_changed, EpicLabelsDialog,
+};
 use gtk4::glib::clone;
 use gtk4::subclass::prelude::*;
-use gtk4::{self, prelude::*};
+use gtk4::{self, gio, prelude::*};
 use gtk4::{glib, CompositeTemplate};
 use log::trace;
 
@@ -306,6 +310,91 @@ impl EpicAsset {
             }
         ));
         self.add_controller(gesture);
+
+        let menu_gesture = gtk4::GestureClick::new();
+        menu_gesture.set_button(gtk4::gdk::BUTTON_SECONDARY);
+        menu_gesture.connect_pressed(clone!(
+            #[weak(rename_to=asset)]
+            self,
+            move |_gesture, _, x, y| {
+                asset.show_labels_menu(x, y);
+            }
+        ));
+        self.add_controller(menu_gesture);
+    }
+
+    /// Id and name of the owned asset shown by the tile
+    fn labeled_asset(&self) -> Option<(String, String)> {
+        let self_ = self.imp();
+        if let Some(data) = self_.data.borrow().as_ref() {
+            return Some((data.id(), data.name()));
+        }
+        self_
+            .fab_data
+            .borrow()
+            .as_ref()
+            .map(|data| (data.id(), data.name()))
+    }
+
+    /// Context menu to put the asset into collections and to edit its tags
+    fn show_labels_menu(&self, x: f64, y: f64) {
+        let Some((id, name)) = self.labeled_asset() else {
+            return;
+        };
+        let labels = Labels::load(&id);
+        let actions = gio::SimpleActionGroup::new();
+        let menu = gio::Menu::new();
+        let section = gio::Menu::new();
+        for (i, collection) in collections::collections().into_iter().enumerate() {
+            let action_name = format!("collection-{i}");
+            section.append(Some(&collection), Some(&format!("tile.{action_name}")));
+            let action = gio::SimpleAction::new_stateful(
+                &action_name,
+                None,
+                &labels.collections.contains(&collection).to_variant(),
+            );
+            action.connect_activate(clone!(
+                #[weak(rename_to=asset)]
+                self,
+                #[strong]
+                id,
+                move |action, _| {
+                    let member = !action
+                        .state()
+                        .and_then(|s| s.get::<bool>())
+                        .unwrap_or_default();
+                    action.set_state(&member.to_variant());
+                    collections::set_member(&id, &collection, member);
+                    asset.labels_changed(&id);
+                }
+            ));
+            actions.add_action(&action);
+        }
+        if section.n_items() > 0 {
+            menu.append_section(Some("Collections"), &section);
+        }
+        let edit = gio::SimpleAction::new("edit-labels", None);
+        edit.connect_activate(clone!(
+            #[weak(rename_to=asset)]
+            self,
+            move |_, _| {
+                EpicLabelsDialog::open(&asset, &id, &name);
+            }
+        ));
+        actions.add_action(&edit);
+        menu.append(Some("Collections and Tags…"), Some("tile.edit-labels"));
+        self.insert_action_group("tile", Some(&actions));
+
+        crate::ui::widgets::context_menu::popup_at(self, &menu, x, y);
+    }
+
+    fn labels_changed(&self, id: &str) {
+        if let Some(window) = self
+            .root()
+            .and_then(|r| r.downcast::<crate::window::EpicAssetManagerWindow>().ok())
+        {
+            labels_changed(&window, id);
+        }
     }
 
     fn update_action_label(&self) {
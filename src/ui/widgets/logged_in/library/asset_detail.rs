@@ -1,6 +1,8 @@
 use crate::models::asset_data::AssetType;
+use crate::models::collections::Labels;
 use crate::ui::widgets::download_manager::asset::Asset;
 use crate::ui::widgets::logged_in::fab::version_dialog::EpicFabVersionDialog;
+use crate::ui::widgets::logged_in::library::actions::labels_dialog::EpicLabelsDialog;
 use diesel::dsl::exists;
 use diesel::{select, ExpressionMethods, QueryDsl, RunQueryDsl};
 use egs_api::api::types::asset_info::AssetInfo;
@@ -49,6 +51,8 @@ pub mod imp {
         #[template_child]
         pub favorite: TemplateChild<gtk4::Button>,
         #[template_child]
+        pub labels: TemplateChild<gtk4::Label>,
+        #[template_child]
         pub actions_menu: TemplateChild<gtk4::MenuButton>,
         #[template_child]
         pub warning: TemplateChild<adw::Banner>,
@@ -86,6 +90,7 @@ pub mod imp {
                 actions_box: TemplateChild::default(),
                 title: TemplateChild::default(),
                 favorite: TemplateChild::default(),
+                labels: TemplateChild::default(),
                 actions_menu: TemplateChild::default(),
                 warning: TemplateChild::default(),
                 images: TemplateChild::default(),
@@ -377,6 +382,18 @@ impl EpicAssetDetails {
                 }
             )
         );
+
+        action!(
+            actions,
+            "edit_labels",
+            clone!(
+                #[weak(rename_to=details)]
+                self,
+                move |_, _| {
+                    details.edit_labels();
+                }
+            )
+        );
     }
 
     fn show_download_details(
@@ -640,6 +657,7 @@ impl EpicAssetDetails {
             self.add_info_row(text);
         }
         self.check_favorite();
+        self.update_labels();
     }
 
     pub fn set_fab_asset(&self, fab_asset: &FabAsset) {
@@ -780,6 +798,7 @@ impl EpicAssetDetails {
         }
 
         self.check_fab_favorite(&fab_asset.asset_id);
+        self.update_labels();
     }
 
     pub fn set_fab_listing_detail(
@@ -797,6 +816,7 @@ impl EpicAssetDetails {
 
         self_.asset.replace(None);
         self_.fab_asset.replace(None);
+        self.update_labels();
 
         self_.details_revealer.set_reveal_child(true);
         self_.details_revealer.set_vexpand_set(false);
@@ -1055,6 +1075,53 @@ impl EpicAssetDetails {
         }
     }
 
+    /// Id and title of the shown library or Fab asset
+    fn labeled_asset(&self) -> Option<(String, String)> {
+        let self_ = self.imp();
+        if let Some(asset) = self.asset() {
+            return Some((asset.id, asset.title.unwrap_or_default()));
+        }
+        self_
+            .fab_asset
+            .borrow()
+            .as_ref()
+            .map(|fa| (fa.asset_id.clone(), fa.title.clone()))
+    }
+
+    fn edit_labels(&self) {
+        let Some((id, title)) = self.labeled_asset() else {
+            return;
+        };
+        let dialog = EpicLabelsDialog::open(self, &id, &title);
+        dialog.connect_close_request(clone!(
+            #[weak(rename_to=details)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_| {
+                details.update_labels();
+                glib::Propagation::Proceed
+            }
+        ));
+    }
+
+    /// Show the collections and tags of the asset below the title
+    fn update_labels(&self) {
+        let self_ = self.imp();
+        let asset = self.labeled_asset();
+        get_action!(self_.actions, @edit_labels).set_enabled(asset.is_some());
+        let labels = asset.map(|(id, _)| Labels::load(&id)).unwrap_or_default();
+        let mut parts = Vec::new();
+        if !labels.collections.is_empty() {
+            parts.push(format!("Collections: {}", labels.collections.join(", ")));
+        }
+        if !labels.tags.is_empty() {
+            parts.push(format!("Tags: {}", labels.tags.join(", ")));
+        }
+        self_.labels.set_label(&parts.join("\n"));
+        self_.labels.set_visible(!parts.is_empty());
+    }
+
     pub fn has_asset(&self) -> bool {
         let self_ = self.imp();
         self_.asset.borrow().is_some() || self_.fab_asset.borrow().is_some()
@@ -1,4 +1,5 @@
 use crate::tools::asset_info::Search;
+use crate::tools::category_filter::Expr;
 use crate::ui::widgets::logged_in::refresh::Refresh;
 use asset::EpicAsset;
 use glib::clone;
@@ -6,12 +7,13 @@ use gtk4::{self, prelude::*, CustomSorter};
 use gtk4::{gio, glib, subclass::prelude::*, CompositeTemplate};
 use gtk_macros::action;
 use log::{debug, error, trace, warn};
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::ffi::OsStr;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 mod actions;
 pub mod asset;
@@ -55,6 +57,10 @@ pub mod imp {
         pub count_label: TemplateChild<gtk4::Label>,
         #[template_child]
         pub refresh_progress: TemplateChild<gtk4::ProgressBar>,
+        #[template_child]
+        pub save_search_button: TemplateChild<gtk4::MenuButton>,
+        #[template_child]
+        pub saved_search_name: TemplateChild<gtk4::Entry>,
         pub filter: RefCell<Option<String>>,
         pub search: RefCell<Option<String>>,
         pub actions: gio::SimpleActionGroup,
@@ -66,6 +72,8 @@ pub mod imp {
         pub order_by_ids: RefCell<Vec<String>>,
         pub category_hierarchy: RefCell<BTreeMap<String, BTreeSet<String>>>,
         pub category_filter_paths: RefCell<Vec<String>>,
+        /// Category of a saved search that is selected once the library lists it
+        pub pending_category: RefCell<Option<String>>,
         pub loaded_assets: RefCell<HashMap<String, egs_api::api::types::asset_info::AssetInfo>>,
         pub loaded_data: RefCell<HashMap<String, crate::models::asset_data::AssetData>>,
         pub asset_product_names: RefCell<HashMap<String, String>>,
@@ -73,6 +81,10 @@ pub mod imp {
         pub image_load_pool: ThreadPool,
         pub assets_pending: std::sync::RwLock<Vec<Object>>,
         pub categories: RefCell<HashSet<String>>,
+        /// Full-text index of the loaded assets
+        pub search_index: RefCell<crate::tools::search::Index>,
+        /// Rank of the assets matching the current search, used for ordering
+        pub search_scores: RefCell<Option<Rc<HashMap<String, f32>>>>,
         pub settings: gio::Settings,
         loading: RefCell<u32>,
         loaded: RefCell<u32>,
@@ -99,6 +111,8 @@ pub mod imp {
                 order: TemplateChild::default(),
                 count_label: TemplateChild::default(),
                 refresh_progress: TemplateChild::default(),
+                save_search_button: TemplateChild::default(),
+                saved_search_name: TemplateChild::default(),
                 filter: RefCell::new(None),
                 search: RefCell::new(None),
                 actions: gio::SimpleActionGroup::new(),
@@ -116,6 +130,7 @@ pub mod imp {
                 order_by_ids: RefCell::new(Vec::new()),
                 category_hierarchy: RefCell::new(BTreeMap::new()),
                 category_filter_paths: RefCell::new(vec![String::new()]),
+                pending_category: RefCell::new(None),
                 loaded_assets: RefCell::new(HashMap::new()),
                 loaded_data: RefCell::new(HashMap::new()),
                 asset_product_names: RefCell::new(HashMap::new()),
@@ -123,6 +138,8 @@ pub mod imp {
                 image_load_pool: ThreadPool::with_name("Image Load Pool".to_string(), 5),
                 assets_pending: std::sync::RwLock::new(vec![]),
                 categories: RefCell::new(HashSet::new()),
+                search_index: RefCell::new(crate::tools::search::Index::default()),
+                search_scores: RefCell::new(None),
                 settings: gio::Settings::new(config::APP_ID),
                 loading: RefCell::new(0),
                 loaded: RefCell::new(0),
@@ -643,6 +660,28 @@ impl EpicLibraryBox {
         }
     }
 
+    fn rank_sorter(scores: Rc<HashMap<String, f32>>) -> CustomSorter {
+        gtk4::CustomSorter::new(move |obj1, obj2| {
+            let info1 = obj1
+                .downcast_ref::<crate::models::asset_data::AssetData>()
+                .unwrap();
+            let info2 = obj2
+                .downcast_ref::<crate::models::asset_data::AssetData>()
+                .unwrap();
+            let score1 = scores.get(&info1.id()).copied().unwrap_or_default();
+            let score2 = scores.get(&info2.id()).copied().unwrap_or_default();
+            score2
+                .total_cmp(&score1)
+                .then_with(|| {
+                    info1
+                        .name()
+                        .to_lowercase()
+                        .cmp(&info2.name().to_lowercase())
+                })
+                .into()
+        })
+    }
+
     /// Open asset based on a name from xdg-open
     fn open_asset(&self) {
         let self_ = self.imp();
@@ -694,6 +733,10 @@ impl EpicLibraryBox {
             self_.grid_model.splice(0, 0, vec.as_slice());
             vec.clear();
         }
+        // Search results were computed before these assets were indexed
+        if self_.search_scores.borrow().is_some() {
+            self.apply_filter();
+        }
         self.update_count();
         // Scroll to top if nothing is selected
         let has_asset = self_.details.get().map_or(false, |d| d.has_asset());
@@ -799,6 +842,13 @@ impl EpicLibraryBox {
 
     pub fn order_changed(&self) {
         let self_ = self.imp();
+        // Search results are ordered by how well they match
+        if let Some(scores) = self_.search_scores.borrow().clone() {
+            self_
+                .sorter_model
+                .set_sorter(Some(&Self::rank_sorter(scores)));
+            return;
+        }
         let asc = self_.order.icon_name().map_or(false, |name| {
             matches!(name.as_str(), "view-sort-ascending-symbolic")
         });
@@ -843,9 +893,83 @@ impl EpicLibraryBox {
             )
         );
 
+        action!(
+            self_.actions,
+            "save_search",
+            clone!(
+                #[weak(rename_to=library)]
+                self,
+                move |_, _| {
+                    library.save_search();
+                }
+            )
+        );
+
+        self_.saved_search_name.connect_activate(clone!(
+            #[weak(rename_to=library)]
+            self,
+            move |_| {
+                library.save_search();
+            }
+        ));
+
         self.insert_action_group("library", Some(&self_.actions));
     }
 
+    /// Keep the current filters as a saved search named after the entry of the save popover
+    fn save_search(&self) {
+        let self_ = self.imp();
+        let name = crate::models::collections::clean_name(&self_.saved_search_name.text());
+        if name.is_empty() {
+            return;
+        }
+        let category = self_
+            .category_filter_paths
+            .borrow()
+            .get(self_.category_dropdown.selected() as usize)
+            .cloned()
+            .unwrap_or_default();
+        crate::models::saved_search::SavedSearch {
+            name,
+            search: self.search().unwrap_or_default(),
+            category,
+            filter: self.filter(),
+            downloaded: self_.downloaded_filter.is_active(),
+            favorites: self_.favorites_filter.is_active(),
+        }
+        .save();
+        self_.saved_search_name.set_text("");
+        self_.save_search_button.popdown();
+        if let Some(sidebar) = self_.sidebar.get() {
+            sidebar.refresh_saved_searches();
+        }
+    }
+
+    /// Show the library through the filters of a saved search
+    pub fn apply_saved_search(&self, saved: &crate::models::saved_search::SavedSearch) {
+        let self_ = self.imp();
+        self.set_property("filter", saved.filter.clone());
+        self_.asset_search.set_text(&saved.search);
+        self_.downloaded_filter.set_active(saved.downloaded);
+        self_.favorites_filter.set_active(saved.favorites);
+        let position = self_
+            .category_filter_paths
+            .borrow()
+            .iter()
+            .position(|p| p == &saved.category);
+        match position {
+            Some(position) => {
+                self_.pending_category.replace(None);
+                self_.category_dropdown.set_selected(position as u32);
+            }
+            None => {
+                // Categories appear while the library loads
+                self_.pending_category.replace(Some(saved.category.clone()));
+                self_.category_dropdown.set_selected(0);
+            }
+        }
+    }
+
     fn show_download_details(&self) {
         let self_ = self.imp();
         if let Some(w) = self_.window.get() {
@@ -893,12 +1017,12 @@ impl EpicLibraryBox {
     pub fn apply_filter(&self) {
         let self_ = self.imp();
         let search = self.search();
-        let filter_p = self.filter();
+        let filter_p = self.category_filter_expr();
         let downloaded_only = self_.downloaded_filter.is_active();
         let favorites_only = self_.favorites_filter.is_active();
 
         let selected_cat = self_.category_dropdown.selected();
-        let category_filter: Option<String> =
+        let category_filter: Option<Expr> =
             if selected_cat == 0 || selected_cat == gtk4::INVALID_LIST_POSITION {
                 None
             } else {
@@ -907,11 +1031,21 @@ impl EpicLibraryBox {
                     .borrow()
                     .get(selected_cat as usize)
                     .filter(|p| !p.is_empty())
-                    .cloned()
+                    .map(|p| Expr::Category(p.clone()))
             };
 
+        let scores = search
+            .map(|se| crate::tools::search::Query::parse(&se))
+            .filter(|query| !query.is_empty())
+            .map(|query| Rc::new(self_.search_index.borrow().search(&query)));
+        let ranking_changed = self_.search_scores.borrow().is_some() || scores.is_some();
+        self_.search_scores.replace(scores.clone());
+        if ranking_changed {
+            self.order_changed();
+        }
+
         if filter_p.is_none()
-            && search.is_none()
+            && scores.is_none()
             && !downloaded_only
             && !favorites_only
             && category_filter.is_none()
@@ -925,13 +1059,11 @@ impl EpicLibraryBox {
             let asset = object
                 .downcast_ref::<crate::models::asset_data::AssetData>()
                 .unwrap();
-            let matches_search = search.as_ref().is_none_or(|se| {
-                asset
-                    .name()
-                    .to_ascii_lowercase()
-                    .contains(&se.to_ascii_lowercase())
-            });
-            let matches_category = filter_p.as_ref().is_none_or(|f| asset.check_category(f));
+            let matches_search = scores.as_ref().is_none_or(|s| s.contains_key(&asset.id()));
+            // An invalid sidebar filter matches nothing, the banner says why
+            let matches_category = filter_p
+                .as_ref()
+                .is_none_or(|f| f.as_ref().is_some_and(|f| asset.check_category(f)));
             let matches_downloaded = !downloaded_only || asset.downloaded();
             let matches_favorites = !favorites_only || asset.favorite();
             let matches_dropdown = category_filter
@@ -948,6 +1080,37 @@ impl EpicLibraryBox {
         self.update_count();
     }
 
+    /// Parse the sidebar filter, `Some(None)` when it is invalid, the user is told why
+    fn category_filter_expr(&self) -> Option<Option<Expr>> {
+        let self_ = self.imp();
+        let window = self_.window.get();
+        let Some(filter) = self.filter() else {
+            if let Some(w) = window {
+                w.clear_notification("category-filter");
+            }
+            return None;
+        };
+        match Expr::parse(&filter) {
+            Ok(expr) => {
+                if let Some(w) = window {
+                    w.clear_notification("category-filter");
+                }
+                Some(Some(expr))
+            }
+            Err(e) => {
+                error!("Invalid category filter {}: {}", filter, e);
+                if let Some(w) = window {
+                    w.add_notification(
+                        "category-filter",
+                        &format!("Invalid category filter \"{filter}\": {e}"),
+                        gtk4::MessageType::Error,
+                    );
+                }
+                Some(None)
+            }
+        }
+    }
+
     pub fn add_category_to_dropdown(&self, path: &str) {
         let self_ = self.imp();
         let parts: Vec<&str> = path.splitn(2, '/').collect();
@@ -1007,8 +1170,24 @@ impl EpicLibraryBox {
                 paths.push(format!("{}/{}", top, sub));
             }
         }
+        drop(hierarchy);
+
+        for (label, path) in crate::models::collections::dropdown_entries(
+            &crate::models::collections::collections(),
+            &crate::models::collections::tags(),
+        ) {
+            model.append(&label);
+            paths.push(path);
+        }
 
-        let new_selected = paths.iter().position(|p| p == &prev_path).unwrap_or(0) as u32;
+        let pending = self_.pending_category.borrow().clone();
+        let new_selected = match pending.and_then(|c| paths.iter().position(|p| p == &c)) {
+            Some(position) => {
+                self_.pending_category.replace(None);
+                position
+            }
+            None => paths.iter().position(|p| p == &prev_path).unwrap_or(0),
+        } as u32;
 
         self_.category_filter_paths.replace(paths);
         self_.category_dropdown.set_model(Some(&model));
@@ -1068,6 +1247,10 @@ impl EpicLibraryBox {
                     }
                 }
             } {
+                self_
+                    .search_index
+                    .borrow_mut()
+                    .add(&asset.id, &asset.search_fields());
                 let data = crate::models::asset_data::AssetData::new(asset, image);
                 let mut data_hash = self_.loaded_data.borrow_mut();
                 // IMPORTANT: Insert the SAME object into both loaded_data and grid_model
@@ -1362,6 +1545,22 @@ impl EpicLibraryBox {
         }
     }
 
+    /// Show new or removed collections and tags in the category dropdown and the sidebar
+    pub fn refresh_labels(&self) {
+        self.rebuild_category_dropdown();
+        if let Some(sidebar) = self.imp().sidebar.get() {
+            sidebar.refresh_labels();
+        }
+    }
+
+    /// Load the collections and tags of every asset again, e.g. after a collection was deleted
+    pub fn reload_labels(&self) {
+        for data in self.imp().loaded_data.borrow().values() {
+            data.check_labels();
+        }
+        self.apply_filter();
+    }
+
     pub fn refresh_asset(&self, id: &str) {
         let self_ = self.imp();
         if let Some(data) = self_.loaded_data.borrow().get(id) {
@@ -20,6 +20,8 @@ pub mod imp {
         pub icon_name: RefCell<Option<String>>,
         pub filter: RefCell<Option<String>>,
         pub path: RefCell<Option<String>>,
+        /// Name of the saved search the button opens
+        pub saved_search: RefCell<Option<String>>,
         pub sidebar: OnceCell<crate::ui::widgets::logged_in::library::sidebar::EpicSidebar>,
         pub expanded: RefCell<bool>,
         pub actions: gio::SimpleActionGroup,
@@ -40,6 +42,7 @@ pub mod imp {
                 icon_name: RefCell::new(None),
                 filter: RefCell::new(None),
                 path: RefCell::new(None),
+                saved_search: RefCell::new(None),
                 sidebar: OnceCell::new(),
                 expanded: RefCell::new(false),
                 actions: gio::SimpleActionGroup::new(),
@@ -166,11 +169,61 @@ impl EpicSidebarButton {
         );
     }
 
+    /// Make the button open a saved search, with a menu to delete it
+    pub fn set_saved_search(&self, name: &str) {
+        let self_ = self.imp();
+        self_.saved_search.replace(Some(name.to_string()));
+
+        let delete = gio::SimpleAction::new("delete", None);
+        delete.connect_activate(clone!(
+            #[weak(rename_to=button)]
+            self,
+            move |_, _| {
+                button.delete_saved_search();
+            }
+        ));
+        self_.actions.add_action(&delete);
+
+        let menu_gesture = gtk4::GestureClick::new();
+        menu_gesture.set_button(gtk4::gdk::BUTTON_SECONDARY);
+        menu_gesture.connect_pressed(clone!(
+            #[weak(rename_to=button)]
+            self,
+            move |_gesture, _, x, y| {
+                button.show_saved_search_menu(x, y);
+            }
+        ));
+        self.add_controller(menu_gesture);
+    }
+
+    pub fn saved_search(&self) -> Option<String> {
+        self.imp().saved_search.borrow().clone()
+    }
+
+    fn show_saved_search_menu(&self, x: f64, y: f64) {
+        let menu = gio::Menu::new();
+        menu.append(Some("Delete"), Some("sidebar_button.delete"));
+        crate::ui::widgets::context_menu::popup_at(self, &menu, x, y);
+    }
+
+    fn delete_saved_search(&self) {
+        let self_ = self.imp();
+        if let Some(name) = self.saved_search() {
+            crate::models::saved_search::SavedSearch::delete(&name);
+            if let Some(s) = self_.sidebar.get() {
+                s.refresh_saved_searches();
+            }
+        }
+    }
+
     pub fn clicked(&self) {
         let self_ = self.imp();
 
         if let Some(s) = self_.sidebar.get() {
-            s.set_filter(self.filter(), self.path());
+            match self.saved_search() {
+                Some(name) => s.open_saved_search(&name),
+                None => s.set_filter(self.filter(), self.path()),
+            }
             s.activate_all_buttons();
         }
 
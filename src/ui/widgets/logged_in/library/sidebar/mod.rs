@@ -1,3 +1,5 @@
+use crate::models::collections;
+use crate::models::saved_search::{SavedSearch, DEFAULT_CATEGORY_PREFIX};
 use gtk4::glib::clone;
 use gtk4::subclass::prelude::*;
 use gtk4::{self, gio, prelude::*};
@@ -33,6 +35,14 @@ pub mod imp {
         pub fab_category: TemplateChild<button::EpicSidebarButton>,
         #[template_child]
         pub games_category: TemplateChild<button::EpicSidebarButton>,
+        #[template_child]
+        pub history_category: TemplateChild<button::EpicSidebarButton>,
+        #[template_child]
+        pub updates_category: TemplateChild<button::EpicSidebarButton>,
+        #[template_child]
+        pub saved_searches: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub labels: TemplateChild<gtk4::Box>,
     }
 
     #[glib::object_subclass]
@@ -52,6 +62,10 @@ pub mod imp {
                 library_category: TemplateChild::default(),
                 fab_category: TemplateChild::default(),
                 games_category: TemplateChild::default(),
+                history_category: TemplateChild::default(),
+                updates_category: TemplateChild::default(),
+                saved_searches: TemplateChild::default(),
+                labels: TemplateChild::default(),
                 settings: gio::Settings::new(crate::config::APP_ID),
             }
         }
@@ -75,6 +89,8 @@ pub mod imp {
             self.library_category.set_sidebar(&obj);
             self.fab_category.set_sidebar(&obj);
             self.games_category.set_sidebar(&obj);
+            self.history_category.set_sidebar(&obj);
+            self.updates_category.set_sidebar(&obj);
         }
     }
 
@@ -114,7 +130,20 @@ impl EpicSidebar {
         }
 
         self_.loggedin.set(loggedin.clone()).unwrap();
-        match self_.settings.string("default-category").as_str() {
+        self.refresh_saved_searches();
+        self.refresh_labels();
+        let default = self_.settings.string("default-category");
+        if let Some(name) = default.strip_prefix(DEFAULT_CATEGORY_PREFIX) {
+            if let Some(button) = self
+                .saved_search_buttons()
+                .into_iter()
+                .find(|b| b.saved_search().as_deref() == Some(name))
+            {
+                button.clicked();
+                return;
+            }
+        }
+        match default.as_str() {
             "engines" => &self_.engines_category,
             "projects" => &self_.projects_category,
             "fab" => &self_.fab_category,
@@ -124,6 +153,92 @@ impl EpicSidebar {
         .clicked();
     }
 
+    /// Show a button for every saved search
+    pub fn refresh_saved_searches(&self) {
+        let self_ = self.imp();
+        while let Some(child) = self_.saved_searches.first_child() {
+            self_.saved_searches.remove(&child);
+        }
+        for saved in SavedSearch::all() {
+            let button: button::EpicSidebarButton = glib::Object::builder()
+                .property("icon-name", "folder-saved-search-symbolic")
+                .property("path", "library")
+                .property(
+                    "tooltip-text",
+                    format!("{}\n{}", saved.name, saved.summary()),
+                )
+                .build();
+            button.set_sidebar(self);
+            button.set_saved_search(&saved.name);
+            button.activate(true);
+            self_.saved_searches.append(&button);
+        }
+    }
+
+    /// Show a button filtering the library for every collection and tag
+    pub fn refresh_labels(&self) {
+        let self_ = self.imp();
+        while let Some(child) = self_.labels.first_child() {
+            self_.labels.remove(&child);
+        }
+        let collections = collections::collections().into_iter().map(|name| {
+            (
+                "bookmark-new-symbolic",
+                collections::collection_filter(&name),
+                "Collection",
+                name,
+            )
+        });
+        let tags = collections::tags().into_iter().map(|tag| {
+            (
+                "text-x-generic-symbolic",
+                collections::tag_filter(&tag),
+                "Tag",
+                tag,
+            )
+        });
+        for (icon, filter, kind, name) in collections.chain(tags) {
+            let button: button::EpicSidebarButton = glib::Object::builder()
+                .property("icon-name", icon)
+                .property("path", "library")
+                .property("filter", filter)
+                .property("tooltip-text", format!("{kind}\n{name}"))
+                .build();
+            button.set_sidebar(self);
+            button.activate(true);
+            self_.labels.append(&button);
+        }
+    }
+
+    fn saved_search_buttons(&self) -> Vec<button::EpicSidebarButton> {
+        Self::buttons(&self.imp().saved_searches)
+    }
+
+    fn buttons(container: &gtk4::Box) -> Vec<button::EpicSidebarButton> {
+        let mut buttons = Vec::new();
+        let mut child = container.first_child();
+        while let Some(c) = child {
+            child = c.next_sibling();
+            if let Ok(button) = c.downcast::<button::EpicSidebarButton>() {
+                buttons.push(button);
+            }
+        }
+        buttons
+    }
+
+    /// Show the library filtered by a saved search
+    pub fn open_saved_search(&self, name: &str) {
+        let self_ = self.imp();
+        let Some(saved) = SavedSearch::load(name) else {
+            self.refresh_saved_searches();
+            return;
+        };
+        self.switch_main_page("library");
+        if let Some(l) = self_.loggedin.get() {
+            l.apply_saved_search(&saved);
+        }
+    }
+
     pub fn set_page_stack(&self, stack: &gtk4::Stack) {
         let self_ = self.imp();
         if self_.page_stack.get().is_some() {
@@ -213,6 +328,15 @@ impl EpicSidebar {
         self_.library_category.activate(true);
         self_.fab_category.activate(true);
         self_.games_category.activate(true);
+        self_.history_category.activate(true);
+        self_.updates_category.activate(true);
+        for button in self
+            .saved_search_buttons()
+            .into_iter()
+            .chain(Self::buttons(&self_.labels))
+        {
+            button.activate(true);
+        }
     }
 }
 
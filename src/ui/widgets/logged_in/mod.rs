@@ -6,12 +6,14 @@ use gtk4::{glib, CompositeTemplate};
 pub mod engines;
 pub mod fab;
 pub mod games;
+pub mod history;
 pub mod library;
 mod log_line;
 pub mod logs;
 mod plugins;
 mod projects;
 pub mod refresh;
+pub mod updates;
 
 pub mod imp {
     use gtk4::glib::{ParamSpec, ParamSpecString};
@@ -39,6 +41,10 @@ pub mod imp {
         #[template_child]
         pub fab: TemplateChild<crate::ui::widgets::logged_in::fab::FabLibraryBox>,
         #[template_child]
+        pub history: TemplateChild<crate::ui::widgets::logged_in::history::EpicHistory>,
+        #[template_child]
+        pub updates: TemplateChild<crate::ui::widgets::logged_in::updates::EpicUpdates>,
+        #[template_child]
         pub details:
             TemplateChild<crate::ui::widgets::logged_in::library::asset_detail::EpicAssetDetails>,
         pub settings: gtk4::gio::Settings,
@@ -61,6 +67,8 @@ pub mod imp {
                 projects: TemplateChild::default(),
                 games: TemplateChild::default(),
                 fab: TemplateChild::default(),
+                history: TemplateChild::default(),
+                updates: TemplateChild::default(),
                 details: TemplateChild::default(),
                 settings: gtk4::gio::Settings::new(crate::config::APP_ID),
             }
@@ -172,6 +180,7 @@ impl EpicLoggedInBox {
         self_.library.set_download_manager(dm);
         self_.engines.set_download_manager(dm);
         self_.fab.set_download_manager(dm);
+        self_.updates.set_download_manager(dm);
     }
 
     pub fn details(&self) -> &library::asset_detail::EpicAssetDetails {
@@ -0,0 +1,212 @@
+use crate::ui::widgets::download_manager::updates::{AssetUpdate, Location, Updates};
+use adw::prelude::ActionRowExt;
+use gtk4::glib::clone;
+use gtk4::{self, gio, glib, prelude::*, subclass::prelude::*, CompositeTemplate};
+use gtk_macros::action;
+
+pub mod imp {
+    use super::*;
+    use crate::ui::widgets::download_manager::EpicDownloadManager;
+    use once_cell::sync::OnceCell;
+    use std::cell::RefCell;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/io/github/achetagames/epic_asset_manager/updates.ui")]
+    pub struct EpicUpdates {
+        pub actions: gio::SimpleActionGroup,
+        pub download_manager: OnceCell<EpicDownloadManager>,
+        pub updates: RefCell<Vec<AssetUpdate>>,
+        #[template_child]
+        pub summary: TemplateChild<gtk4::Label>,
+        #[template_child]
+        pub update_all_button: TemplateChild<gtk4::Button>,
+        #[template_child]
+        pub stack: TemplateChild<gtk4::Stack>,
+        #[template_child]
+        pub entries: TemplateChild<gtk4::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EpicUpdates {
+        const NAME: &'static str = "EpicUpdates";
+        type Type = super::EpicUpdates;
+        type ParentType = gtk4::Box;
+
+        fn new() -> Self {
+            Self {
+                actions: gio::SimpleActionGroup::new(),
+                download_manager: OnceCell::new(),
+                updates: RefCell::new(Vec::new()),
+                summary: TemplateChild::default(),
+                update_all_button: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                entries: TemplateChild::default(),
+            }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for EpicUpdates {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.setup_actions();
+            // Look again every time the page is opened
+            obj.connect_map(|updates| updates.refresh());
+        }
+    }
+
+    impl WidgetImpl for EpicUpdates {}
+    impl BoxImpl for EpicUpdates {}
+}
+
+glib::wrapper! {
+    pub struct EpicUpdates(ObjectSubclass<imp::EpicUpdates>)
+        @extends gtk4::Widget, gtk4::Box,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Orientable;
+}
+
+impl Default for EpicUpdates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpicUpdates {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn set_download_manager(
+        &self,
+        dm: &crate::ui::widgets::download_manager::EpicDownloadManager,
+    ) {
+        let self_ = self.imp();
+        if self_.download_manager.get().is_none() {
+            self_.download_manager.set(dm.clone()).unwrap();
+        }
+    }
+
+    fn setup_actions(&self) {
+        let self_ = self.imp();
+        self.insert_action_group("updates", Some(&self_.actions));
+
+        action!(
+            self_.actions,
+            "refresh",
+            clone!(
+                #[weak(rename_to=updates)]
+                self,
+                move |_, _| {
+                    updates.refresh();
+                }
+            )
+        );
+        action!(
+            self_.actions,
+            "update_all",
+            clone!(
+                #[weak(rename_to=updates)]
+                self,
+                move |_, _| {
+                    let all = updates.imp().updates.borrow().clone();
+                    updates.update(all);
+                }
+            )
+        );
+    }
+
+    /// Look for newer releases of the local assets
+    pub fn refresh(&self) {
+        let self_ = self.imp();
+        let Some(dm) = self_.download_manager.get() else {
+            return;
+        };
+        self_.stack.set_visible_child_name("scanning");
+        self_.summary.set_label("");
+        self_.update_all_button.set_sensitive(false);
+        let (sender, receiver) = async_channel::bounded(1);
+        dm.scan_updates(sender);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=updates)]
+            self,
+            async move {
+                if let Ok(found) = receiver.recv().await {
+                    updates.imp().updates.replace(found);
+                    updates.show_updates();
+                }
+            }
+        ));
+    }
+
+    fn show_updates(&self) {
+        let self_ = self.imp();
+        let Some(dm) = self_.download_manager.get() else {
+            return;
+        };
+        let updates = self_.updates.borrow().clone();
+        self_.entries.remove_all();
+        let mut pending = 0;
+        for update in &updates {
+            let location = match &update.location {
+                Location::Vault(path) => format!("Vault {}", path.display()),
+                Location::Project(path) => format!("Project {}", path.display()),
+            };
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&update.title()))
+                .subtitle(glib::markup_escape_text(&format!(
+                    "{} → {} · {}",
+                    update.installed,
+                    update.latest_title(),
+                    location
+                )))
+                .build();
+            if dm.is_updating(&update.latest) {
+                let label = gtk4::Label::new(Some("Downloading"));
+                label.add_css_class("dim-label");
+                row.add_suffix(&label);
+            } else {
+                pending += 1;
+                let button = gtk4::Button::with_label("Update");
+                button.set_valign(gtk4::Align::Center);
+                button.connect_clicked(clone!(
+                    #[weak(rename_to=updates)]
+                    self,
+                    #[to_owned]
+                    update,
+                    move |_| {
+                        updates.update(vec![update.clone()]);
+                    }
+                ));
+                row.add_suffix(&button);
+            }
+            self_.entries.append(&row);
+        }
+        self_.summary.set_label(&match updates.len() {
+            0 => String::new(),
+            1 => "1 update available".to_string(),
+            n => format!("{n} updates available"),
+        });
+        self_.update_all_button.set_sensitive(pending > 0);
+        self_.stack.set_visible_child_name(if updates.is_empty() {
+            "empty"
+        } else {
+            "entries"
+        });
+    }
+
+    fn update(&self, updates: Vec<AssetUpdate>) {
+        let self_ = self.imp();
+        if let Some(dm) = self_.download_manager.get() {
+            dm.update_assets(updates);
+        }
+        self.show_updates();
+    }
+}
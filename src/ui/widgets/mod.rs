@@ -1,4 +1,5 @@
 pub mod button_cust;
+pub mod context_menu;
 pub mod download_manager;
 pub mod logged_in;
 pub mod preferences;
@@ -1,5 +1,7 @@
 pub mod dir_row;
+pub mod vault_cleanup;
 
+use crate::ui::widgets::download_manager::vault::Vault;
 use adw::prelude::PreferencesDialogExt;
 use gtk4::gio::{File, FileQueryInfoFlags, FileType, SettingsBindFlags};
 use gtk4::glib::clone;
@@ -58,6 +60,38 @@ pub mod imp {
         pub default_category_selection: TemplateChild<gtk4::DropDown>,
         #[template_child]
         pub accent_color_selection: TemplateChild<gtk4::DropDown>,
+        #[template_child]
+        pub download_speed_limit: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub download_schedule_switch: TemplateChild<gtk4::Switch>,
+        #[template_child]
+        pub download_schedule_start: TemplateChild<gtk4::Entry>,
+        #[template_child]
+        pub download_schedule_end: TemplateChild<gtk4::Entry>,
+        #[template_child]
+        pub download_schedule_limit: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub download_threads_auto_switch: TemplateChild<gtk4::Switch>,
+        #[template_child]
+        pub download_threads: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub image_threads: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub file_threads: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub chunk_store_size: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub min_free_space: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub low_space_refuse_switch: TemplateChild<gtk4::Switch>,
+        #[template_child]
+        pub download_notifications_switch: TemplateChild<gtk4::Switch>,
+        #[template_child]
+        pub pause_on_metered_switch: TemplateChild<gtk4::Switch>,
+        #[template_child]
+        pub download_retry_limit: TemplateChild<gtk4::SpinButton>,
+        #[template_child]
+        pub copy_strategy_selection: TemplateChild<gtk4::DropDown>,
     }
 
     #[glib::object_subclass]
@@ -88,6 +122,22 @@ pub mod imp {
                 log_level_selection: TemplateChild::default(),
                 default_category_selection: TemplateChild::default(),
                 accent_color_selection: TemplateChild::default(),
+                download_speed_limit: TemplateChild::default(),
+                download_schedule_switch: TemplateChild::default(),
+                download_schedule_start: TemplateChild::default(),
+                download_schedule_end: TemplateChild::default(),
+                download_schedule_limit: TemplateChild::default(),
+                download_threads_auto_switch: TemplateChild::default(),
+                download_threads: TemplateChild::default(),
+                image_threads: TemplateChild::default(),
+                file_threads: TemplateChild::default(),
+                chunk_store_size: TemplateChild::default(),
+                min_free_space: TemplateChild::default(),
+                low_space_refuse_switch: TemplateChild::default(),
+                download_notifications_switch: TemplateChild::default(),
+                pause_on_metered_switch: TemplateChild::default(),
+                download_retry_limit: TemplateChild::default(),
+                copy_strategy_selection: TemplateChild::default(),
             }
         }
 
@@ -161,49 +211,71 @@ impl PreferencesWindow {
         ("red", "Red"),
         ("teal", "Teal"),
     ];
+    const COPY_STRATEGY_OPTIONS: [(&'static str, &'static str); 3] = [
+        ("copy", "Copy"),
+        ("reflink", "Reflink"),
+        ("hardlink", "Hardlink"),
+    ];
 
     pub fn new() -> Self {
         glib::Object::new()
     }
 
+    /// Fixed categories followed by the saved searches
+    fn default_category_options() -> Vec<(String, String)> {
+        Self::DEFAULT_CATEGORY_OPTIONS
+            .iter()
+            .map(|(id, label)| ((*id).to_string(), (*label).to_string()))
+            .chain(
+                crate::models::saved_search::SavedSearch::all()
+                    .into_iter()
+                    .map(|saved| (saved.default_category(), saved.name)),
+            )
+            .collect()
+    }
+
     fn setup_dropdowns(&self) {
         let self_ = self.imp();
         Self::set_dropdown_items(&self_.log_level_selection, &Self::LOG_LEVEL_OPTIONS);
-        Self::set_dropdown_items(
-            &self_.default_category_selection,
-            &Self::DEFAULT_CATEGORY_OPTIONS,
-        );
+        let categories = Self::default_category_options();
+        Self::set_dropdown_items(&self_.default_category_selection, &categories);
         Self::set_dropdown_items(&self_.accent_color_selection, &Self::ACCENT_COLOR_OPTIONS);
+        Self::set_dropdown_items(&self_.copy_strategy_selection, &Self::COPY_STRATEGY_OPTIONS);
     }
 
-    fn set_dropdown_items(dropdown: &gtk4::DropDown, items: &[(&str, &str)]) {
-        let labels: Vec<&str> = items.iter().map(|(_, label)| *label).collect();
+    /// Options are `(id, label)` pairs, either static ones or built at runtime
+    fn set_dropdown_items(dropdown: &gtk4::DropDown, items: &[(impl AsRef<str>, impl AsRef<str>)]) {
+        let labels: Vec<&str> = items.iter().map(|(_, label)| label.as_ref()).collect();
         let model = StringList::new(&labels);
         dropdown.set_model(Some(&model));
     }
 
     fn dropdown_selected_id(
         dropdown: &gtk4::DropDown,
-        items: &[(&str, &str)],
+        items: &[(impl AsRef<str>, impl AsRef<str>)],
         fallback: &str,
     ) -> String {
         let selected = dropdown.selected() as usize;
         items
             .get(selected)
-            .map(|(id, _)| (*id).to_string())
+            .map(|(id, _)| id.as_ref().to_string())
             .unwrap_or_else(|| fallback.to_string())
     }
 
     fn dropdown_set_selected_id(
         dropdown: &gtk4::DropDown,
-        items: &[(&str, &str)],
+        items: &[(impl AsRef<str>, impl AsRef<str>)],
         id: &str,
         fallback: &str,
     ) {
         let index = items
             .iter()
-            .position(|(item_id, _)| *item_id == id)
-            .or_else(|| items.iter().position(|(item_id, _)| *item_id == fallback))
+            .position(|(item_id, _)| item_id.as_ref() == id)
+            .or_else(|| {
+                items
+                    .iter()
+                    .position(|(item_id, _)| item_id.as_ref() == fallback)
+            })
             .unwrap_or(0);
         dropdown.set_selected(index as u32);
     }
@@ -258,6 +330,107 @@ impl PreferencesWindow {
             .flags(SettingsBindFlags::DEFAULT)
             .build();
 
+        self_
+            .settings
+            .bind(
+                "download-speed-limit",
+                &*self_.download_speed_limit,
+                "value",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-schedule-enabled",
+                &*self_.download_schedule_switch,
+                "active",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-schedule-start",
+                &*self_.download_schedule_start,
+                "text",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-schedule-end",
+                &*self_.download_schedule_end,
+                "text",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-schedule-limit",
+                &*self_.download_schedule_limit,
+                "value",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-threads-auto",
+                &*self_.download_threads_auto_switch,
+                "active",
+            )
+            .build();
+        self_
+            .settings
+            .bind("download-threads", &*self_.download_threads, "value")
+            .build();
+        self_
+            .settings
+            .bind("image-threads", &*self_.image_threads, "value")
+            .build();
+        self_
+            .settings
+            .bind("file-threads", &*self_.file_threads, "value")
+            .build();
+        self_
+            .settings
+            .bind("chunk-store-size", &*self_.chunk_store_size, "value")
+            .build();
+        self_
+            .settings
+            .bind("min-free-space", &*self_.min_free_space, "value")
+            .build();
+        self_
+            .settings
+            .bind(
+                "low-space-refuse",
+                &*self_.low_space_refuse_switch,
+                "active",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-notifications",
+                &*self_.download_notifications_switch,
+                "active",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "pause-on-metered",
+                &*self_.pause_on_metered_switch,
+                "active",
+            )
+            .build();
+        self_
+            .settings
+            .bind(
+                "download-retry-limit",
+                &*self_.download_retry_limit,
+                "value",
+            )
+            .build();
+
         self_.github_user.connect_changed(clone!(
             #[weak(rename_to=preferences)]
             self,
@@ -291,6 +464,16 @@ impl PreferencesWindow {
                 preferences.accent_color_changed();
             }
         ));
+
+        self_
+            .copy_strategy_selection
+            .connect_selected_notify(clone!(
+                #[weak(rename_to=preferences)]
+                self,
+                move |_| {
+                    preferences.copy_strategy_changed();
+                }
+            ));
     }
 
     fn log_level_changed(&self) {
@@ -317,11 +500,9 @@ impl PreferencesWindow {
 
     fn default_category_changed(&self) {
         let self_ = self.imp();
-        let selected = Self::dropdown_selected_id(
-            &self_.default_category_selection,
-            &Self::DEFAULT_CATEGORY_OPTIONS,
-            "library",
-        );
+        let categories = Self::default_category_options();
+        let selected =
+            Self::dropdown_selected_id(&self_.default_category_selection, &categories, "library");
         self_
             .settings
             .set_string("default-category", &selected)
@@ -339,6 +520,19 @@ impl PreferencesWindow {
         Self::apply_accent_color(&color);
     }
 
+    fn copy_strategy_changed(&self) {
+        let self_ = self.imp();
+        let strategy = Self::dropdown_selected_id(
+            &self_.copy_strategy_selection,
+            &Self::COPY_STRATEGY_OPTIONS,
+            "copy",
+        );
+        self_
+            .settings
+            .set_string("copy-strategy", &strategy)
+            .unwrap();
+    }
+
     pub fn apply_accent_color(color: &str) {
         let css = match color {
             "olive" => {
@@ -445,9 +639,10 @@ impl PreferencesWindow {
         );
         self.log_level_changed();
         let category = self_.settings.string("default-category");
+        let categories = Self::default_category_options();
         Self::dropdown_set_selected_id(
             &self_.default_category_selection,
-            &Self::DEFAULT_CATEGORY_OPTIONS,
+            &categories,
             category.as_str(),
             "library",
         );
@@ -460,6 +655,13 @@ impl PreferencesWindow {
             "default",
         );
         Self::apply_accent_color(&accent);
+
+        Self::dropdown_set_selected_id(
+            &self_.copy_strategy_selection,
+            &Self::COPY_STRATEGY_OPTIONS,
+            self_.settings.string("copy-strategy").as_str(),
+            "copy",
+        );
     }
 
     fn load_secrets(&self) {
@@ -637,6 +839,38 @@ impl PreferencesWindow {
                 }
             )
         );
+        action!(
+            actions,
+            "verify_vaults",
+            clone!(
+                #[weak(rename_to=preferences)]
+                self,
+                move |_, _| {
+                    let self_ = preferences.imp();
+                    if let Some(w) = self_.window.get() {
+                        w.imp().download_manager.verify_all_vaults();
+                    }
+                }
+            )
+        );
+        action!(
+            actions,
+            "cleanup_vaults",
+            clone!(
+                #[weak(rename_to=preferences)]
+                self,
+                move |_, _| {
+                    let self_ = preferences.imp();
+                    if let Some(w) = self_.window.get() {
+                        let dialog = vault_cleanup::EpicVaultCleanup::new();
+                        dialog.set_transient_for(Some(w));
+                        dialog.set_download_manager(&w.imp().download_manager);
+                        dialog.present();
+                        dialog.scan();
+                    }
+                }
+            )
+        );
         action!(
             actions,
             "add_engine",
@@ -0,0 +1,245 @@
+use crate::tools::vault_cleanup::{Candidate, Category};
+use crate::ui::widgets::download_manager::vault::Vault;
+use adw::prelude::{ActionRowExt, ExpanderRowExt};
+use adw::subclass::prelude::AdwWindowImpl;
+use gtk4::glib::clone;
+use gtk4::subclass::prelude::*;
+use gtk4::{self, gio, prelude::*};
+use gtk4::{glib, CompositeTemplate};
+use gtk_macros::action;
+
+/// Entries shown per group, the rest is only counted
+const MAX_LISTED: usize = 100;
+
+pub mod imp {
+    use super::*;
+    use crate::ui::widgets::download_manager::EpicDownloadManager;
+    use once_cell::sync::OnceCell;
+    use std::cell::RefCell;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/io/github/achetagames/epic_asset_manager/vault_cleanup.ui")]
+    pub struct EpicVaultCleanup {
+        pub download_manager: OnceCell<EpicDownloadManager>,
+        pub candidates: RefCell<Vec<Candidate>>,
+        pub selected: RefCell<Vec<Category>>,
+        pub actions: gio::SimpleActionGroup,
+        #[template_child]
+        pub stack: TemplateChild<gtk4::Stack>,
+        #[template_child]
+        pub summary: TemplateChild<gtk4::Label>,
+        #[template_child]
+        pub groups: TemplateChild<gtk4::ListBox>,
+        #[template_child]
+        pub delete_button: TemplateChild<gtk4::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EpicVaultCleanup {
+        const NAME: &'static str = "EpicVaultCleanup";
+        type Type = super::EpicVaultCleanup;
+        type ParentType = adw::Window;
+
+        fn new() -> Self {
+            Self {
+                download_manager: OnceCell::new(),
+                candidates: RefCell::new(Vec::new()),
+                // Deleting assets is left to the user, leftovers are safe to remove
+                selected: RefCell::new(vec![Category::Temporary, Category::Empty]),
+                actions: gio::SimpleActionGroup::new(),
+                stack: TemplateChild::default(),
+                summary: TemplateChild::default(),
+                groups: TemplateChild::default(),
+                delete_button: TemplateChild::default(),
+            }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for EpicVaultCleanup {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup_actions();
+        }
+    }
+
+    impl WidgetImpl for EpicVaultCleanup {}
+    impl WindowImpl for EpicVaultCleanup {}
+    impl AdwWindowImpl for EpicVaultCleanup {}
+}
+
+glib::wrapper! {
+    pub struct EpicVaultCleanup(ObjectSubclass<imp::EpicVaultCleanup>)
+        @extends gtk4::Widget, gtk4::Window, adw::Window,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Native, gtk4::Root, gtk4::ShortcutManager;
+}
+
+impl Default for EpicVaultCleanup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpicVaultCleanup {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn set_download_manager(
+        &self,
+        dm: &crate::ui::widgets::download_manager::EpicDownloadManager,
+    ) {
+        let self_ = self.imp();
+        if self_.download_manager.get().is_none() {
+            self_.download_manager.set(dm.clone()).unwrap();
+        }
+    }
+
+    fn setup_actions(&self) {
+        let self_ = self.imp();
+        self.insert_action_group("vault_cleanup", Some(&self_.actions));
+
+        action!(
+            self_.actions,
+            "delete",
+            clone!(
+                #[weak(rename_to=cleanup)]
+                self,
+                move |_, _| {
+                    cleanup.delete_selected();
+                }
+            )
+        );
+    }
+
+    /// Scan the vaults and show what can be removed
+    pub fn scan(&self) {
+        let self_ = self.imp();
+        let Some(dm) = self_.download_manager.get() else {
+            return;
+        };
+        self_.stack.set_visible_child_name("scanning");
+        let (sender, receiver) = async_channel::bounded(1);
+        dm.scan_vault_cleanup(sender);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=cleanup)]
+            self,
+            async move {
+                if let Ok(candidates) = receiver.recv().await {
+                    cleanup.show_candidates(candidates);
+                }
+            }
+        ));
+    }
+
+    fn show_candidates(&self, candidates: Vec<Candidate>) {
+        let self_ = self.imp();
+        self_.groups.remove_all();
+        for category in Category::ALL {
+            let entries: Vec<&Candidate> = candidates
+                .iter()
+                .filter(|c| c.category == category)
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            let size: u64 = entries.iter().map(|c| c.size).sum();
+            let group = adw::ExpanderRow::builder()
+                .title(category.title())
+                .subtitle(format!("{} entries, {}", entries.len(), format_size(size)))
+                .build();
+            let check = gtk4::CheckButton::new();
+            check.set_valign(gtk4::Align::Center);
+            check.set_active(self_.selected.borrow().contains(&category));
+            check.connect_toggled(clone!(
+                #[weak(rename_to=cleanup)]
+                self,
+                move |check| {
+                    let mut selected = cleanup.imp().selected.borrow_mut();
+                    selected.retain(|c| *c != category);
+                    if check.is_active() {
+                        selected.push(category);
+                    }
+                    drop(selected);
+                    cleanup.update_summary();
+                }
+            ));
+            group.add_prefix(&check);
+            for entry in entries.iter().take(MAX_LISTED) {
+                let row = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(&entry.label))
+                    .subtitle(glib::markup_escape_text(&entry.path.display().to_string()))
+                    .build();
+                row.add_suffix(&gtk4::Label::new(Some(&format_size(entry.size))));
+                group.add_row(&row);
+            }
+            if entries.len() > MAX_LISTED {
+                group.add_row(
+                    &adw::ActionRow::builder()
+                        .title(format!("and {} more", entries.len() - MAX_LISTED))
+                        .build(),
+                );
+            }
+            self_.groups.append(&group);
+        }
+        self_
+            .stack
+            .set_visible_child_name(if candidates.is_empty() {
+                "clean"
+            } else {
+                "results"
+            });
+        self_.candidates.replace(candidates);
+        self.update_summary();
+    }
+
+    fn selected_candidates(&self) -> Vec<Candidate> {
+        let self_ = self.imp();
+        let selected = self_.selected.borrow();
+        self_
+            .candidates
+            .borrow()
+            .iter()
+            .filter(|c| selected.contains(&c.category))
+            .cloned()
+            .collect()
+    }
+
+    fn update_summary(&self) {
+        let self_ = self.imp();
+        let selected = self.selected_candidates();
+        let size: u64 = selected.iter().map(|c| c.size).sum();
+        let total: u64 = self_.candidates.borrow().iter().map(|c| c.size).sum();
+        self_.summary.set_label(&format!(
+            "{} of {} reclaimable selected",
+            format_size(size),
+            format_size(total)
+        ));
+        self_.delete_button.set_sensitive(!selected.is_empty());
+    }
+
+    fn delete_selected(&self) {
+        let self_ = self.imp();
+        let selected = self.selected_candidates();
+        if let Some(dm) = self_.download_manager.get() {
+            if !selected.is_empty() {
+                dm.clean_vaults(selected);
+            }
+        }
+        self.close();
+    }
+}
+
+fn format_size(size: u64) -> String {
+    format!(
+        "{:.2}",
+        byte_unit::Byte::from_u64(size).get_appropriate_unit(byte_unit::UnitType::Decimal)
+    )
+}
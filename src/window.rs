@@ -1,6 +1,7 @@
 use crate::application::EpicAssetManager;
 use crate::config::{APP_ID, PROFILE};
 use crate::ui::update::Update;
+use crate::ui::widgets::download_manager::queue::Queue;
 use crate::ui::widgets::logged_in::refresh::Refresh;
 use crate::ui::widgets::progress_icon::ProgressIconExt;
 use crate::ui::PreferencesWindow;
@@ -147,6 +148,7 @@ pub mod imp {
             if let Err(err) = self.obj().save_window_size() {
                 warn!("Failed to save window state, {}", &err);
             }
+            self.download_manager.save_queue_progress();
 
             // Signal background tasks to stop
             crate::RUNNING.store(false, std::sync::atomic::Ordering::Relaxed);